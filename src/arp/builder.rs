@@ -0,0 +1,216 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::net::Ipv4Addr;
+use hwaddr::HwAddr;
+
+use crate::error::*;
+use crate::buffer::{self, Buffer};
+use crate::builder::{Builder as Build, Finalization};
+use crate::packet::{AsPacket, AsPacketMut};
+use crate::ether;
+use crate::arp::{Hardware, Operation, Packet};
+
+/// ARP packet builder.
+///
+/// The packet defaults to Ethernet hardware addresses and IPv4 protocol
+/// addresses.
+#[derive(Debug)]
+pub struct Builder<B: Buffer = buffer::Dynamic> {
+	buffer:    B,
+	finalizer: Finalization,
+}
+
+impl<B: Buffer> Build<B> for Builder<B> {
+	fn with(mut buffer: B) -> Result<Self> {
+		use crate::size::header::Min;
+		buffer.next(Packet::<()>::min())?;
+
+		// Set the address lengths, the setters expect them to be there.
+		buffer.data_mut()[4] = 6;
+		buffer.data_mut()[5] = 4;
+
+		Packet::unchecked(buffer.data_mut())
+			.set_hardware(Hardware::Ethernet)?
+			.set_protocol(ether::Protocol::Ipv4)?
+			.set_operation(Operation::Request)?;
+
+		Ok(Builder {
+			buffer,
+			finalizer: Default::default(),
+		})
+	}
+
+	fn finalizer(&mut self) -> &mut Finalization {
+		&mut self.finalizer
+	}
+
+	fn build(self) -> Result<B::Inner> {
+		let mut buffer = self.buffer.into_inner();
+		self.finalizer.finalize(buffer.as_mut())?;
+		Ok(buffer)
+	}
+}
+
+impl Default for Builder<buffer::Dynamic> {
+	fn default() -> Self {
+		Builder::with(buffer::Dynamic::default()).unwrap()
+	}
+}
+
+impl<'a, B: Buffer> AsPacket<'a, Packet<&'a [u8]>> for Builder<B> {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.buffer.data())
+	}
+}
+
+impl<'a, B: Buffer> AsPacketMut<'a, Packet<&'a mut [u8]>> for Builder<B> {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.buffer.data_mut())
+	}
+}
+
+impl<B: Buffer> Builder<B> {
+	/// Hardware type.
+	pub fn hardware(mut self, value: Hardware) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_hardware(value)?;
+		Ok(self)
+	}
+
+	/// Protocol type.
+	pub fn protocol(mut self, value: ether::Protocol) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_protocol(value)?;
+		Ok(self)
+	}
+
+	/// Operation.
+	pub fn operation(mut self, value: Operation) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_operation(value)?;
+		Ok(self)
+	}
+
+	/// Hardware address of the sender.
+	pub fn sender_hardware_address(mut self, value: HwAddr) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_sender_hardware_address(value)?;
+		Ok(self)
+	}
+
+	/// Protocol address of the sender.
+	pub fn sender_protocol_address(mut self, value: Ipv4Addr) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_sender_protocol_address(value)?;
+		Ok(self)
+	}
+
+	/// Hardware address of the target.
+	pub fn target_hardware_address(mut self, value: HwAddr) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_target_hardware_address(value)?;
+		Ok(self)
+	}
+
+	/// Protocol address of the target.
+	pub fn target_protocol_address(mut self, value: Ipv4Addr) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_target_protocol_address(value)?;
+		Ok(self)
+	}
+
+	/// Make it a request asking for the hardware address of `target`.
+	pub fn request(self, sender: (HwAddr, Ipv4Addr), target: Ipv4Addr) -> Result<Self> {
+		self.operation(Operation::Request)?
+			.sender_hardware_address(sender.0)?
+			.sender_protocol_address(sender.1)?
+			.target_hardware_address([0; 6].into())?
+			.target_protocol_address(target)
+	}
+
+	/// Make it a reply to a request from `target`.
+	pub fn reply(self, sender: (HwAddr, Ipv4Addr), target: (HwAddr, Ipv4Addr)) -> Result<Self> {
+		self.operation(Operation::Reply)?
+			.sender_hardware_address(sender.0)?
+			.sender_protocol_address(sender.1)?
+			.target_hardware_address(target.0)?
+			.target_protocol_address(target.1)
+	}
+
+	/// Make it a gratuitous request announcing the given addresses.
+	pub fn gratuitous(self, hardware: HwAddr, protocol: Ipv4Addr) -> Result<Self> {
+		self.request((hardware, protocol), protocol)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::Ipv4Addr;
+	use crate::builder::Builder;
+	use crate::packet::Packet;
+	use crate::ether;
+	use crate::arp;
+
+	#[test]
+	fn request() {
+		let packet = ether::Builder::default()
+			.destination("ff:ff:ff:ff:ff:ff".parse().unwrap()).unwrap()
+			.source("00:23:69:63:59:be".parse().unwrap()).unwrap()
+			.arp().unwrap()
+				.request(("00:23:69:63:59:be".parse().unwrap(), Ipv4Addr::new(192, 168, 0, 1)), Ipv4Addr::new(192, 168, 0, 79)).unwrap()
+				.build().unwrap();
+
+		let ether = ether::Packet::new(packet).unwrap();
+		assert_eq!(ether.protocol(), ether::Protocol::Arp);
+
+		let arp = arp::Packet::new(ether.payload()).unwrap();
+		assert_eq!(arp.hardware(), arp::Hardware::Ethernet);
+		assert_eq!(arp.protocol(), ether::Protocol::Ipv4);
+		assert_eq!(arp.operation(), arp::Operation::Request);
+		assert_eq!(arp.sender_protocol_address(), Ipv4Addr::new(192, 168, 0, 1));
+		assert_eq!(arp.target_hardware_address(), "00:00:00:00:00:00".parse().unwrap());
+		assert_eq!(arp.target_protocol_address(), Ipv4Addr::new(192, 168, 0, 79));
+		assert_eq!(arp.to_string(), "ARP, Request who-has 192.168.0.79 tell 192.168.0.1, length 28");
+	}
+
+	#[test]
+	fn reply() {
+		let packet = arp::Builder::default()
+			.reply(("e4:b3:18:26:63:a3".parse().unwrap(), Ipv4Addr::new(192, 168, 0, 79)), ("00:23:69:63:59:be".parse().unwrap(), Ipv4Addr::new(192, 168, 0, 1))).unwrap()
+			.build().unwrap();
+
+		let arp = arp::Packet::new(packet).unwrap();
+		assert_eq!(arp.operation(), arp::Operation::Reply);
+		assert_eq!(arp.sender_hardware_address(), "e4:b3:18:26:63:a3".parse().unwrap());
+		assert_eq!(arp.target_hardware_address(), "00:23:69:63:59:be".parse().unwrap());
+		assert_eq!(arp.target_protocol_address(), Ipv4Addr::new(192, 168, 0, 1));
+		assert_eq!(arp.to_string(), "ARP, Reply 192.168.0.79 is-at E4:B3:18:26:63:A3, length 28");
+	}
+
+	#[test]
+	fn gratuitous() {
+		let packet = arp::Builder::default()
+			.gratuitous("00:23:69:63:59:be".parse().unwrap(), Ipv4Addr::new(192, 168, 0, 1)).unwrap()
+			.build().unwrap();
+
+		let arp = arp::Packet::new(packet).unwrap();
+		assert_eq!(arp.operation(), arp::Operation::Request);
+		assert!(arp.is_gratuitous());
+	}
+
+	#[test]
+	fn reverse() {
+		let packet = ether::Builder::default()
+			.arp().unwrap()
+				.operation(arp::Operation::ReverseRequest).unwrap()
+				.build().unwrap();
+
+		let ether = ether::Packet::new(packet).unwrap();
+		assert_eq!(ether.protocol(), ether::Protocol::Rarp);
+	}
+}
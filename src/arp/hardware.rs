@@ -0,0 +1,90 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+/// ARP hardware types.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Hardware {
+	/// Ethernet (10Mb).
+	Ethernet,
+
+	/// Experimental Ethernet (3Mb).
+	ExperimentalEthernet,
+
+	/// IEEE 802 networks.
+	Ieee802,
+
+	/// ARCNET.
+	Arcnet,
+
+	/// Frame Relay.
+	FrameRelay,
+
+	/// Asynchronous Transmission Mode.
+	Atm,
+
+	/// HDLC.
+	Hdlc,
+
+	/// Fibre Channel.
+	FibreChannel,
+
+	/// Serial Line.
+	SerialLine,
+
+	/// InfiniBand.
+	Infiniband,
+
+	/// Unknown hardware type.
+	Unknown(u16),
+}
+
+impl From<u16> for Hardware {
+	fn from(value: u16) -> Hardware {
+		use self::Hardware::*;
+
+		match value {
+			1  => Ethernet,
+			2  => ExperimentalEthernet,
+			6  => Ieee802,
+			7  => Arcnet,
+			15 => FrameRelay,
+			16 => Atm,
+			17 => Hdlc,
+			18 => FibreChannel,
+			20 => SerialLine,
+			32 => Infiniband,
+			n  => Unknown(n),
+		}
+	}
+}
+
+impl From<Hardware> for u16 {
+	fn from(value: Hardware) -> u16 {
+		use self::Hardware::*;
+
+		match value {
+			Ethernet             => 1,
+			ExperimentalEthernet => 2,
+			Ieee802              => 6,
+			Arcnet               => 7,
+			FrameRelay           => 15,
+			Atm                  => 16,
+			Hdlc                 => 17,
+			FibreChannel         => 18,
+			SerialLine           => 20,
+			Infiniband           => 32,
+			Unknown(n)           => n,
+		}
+	}
+}
@@ -0,0 +1,324 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::io::Cursor;
+use std::net::Ipv4Addr;
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
+use hwaddr::HwAddr;
+
+use crate::error::*;
+use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::ether;
+use crate::arp::{Hardware, Operation};
+
+/// ARP packet parser.
+///
+/// Only Ethernet hardware addresses and IPv4 protocol addresses are
+/// supported.
+#[derive(Clone)]
+pub struct Packet<B> {
+	buffer: B,
+}
+
+sized!(Packet,
+	header {
+		min:  28,
+		max:  28,
+		size: 28,
+	}
+
+	payload {
+		min:  0,
+		max:  0,
+		size: 0,
+	});
+
+impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("arp::Packet")
+			.field("hardware", &self.hardware())
+			.field("protocol", &self.protocol())
+			.field("operation", &self.operation())
+			.field("sender_hardware_address", &self.sender_hardware_address())
+			.field("sender_protocol_address", &self.sender_protocol_address())
+			.field("target_hardware_address", &self.target_hardware_address())
+			.field("target_protocol_address", &self.target_protocol_address())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.operation() {
+			Operation::Request =>
+				write!(f, "ARP, Request who-has {} tell {}",
+					self.target_protocol_address(), self.sender_protocol_address())?,
+
+			Operation::Reply =>
+				write!(f, "ARP, Reply {} is-at {}",
+					self.sender_protocol_address(), self.sender_hardware_address())?,
+
+			operation =>
+				write!(f, "ARP, {:?} {} > {}", operation,
+					self.sender_protocol_address(), self.target_protocol_address())?,
+		}
+
+		write!(f, ", length {}", self.buffer.as_ref().len())
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Create an ARP packet without checking the buffer.
+	pub fn unchecked(buffer: B) -> Packet<B> {
+		Packet { buffer }
+	}
+
+	/// Parse an ARP packet, checking the buffer contents are correct.
+	pub fn new(buffer: B) -> Result<Packet<B>> {
+		use crate::size::header::Min;
+
+		let packet = Packet::unchecked(buffer);
+
+		if packet.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		if packet.hardware_length() != 6 || packet.protocol_length() != 4 {
+			Err(Error::InvalidPacket)?
+		}
+
+		Ok(packet)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Convert the packet to its owned version.
+	///
+	/// # Notes
+	///
+	/// It would be nice if `ToOwned` could be implemented, but `Packet` already
+	/// implements `Clone` and the impl would conflict.
+	pub fn to_owned(&self) -> Packet<Vec<u8>> {
+		Packet::unchecked(self.as_ref().to_vec())
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
+	fn as_ref(&self) -> &[u8] {
+		// Anything after the addresses is link-layer padding.
+		&self.buffer.as_ref()[.. 28]
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
+	fn as_mut(&mut self) -> &mut [u8] {
+		&mut self.buffer.as_mut()[.. 28]
+	}
+}
+
+impl<'a, B: AsRef<[u8]>> AsPacket<'a, Packet<&'a [u8]>> for B {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.as_ref())
+	}
+}
+
+impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Packet<&'a mut [u8]>> for B {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.as_mut())
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Packet<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		self.buffer.as_ref()[.. 28].split_at(28)
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Packet<B> {
+	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+		self.buffer.as_mut()[.. 28].split_at_mut(28)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Hardware type.
+	pub fn hardware(&self) -> Hardware {
+		(&self.buffer.as_ref()[0 ..]).read_u16::<BigEndian>().unwrap().into()
+	}
+
+	/// Protocol type.
+	pub fn protocol(&self) -> ether::Protocol {
+		(&self.buffer.as_ref()[2 ..]).read_u16::<BigEndian>().unwrap().into()
+	}
+
+	/// Length of the hardware addresses.
+	pub fn hardware_length(&self) -> u8 {
+		self.buffer.as_ref()[4]
+	}
+
+	/// Length of the protocol addresses.
+	pub fn protocol_length(&self) -> u8 {
+		self.buffer.as_ref()[5]
+	}
+
+	/// Operation.
+	pub fn operation(&self) -> Operation {
+		(&self.buffer.as_ref()[6 ..]).read_u16::<BigEndian>().unwrap().into()
+	}
+
+	/// Hardware address of the sender.
+	pub fn sender_hardware_address(&self) -> HwAddr {
+		self.buffer.as_ref()[8 .. 14].into()
+	}
+
+	/// Protocol address of the sender.
+	pub fn sender_protocol_address(&self) -> Ipv4Addr {
+		let buffer = self.buffer.as_ref();
+		Ipv4Addr::new(buffer[14], buffer[15], buffer[16], buffer[17])
+	}
+
+	/// Hardware address of the target.
+	pub fn target_hardware_address(&self) -> HwAddr {
+		self.buffer.as_ref()[18 .. 24].into()
+	}
+
+	/// Protocol address of the target.
+	pub fn target_protocol_address(&self) -> Ipv4Addr {
+		let buffer = self.buffer.as_ref();
+		Ipv4Addr::new(buffer[24], buffer[25], buffer[26], buffer[27])
+	}
+
+	/// Check if it's a gratuitous ARP, announcing the sender's own address.
+	pub fn is_gratuitous(&self) -> bool {
+		self.sender_protocol_address() == self.target_protocol_address()
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
+	/// Hardware type.
+	pub fn set_hardware(&mut self, value: Hardware) -> Result<&mut Self> {
+		Cursor::new(&mut self.buffer.as_mut()[0 ..])
+			.write_u16::<BigEndian>(value.into())?;
+
+		Ok(self)
+	}
+
+	/// Protocol type.
+	pub fn set_protocol(&mut self, value: ether::Protocol) -> Result<&mut Self> {
+		Cursor::new(&mut self.buffer.as_mut()[2 ..])
+			.write_u16::<BigEndian>(value.into())?;
+
+		Ok(self)
+	}
+
+	/// Operation.
+	pub fn set_operation(&mut self, value: Operation) -> Result<&mut Self> {
+		Cursor::new(&mut self.buffer.as_mut()[6 ..])
+			.write_u16::<BigEndian>(value.into())?;
+
+		Ok(self)
+	}
+
+	/// Hardware address of the sender.
+	pub fn set_sender_hardware_address(&mut self, value: HwAddr) -> Result<&mut Self> {
+		self.buffer.as_mut()[8 .. 14].copy_from_slice(&value.octets());
+
+		Ok(self)
+	}
+
+	/// Protocol address of the sender.
+	pub fn set_sender_protocol_address(&mut self, value: Ipv4Addr) -> Result<&mut Self> {
+		self.buffer.as_mut()[14 .. 18].copy_from_slice(&value.octets());
+
+		Ok(self)
+	}
+
+	/// Hardware address of the target.
+	pub fn set_target_hardware_address(&mut self, value: HwAddr) -> Result<&mut Self> {
+		self.buffer.as_mut()[18 .. 24].copy_from_slice(&value.octets());
+
+		Ok(self)
+	}
+
+	/// Protocol address of the target.
+	pub fn set_target_protocol_address(&mut self, value: Ipv4Addr) -> Result<&mut Self> {
+		self.buffer.as_mut()[24 .. 28].copy_from_slice(&value.octets());
+
+		Ok(self)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::Ipv4Addr;
+	use crate::packet::Packet;
+	use crate::ether;
+	use crate::arp;
+
+	#[test]
+	fn request() {
+		let raw = [
+			// Ethernet
+			0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x23, 0x69, 0x63, 0x59, 0xbe, 0x08, 0x06,
+			// ARP
+			0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01, 0x00, 0x23, 0x69, 0x63, 0x59, 0xbe, 0xc0, 0xa8, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0xa8, 0x00, 0x4f,
+			// Padding
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+		];
+
+		let ether = ether::Packet::new(&raw[..]).unwrap();
+		assert_eq!(ether.protocol(), ether::Protocol::Arp);
+
+		let arp = arp::Packet::new(ether.payload()).unwrap();
+		assert_eq!(arp.hardware(), arp::Hardware::Ethernet);
+		assert_eq!(arp.protocol(), ether::Protocol::Ipv4);
+		assert_eq!(arp.operation(), arp::Operation::Request);
+		assert_eq!(arp.sender_hardware_address(), "00:23:69:63:59:be".parse().unwrap());
+		assert_eq!(arp.sender_protocol_address(), Ipv4Addr::new(192, 168, 0, 1));
+		assert_eq!(arp.target_hardware_address(), "00:00:00:00:00:00".parse().unwrap());
+		assert_eq!(arp.target_protocol_address(), Ipv4Addr::new(192, 168, 0, 79));
+		assert!(!arp.is_gratuitous());
+		assert_eq!(arp.as_ref().len(), 28);
+		assert!(arp.payload().is_empty());
+
+		assert_eq!(arp.to_string(), "ARP, Request who-has 192.168.0.79 tell 192.168.0.1, length 46");
+	}
+
+	#[test]
+	fn unsupported() {
+		let raw = [
+			0x00, 0x01, 0x08, 0x00, 0x08, 0x04, 0x00, 0x01, 0x00, 0x23, 0x69, 0x63, 0x59, 0xbe, 0xc0, 0xa8, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0xa8, 0x00, 0x4f,
+		];
+
+		assert!(arp::Packet::new(&raw[..]).is_err());
+		assert!(arp::Packet::new(&raw[.. 20]).is_err());
+	}
+
+	#[test]
+	fn mutable() {
+		let mut raw = [
+			0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01, 0x00, 0x23, 0x69, 0x63, 0x59, 0xbe, 0xc0, 0xa8, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0xa8, 0x00, 0x4f,
+		];
+
+		let mut arp = arp::Packet::new(&mut raw[..]).unwrap();
+		arp.set_operation(arp::Operation::Reply).unwrap()
+			.set_target_hardware_address("e4:b3:18:26:63:a3".parse().unwrap()).unwrap()
+			.set_target_protocol_address(Ipv4Addr::new(10, 0, 0, 1)).unwrap();
+
+		assert_eq!(arp.operation(), arp::Operation::Reply);
+		assert_eq!(arp.target_hardware_address(), "e4:b3:18:26:63:a3".parse().unwrap());
+		assert_eq!(arp.target_protocol_address(), Ipv4Addr::new(10, 0, 0, 1));
+	}
+}
@@ -33,14 +33,9 @@ impl<F: FnOnce(&mut [u8]) -> Result<()>> Finalizer for F {
 }
 
 /// Takes care of grouping finalizers through the builder chain.
+#[derive(Default)]
 pub struct Finalization(Vec<Box<dyn Finalizer>>);
 
-impl Default for Finalization {
-	fn default() -> Self {
-		Finalization(Vec::new())
-	}
-}
-
 impl fmt::Debug for Finalization {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("builder::Finalization")
@@ -57,7 +52,7 @@ impl Finalization {
 
 	/// Add a serie of finalizers.
 	pub fn extend<I: IntoIterator<Item = Box<dyn Finalizer>>>(&mut self, finalizers: I) {
-		self.0.extend(finalizers.into_iter());
+		self.0.extend(finalizers);
 	}
 
 	/// Finalize a buffer.
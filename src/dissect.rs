@@ -0,0 +1,646 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::slice;
+use std::net::IpAddr;
+
+use crate::error::*;
+use crate::packet::Packet as P;
+use crate::{ether, arp, ip, icmp, icmpv6, tcp, udp};
+pub use crate::pcap::LinkType;
+
+/// Most IP packets dissected within one another, anything deeper is kept as
+/// payload.
+const DEPTH: usize = 8;
+
+/// A dissected layer.
+#[derive(Debug)]
+pub enum Layer<'a> {
+	/// Ethernet frame.
+	Ether(ether::Packet<&'a [u8]>),
+
+	/// IEEE 802.1Q tag.
+	Vlan(ether::vlan::Packet<&'a [u8]>),
+
+	/// ARP packet.
+	Arp(arp::Packet<&'a [u8]>),
+
+	/// IPv4 or IPv6 packet.
+	Ip(ip::Packet<&'a [u8]>),
+
+	/// ICMP packet.
+	Icmp(icmp::Packet<&'a [u8]>),
+
+	/// ICMPv6 packet.
+	Icmpv6(icmpv6::Packet<&'a [u8]>),
+
+	/// TCP packet.
+	Tcp(tcp::Packet<&'a [u8]>),
+
+	/// UDP packet.
+	Udp(udp::Packet<&'a [u8]>),
+
+	/// Data that was not dissected further.
+	Payload(&'a [u8]),
+}
+
+/// Stack of dissected layers, outermost first, with their byte offsets.
+#[derive(Debug)]
+pub struct Stack<'a> {
+	layers:  Vec<Layer<'a>>,
+	offsets: Vec<usize>,
+	error:   Option<Error>,
+	next:    usize,
+}
+
+/// Dissect the data into its layers, starting from the given link-layer
+/// header type.
+///
+/// Dissection stops at the first unknown or broken layer, the rest of the
+/// data is then kept as `Layer::Payload` and the reason is available through
+/// `Stack::error`. IP packets cut short by the capture snapshot length are
+/// kept, along with whatever of their payload was captured, and IP packets
+/// nested more than 8 deep are kept as payload.
+pub fn dissect(link_type: LinkType, buffer: &[u8]) -> Stack<'_> {
+	let mut stack = Stack {
+		layers:  Vec::new(),
+		offsets: Vec::new(),
+		error:   None,
+		next:    0,
+	};
+
+	let result = match link_type {
+		LinkType::Ethernet =>
+			stack.dissect_ether(buffer, 0),
+
+		LinkType::Raw | LinkType::Ipv4 | LinkType::Ipv6 =>
+			stack.dissect_ip(buffer, 0, 0),
+
+		LinkType::Null | LinkType::Loop if buffer.len() >= 4 =>
+			stack.dissect_ip(&buffer[4 ..], 4, 0),
+
+		LinkType::LinuxSll if buffer.len() >= 16 => {
+			let protocol = u16::from_be_bytes([buffer[14], buffer[15]]);
+			stack.dissect_network(protocol.into(), &buffer[16 ..], 16)
+		}
+
+		_ =>
+			stack.dissect_payload(buffer, 0)
+	};
+
+	if let Err(error) = result {
+		let next = stack.next;
+
+		stack.error = Some(error);
+		stack.dissect_payload(&buffer[next ..], next).unwrap();
+	}
+
+	stack
+}
+
+impl<'a> Stack<'a> {
+	/// Number of layers.
+	pub fn len(&self) -> usize {
+		self.layers.len()
+	}
+
+	/// Check if no layer was dissected.
+	pub fn is_empty(&self) -> bool {
+		self.layers.is_empty()
+	}
+
+	/// The dissected layers, outermost first.
+	pub fn layers(&self) -> &[Layer<'a>] {
+		&self.layers
+	}
+
+	/// Byte offset of every layer within the data.
+	pub fn offsets(&self) -> &[usize] {
+		&self.offsets
+	}
+
+	/// Get a layer along with its byte offset.
+	pub fn get(&self, index: usize) -> Option<(usize, &Layer<'a>)> {
+		self.layers.get(index).map(|l| (self.offsets[index], l))
+	}
+
+	/// Iterate over the layers along with their byte offsets.
+	pub fn iter(&self) -> StackIter<'_, 'a> {
+		StackIter {
+			layers:  self.layers.iter(),
+			offsets: self.offsets.iter(),
+		}
+	}
+
+	/// The error that stopped the dissection, if any.
+	pub fn error(&self) -> Option<&Error> {
+		self.error.as_ref()
+	}
+
+	/// Outermost Ethernet frame.
+	pub fn ether(&self) -> Option<&ether::Packet<&'a [u8]>> {
+		self.layers.iter().find_map(|l| if let Layer::Ether(p) = l { Some(p) } else { None })
+	}
+
+	/// Outermost ARP packet.
+	pub fn arp(&self) -> Option<&arp::Packet<&'a [u8]>> {
+		self.layers.iter().find_map(|l| if let Layer::Arp(p) = l { Some(p) } else { None })
+	}
+
+	/// Outermost IP packet.
+	pub fn ip(&self) -> Option<&ip::Packet<&'a [u8]>> {
+		self.layers.iter().find_map(|l| if let Layer::Ip(p) = l { Some(p) } else { None })
+	}
+
+	/// Outermost ICMP packet.
+	pub fn icmp(&self) -> Option<&icmp::Packet<&'a [u8]>> {
+		self.layers.iter().find_map(|l| if let Layer::Icmp(p) = l { Some(p) } else { None })
+	}
+
+	/// Outermost ICMPv6 packet.
+	pub fn icmpv6(&self) -> Option<&icmpv6::Packet<&'a [u8]>> {
+		self.layers.iter().find_map(|l| if let Layer::Icmpv6(p) = l { Some(p) } else { None })
+	}
+
+	/// Outermost TCP packet.
+	pub fn tcp(&self) -> Option<&tcp::Packet<&'a [u8]>> {
+		self.layers.iter().find_map(|l| if let Layer::Tcp(p) = l { Some(p) } else { None })
+	}
+
+	/// Outermost UDP packet.
+	pub fn udp(&self) -> Option<&udp::Packet<&'a [u8]>> {
+		self.layers.iter().find_map(|l| if let Layer::Udp(p) = l { Some(p) } else { None })
+	}
+
+	/// Data following the last dissected layer.
+	pub fn payload(&self) -> Option<&'a [u8]> {
+		match self.layers.last() {
+			Some(&Layer::Payload(payload)) => Some(payload),
+			_                              => None,
+		}
+	}
+
+	fn push(&mut self, offset: usize, layer: Layer<'a>) {
+		self.offsets.push(offset);
+		self.layers.push(layer);
+	}
+
+	fn dissect_payload(&mut self, buffer: &'a [u8], offset: usize) -> Result<()> {
+		self.next = offset + buffer.len();
+
+		if !buffer.is_empty() {
+			self.push(offset, Layer::Payload(buffer));
+		}
+
+		Ok(())
+	}
+
+	fn dissect_ether(&mut self, buffer: &'a [u8], offset: usize) -> Result<()> {
+		self.next = offset;
+
+		let packet       = ether::Packet::new(buffer)?;
+		let mut protocol = packet.protocol();
+		let mut header   = 14;
+
+		self.push(offset, Layer::Ether(packet));
+
+		while protocol.is_tag() {
+			self.next = offset + header;
+
+			let tag  = ether::vlan::Packet::new(&buffer[header ..])?;
+			protocol = tag.protocol();
+
+			self.push(offset + header, Layer::Vlan(tag));
+			header += 4;
+		}
+
+		self.dissect_network(protocol, &buffer[header ..], offset + header)
+	}
+
+	fn dissect_network(&mut self, protocol: ether::Protocol, buffer: &'a [u8], offset: usize) -> Result<()> {
+		self.next = offset;
+
+		match protocol {
+			ether::Protocol::Ipv4 | ether::Protocol::Ipv6 =>
+				self.dissect_ip(buffer, offset, 0),
+
+			ether::Protocol::Arp | ether::Protocol::Rarp => {
+				self.push(offset, Layer::Arp(arp::Packet::new(buffer)?));
+				Ok(())
+			}
+
+			_ =>
+				self.dissect_payload(buffer, offset)
+		}
+	}
+
+	fn dissect_ip(&mut self, buffer: &'a [u8], offset: usize, depth: usize) -> Result<()> {
+		self.next = offset;
+
+		if buffer.is_empty() {
+			Err(Error::SmallBuffer)?
+		}
+
+		// Captures are often cut at the snapshot length, so only the headers
+		// have to be there and the payload is whatever was captured.
+		let packet = ip::Packet::no_payload(buffer)?;
+
+		let (header, end, truncated) = match packet {
+			ip::Packet::V4(ref packet) => {
+				let (header, payload) = packet.split();
+				(header.len(), header.len() + payload.len(), buffer.len() < packet.length() as usize)
+			}
+
+			ip::Packet::V6(ref packet) => {
+				let (header, payload) = packet.split();
+				(header.len(), header.len() + payload.len(), buffer.len() < header.len() + packet.length() as usize)
+			}
+		};
+
+		let upper = match packet {
+			ip::Packet::V4(ref packet) =>
+				Ok((packet.protocol(), header, packet.offset() != 0)),
+
+			ip::Packet::V6(ref packet) => {
+				let fragment = packet.extensions().any(|e|
+					matches!(e, Ok(ip::v6::Extension::Fragment(ref f)) if f.offset() != 0));
+
+				packet.upper_layer().map(|(protocol, upper)| (protocol, upper, fragment))
+			}
+		};
+
+		// A broken extension header chain still leaves a valid IPv6 header.
+		self.push(offset, Layer::Ip(packet));
+		self.next = offset + header;
+
+		let (protocol, header, fragment) = upper?;
+
+		let buffer = &buffer[header.min(end) .. end];
+		let offset = offset + header;
+		self.next  = offset;
+
+		if fragment {
+			return self.dissect_payload(buffer, offset);
+		}
+
+		match protocol {
+			ip::Protocol::Ipv4 | ip::Protocol::Ipv6 if depth + 1 < DEPTH =>
+				self.dissect_ip(buffer, offset, depth + 1),
+
+			ip::Protocol::Icmp => {
+				self.push(offset, Layer::Icmp(icmp::Packet::new(buffer)?));
+				Ok(())
+			}
+
+			ip::Protocol::Ipv6Icmp => {
+				self.push(offset, Layer::Icmpv6(icmpv6::Packet::new(buffer)?));
+				Ok(())
+			}
+
+			ip::Protocol::Tcp => {
+				let packet = tcp::Packet::new(buffer)?;
+				let header = packet.split().0.len();
+
+				self.push(offset, Layer::Tcp(packet));
+				self.dissect_payload(&buffer[header ..], offset + header)
+			}
+
+			ip::Protocol::Udp => {
+				let packet = if truncated {
+					udp::Packet::no_payload(buffer)?
+				}
+				else {
+					udp::Packet::new(buffer)?
+				};
+
+				let (header, payload) = packet.split();
+				let (header, length)  = (header.len(), payload.len());
+
+				self.push(offset, Layer::Udp(packet));
+				self.dissect_payload(&buffer[header .. header + length], offset + header)
+			}
+
+			_ =>
+				self.dissect_payload(buffer, offset)
+		}
+	}
+}
+
+impl<'a> fmt::Display for Stack<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut layers = self.layers.iter().peekable();
+
+		while let Some(layer) = layers.next() {
+			let separator = match *layer {
+				Layer::Ether(ref packet) => {
+					write!(f, "{}", packet)?;
+					": "
+				}
+
+				Layer::Vlan(ref packet) => {
+					write!(f, "{}", packet)?;
+					", "
+				}
+
+				Layer::Ip(ref packet) => {
+					let (name, source, destination, protocol, length): (_, IpAddr, IpAddr, _, _) = match *packet {
+						ip::Packet::V4(ref packet) =>
+							("IP", packet.source().into(), packet.destination().into(),
+								packet.protocol(), packet.payload().len()),
+
+						ip::Packet::V6(ref packet) =>
+							("IP6", packet.source().into(), packet.destination().into(),
+								packet.upper_layer().map(|(p, _)| p).unwrap_or(packet.next_header()),
+								packet.payload().len()),
+					};
+
+					match layers.peek() {
+						Some(Layer::Tcp(packet)) => {
+							write!(f, "{} {}.{} > {}.{}: ", name,
+								source, packet.source(), destination, packet.destination())?;
+							packet.summary(f)?;
+							layers.next();
+						}
+
+						Some(Layer::Udp(packet)) => {
+							write!(f, "{} {}.{} > {}.{}: ", name,
+								source, packet.source(), destination, packet.destination())?;
+							packet.summary(f)?;
+							layers.next();
+						}
+
+						Some(&&Layer::Ip(_)) | Some(&&Layer::Icmp(_)) | Some(&&Layer::Icmpv6(_)) =>
+							write!(f, "{} {} > {}", name, source, destination)?,
+
+						_ =>
+							write!(f, "{} {} > {}: {:?}, length {}", name, source, destination,
+								protocol, length)?,
+					}
+
+					": "
+				}
+
+				Layer::Arp(ref packet) => {
+					write!(f, "{}", packet)?;
+					""
+				}
+
+				Layer::Icmp(ref packet) => {
+					write!(f, "{}", packet)?;
+					""
+				}
+
+				Layer::Icmpv6(ref packet) => {
+					write!(f, "{}", packet)?;
+					""
+				}
+
+				Layer::Tcp(ref packet) => {
+					write!(f, "{}", packet)?;
+					""
+				}
+
+				Layer::Udp(ref packet) => {
+					write!(f, "{}", packet)?;
+					""
+				}
+
+				Layer::Payload(payload) => {
+					if self.layers.len() == 1 {
+						write!(f, "length {}", payload.len())?;
+					}
+
+					""
+				}
+			};
+
+			match layers.peek() {
+				None | Some(&&Layer::Payload(_)) => (),
+				Some(_)                          => f.write_str(separator)?,
+			}
+		}
+
+		if self.error.is_some() {
+			f.write_str(" [|truncated]")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Iterator over the dissected layers and their byte offsets.
+pub struct StackIter<'s, 'a> {
+	layers:  slice::Iter<'s, Layer<'a>>,
+	offsets: slice::Iter<'s, usize>,
+}
+
+impl<'s, 'a> Iterator for StackIter<'s, 'a> {
+	type Item = (usize, &'s Layer<'a>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		Some((*self.offsets.next()?, self.layers.next()?))
+	}
+}
+
+impl<'s, 'a> IntoIterator for &'s Stack<'a> {
+	type Item     = (usize, &'s Layer<'a>);
+	type IntoIter = StackIter<'s, 'a>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::builder::Builder;
+	use crate::dissect::{dissect, Layer, LinkType};
+	use crate::{ether, ip, tcp};
+
+	#[test]
+	fn tcp() {
+		let packet = ether::Builder::default()
+			.vlan(42).unwrap()
+			.ip().unwrap().v4().unwrap()
+				.source("10.0.0.1".parse().unwrap()).unwrap()
+				.destination("10.0.0.2".parse().unwrap()).unwrap()
+				.tcp().unwrap()
+					.source(1337).unwrap()
+					.destination(80).unwrap()
+					.flags(tcp::flag::SYN).unwrap()
+					.payload(b"hello").unwrap()
+					.build().unwrap();
+
+		let stack = dissect(LinkType::Ethernet, &packet);
+		assert!(stack.error().is_none());
+		assert_eq!(stack.offsets(), &[0, 14, 18, 38, 58]);
+
+		match stack.layers() {
+			[Layer::Ether(_), Layer::Vlan(tag), Layer::Ip(ip::Packet::V4(_)), Layer::Tcp(tcp), Layer::Payload(payload)] => {
+				assert_eq!(tag.vid(), 42);
+				assert_eq!(tcp.destination(), 80);
+				assert_eq!(payload, b"hello");
+			}
+
+			_ => unreachable!()
+		}
+
+		assert_eq!(stack.tcp().unwrap().source(), 1337);
+		assert_eq!(stack.payload(), Some(&b"hello"[..]));
+	}
+
+	#[test]
+	fn truncated() {
+		let mut packet = ip::v4::Builder::default()
+			.source("10.0.0.1".parse().unwrap()).unwrap()
+			.destination("10.0.0.2".parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(53).unwrap()
+				.destination(53).unwrap()
+				.payload(b"query").unwrap()
+				.build().unwrap();
+
+		let stack = dissect(LinkType::Raw, &packet);
+		assert!(stack.error().is_none());
+		assert_eq!(stack.offsets(), &[0, 20, 28]);
+		assert_eq!(stack.udp().unwrap().destination(), 53);
+
+		packet[24] = 0xff;
+
+		let stack = dissect(LinkType::Raw, &packet);
+		assert!(stack.error().is_some());
+		assert_eq!(stack.offsets(), &[0, 20]);
+		assert_eq!(stack.payload(), Some(&packet[20 ..]));
+
+		let stack = dissect(LinkType::Raw, &packet[.. 10]);
+		assert!(stack.error().is_some());
+		assert_eq!(stack.offsets(), &[0]);
+		assert_eq!(stack.payload(), Some(&packet[.. 10]));
+
+		let stack = dissect(LinkType::Unknown(1234), &packet);
+		assert!(stack.error().is_none());
+		assert_eq!(stack.payload(), Some(&packet[..]));
+	}
+
+	#[test]
+	fn snapshot() {
+		let packet = ether::Builder::default()
+			.ip().unwrap().v4().unwrap()
+				.source("10.0.0.1".parse().unwrap()).unwrap()
+				.destination("10.0.0.2".parse().unwrap()).unwrap()
+				.tcp().unwrap()
+					.source(1337).unwrap()
+					.destination(80).unwrap()
+					.flags(tcp::flag::ACK).unwrap()
+					.payload(b"hello").unwrap()
+					.build().unwrap();
+
+		let stack = dissect(LinkType::Ethernet, &packet[.. 56]);
+		assert!(stack.error().is_none());
+		assert_eq!(stack.offsets(), &[0, 14, 34, 54]);
+		assert_eq!(stack.ip().unwrap().destination(), "10.0.0.2".parse::<std::net::IpAddr>().unwrap());
+		assert_eq!(stack.tcp().unwrap().destination(), 80);
+		assert_eq!(stack.payload(), Some(&b"he"[..]));
+
+		let stack = dissect(LinkType::Ethernet, &packet[.. 40]);
+		assert!(stack.error().is_some());
+		assert_eq!(stack.offsets(), &[0, 14, 34]);
+		assert!(stack.ip().is_some());
+		assert_eq!(stack.payload(), Some(&packet[34 .. 40]));
+	}
+
+	#[test]
+	fn extensions() {
+		let packet = ip::v6::Builder::default()
+			.source("::1".parse().unwrap()).unwrap()
+			.destination("::2".parse().unwrap()).unwrap()
+			.next_header(ip::Protocol::Hopopt).unwrap()
+			.payload(&[0x11, 0x05, 0x00, 0x00]).unwrap()
+			.build().unwrap();
+
+		let stack = dissect(LinkType::Raw, &packet);
+		assert!(stack.error().is_some());
+		assert_eq!(stack.offsets(), &[0, 40]);
+		assert!(matches!(stack.layers()[0], Layer::Ip(ip::Packet::V6(_))));
+		assert_eq!(stack.payload(), Some(&packet[40 ..]));
+	}
+
+	#[test]
+	fn display() {
+		let raw = [
+			0x45u8, 0x00, 0x00, 0x30, 0x00, 0x01, 0x40, 0x00, 0x40, 0x06, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+			0x05, 0x39, 0x00, 0x50, 0x00, 0x00, 0x00, 0x7b, 0x00, 0x00, 0x00, 0x00, 0x70, 0x02, 0x72, 0x10, 0x00, 0x00, 0x00, 0x00,
+			0x02, 0x04, 0x05, 0xb4, 0x03, 0x03, 0x07, 0x00,
+		];
+
+		assert_eq!(ip::v4::Packet::new(&raw[..]).unwrap().to_string(),
+			"IP 10.0.0.1.1337 > 10.0.0.2.80: Flags [S], seq 123, win 29200, options [mss 1460,wscale 7], length 0");
+
+		assert_eq!(tcp::Packet::new(&raw[20 ..]).unwrap().to_string(),
+			"1337 > 80: Flags [S], seq 123, win 29200, options [mss 1460,wscale 7], length 0");
+
+		let packet = ether::Builder::default()
+			.source("00:00:00:00:00:01".parse().unwrap()).unwrap()
+			.destination("00:00:00:00:00:02".parse().unwrap()).unwrap()
+			.vlan(42).unwrap()
+			.ip().unwrap().v6().unwrap()
+				.source("::1".parse().unwrap()).unwrap()
+				.destination("::2".parse().unwrap()).unwrap()
+				.udp().unwrap()
+					.source(1234).unwrap()
+					.destination(53).unwrap()
+					.payload(b"query").unwrap()
+					.build().unwrap();
+
+		assert_eq!(dissect(LinkType::Ethernet, &packet).to_string(),
+			"00:00:00:00:00:01 > 00:00:00:00:00:02, ethertype Vlan (0x8100), length 71: \
+			 vlan 42, p 0, ethertype Ipv6 (0x86dd), IP6 ::1.1234 > ::2.53: UDP, length 5");
+
+		assert_eq!(dissect(LinkType::Ethernet, &packet[.. 60]).to_string(),
+			"00:00:00:00:00:01 > 00:00:00:00:00:02, ethertype Vlan (0x8100), length 60: \
+			 vlan 42, p 0, ethertype Ipv6 (0x86dd), IP6 ::1 > ::2: Udp, length 2 [|truncated]");
+
+		let packet = ip::v4::Builder::default()
+			.source("10.0.0.1".parse().unwrap()).unwrap()
+			.destination("10.0.0.2".parse().unwrap()).unwrap()
+			.icmp().unwrap().echo().unwrap().request().unwrap()
+				.identifier(42).unwrap()
+				.sequence(2).unwrap()
+				.build().unwrap();
+
+		assert_eq!(dissect(LinkType::Raw, &packet).to_string(),
+			"IP 10.0.0.1 > 10.0.0.2: ICMP echo request, id 42, seq 2, length 8");
+	}
+
+	#[test]
+	fn nested() {
+		let mut packet = Vec::new();
+
+		for _ in 0 .. 10 {
+			packet = ip::v4::Builder::default()
+				.source("10.0.0.1".parse().unwrap()).unwrap()
+				.destination("10.0.0.2".parse().unwrap()).unwrap()
+				.protocol(ip::Protocol::Ipv4).unwrap()
+				.payload(&packet).unwrap()
+				.build().unwrap();
+		}
+
+		let stack = dissect(LinkType::Raw, &packet);
+		assert!(stack.error().is_none());
+		assert_eq!(stack.len(), 9);
+		assert_eq!(stack.offsets()[8], 8 * 20);
+		assert_eq!(stack.payload(), Some(&packet[8 * 20 ..]));
+	}
+}
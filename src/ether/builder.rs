@@ -13,15 +13,14 @@
 //  0. You just DO WHAT THE FUCK YOU WANT TO.
 
 use std::io::Cursor;
-use byteorder::{WriteBytesExt, BigEndian};
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
 use hwaddr::HwAddr;
 
 use crate::error::*;
 use crate::buffer::{self, Buffer};
 use crate::builder::{Builder as Build, Finalization};
 use crate::packet::{AsPacket, AsPacketMut};
-use crate::ether::Packet;
-use crate::ether::Protocol;
+use crate::ether::{Packet, Protocol, vlan};
 
 /// Ethernet frame builder.
 #[derive(Debug)]
@@ -29,7 +28,9 @@ pub struct Builder<B: Buffer = buffer::Dynamic> {
 	buffer:    B,
 	finalizer: Finalization,
 
-	payload: bool,
+	protocol: usize,
+	tag:      Option<usize>,
+	payload:  bool,
 }
 
 impl<B: Buffer> Build<B> for Builder<B> {
@@ -41,7 +42,9 @@ impl<B: Buffer> Build<B> for Builder<B> {
 			buffer:    buffer,
 			finalizer: Default::default(),
 
-			payload: false,
+			protocol: 12,
+			tag:      None,
+			payload:  false,
 		})
 	}
 
@@ -89,9 +92,61 @@ impl<B: Buffer> Builder<B> {
 		Ok(self)
 	}
 
-	/// Protocol of the inner packet.
+	/// Protocol of the inner packet, when VLAN tags are present this is set on
+	/// the innermost one.
 	pub fn protocol(mut self, value: Protocol) -> Result<Self> {
-		Packet::unchecked(self.buffer.data_mut()).set_protocol(value)?;
+		let offset = self.protocol;
+		Cursor::new(&mut self.buffer.data_mut()[offset ..])
+			.write_u16::<BigEndian>(value.into())?;
+
+		Ok(self)
+	}
+
+	/// Push a VLAN tag with the given tag protocol identifier, tags are pushed
+	/// from the outermost to the innermost.
+	pub fn tag(mut self, protocol: Protocol, vid: u16) -> Result<Self> {
+		if self.payload {
+			Err(Error::AlreadyDefined)?
+		}
+
+		if !protocol.is_tag() {
+			Err(Error::InvalidValue)?
+		}
+
+		self = self.protocol(protocol)?;
+
+		let offset = self.buffer.length();
+		self.buffer.more(4)?;
+		vlan::Packet::unchecked(&mut self.buffer.data_mut()[offset ..]).set_vid(vid)?;
+
+		self.tag      = Some(offset);
+		self.protocol = offset + 2;
+
+		Ok(self)
+	}
+
+	/// Push an IEEE 802.1Q customer VLAN tag.
+	pub fn vlan(self, vid: u16) -> Result<Self> {
+		self.tag(Protocol::Vlan, vid)
+	}
+
+	/// Push an IEEE 802.1ad service VLAN tag.
+	pub fn service_vlan(self, vid: u16) -> Result<Self> {
+		self.tag(Protocol::PBridge, vid)
+	}
+
+	/// Priority code point of the last pushed tag.
+	pub fn pcp(mut self, value: u8) -> Result<Self> {
+		let offset = self.tag.ok_or(Error::InvalidPacket)?;
+		vlan::Packet::unchecked(&mut self.buffer.data_mut()[offset ..]).set_pcp(value)?;
+
+		Ok(self)
+	}
+
+	/// Drop eligible indicator of the last pushed tag.
+	pub fn dei(mut self, value: bool) -> Result<Self> {
+		let offset = self.tag.ok_or(Error::InvalidPacket)?;
+		vlan::Packet::unchecked(&mut self.buffer.data_mut()[offset ..]).set_dei(value)?;
 
 		Ok(self)
 	}
@@ -118,17 +173,18 @@ impl<B: Buffer> Builder<B> {
 			Err(Error::AlreadyDefined)?
 		}
 
-		let offset = self.buffer.offset();
-		let length = self.buffer.length();
+		let offset   = self.buffer.offset();
+		let length   = self.buffer.length();
+		let position = self.protocol;
 
 		self.finalizer.add(move |out| {
 			match out[offset + length] >> 4 {
 				4 =>
-					Cursor::new(&mut out[offset + 12 ..])
+					Cursor::new(&mut out[offset + position ..])
 						.write_u16::<BigEndian>(Protocol::Ipv4.into())?,
 
 				6 =>
-					Cursor::new(&mut out[offset + 12 ..])
+					Cursor::new(&mut out[offset + position ..])
 						.write_u16::<BigEndian>(Protocol::Ipv6.into())?,
 
 				_ =>
@@ -143,6 +199,37 @@ impl<B: Buffer> Builder<B> {
 
 		Ok(ip)
 	}
+
+	/// Build an ARP packet inside the Ethernet frame.
+	pub fn arp(mut self) -> Result<crate::arp::Builder<B>> {
+		if self.payload {
+			Err(Error::AlreadyDefined)?
+		}
+
+		let offset   = self.buffer.offset();
+		let length   = self.buffer.length();
+		let position = self.protocol;
+
+		self.finalizer.add(move |out| {
+			let operation = crate::arp::Operation::from((&out[offset + length + 6 ..]).read_u16::<BigEndian>()?);
+			let protocol  = if operation.is_reverse() {
+				Protocol::Rarp
+			}
+			else {
+				Protocol::Arp
+			};
+
+			Cursor::new(&mut out[offset + position ..])
+				.write_u16::<BigEndian>(protocol.into())?;
+
+			Ok(())
+		});
+
+		let mut arp = crate::arp::Builder::with(self.buffer)?;
+		arp.finalizer().extend(self.finalizer);
+
+		Ok(arp)
+	}
 }
 
 #[cfg(test)]
@@ -189,4 +276,50 @@ mod test {
 		assert_eq!(udp.destination(), 9001);
 		assert!(udp.is_valid(&ip::Packet::from(&ip)));
 	}
+
+	#[test]
+	fn tags() {
+		let packet = ether::Builder::default()
+			.destination("00:23:69:63:59:be".parse().unwrap()).unwrap()
+			.source("e4:b3:18:26:63:a3".parse().unwrap()).unwrap()
+			.service_vlan(100).unwrap()
+			.vlan(200).unwrap()
+				.pcp(5).unwrap()
+				.dei(true).unwrap()
+			.ip().unwrap().v4().unwrap()
+				.source("192.168.0.1".parse().unwrap()).unwrap()
+				.destination("192.168.0.2".parse().unwrap()).unwrap()
+				.udp().unwrap()
+					.source(1337).unwrap()
+					.destination(53).unwrap()
+					.build().unwrap();
+
+		let ether = ether::Packet::new(packet).unwrap();
+		assert_eq!(ether.protocol(), ether::Protocol::PBridge);
+
+		let tags = ether.tags().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(tags.len(), 2);
+		assert_eq!(tags[0].vid(), 100);
+		assert_eq!(tags[0].pcp(), 0);
+		assert_eq!(tags[0].protocol(), ether::Protocol::Vlan);
+		assert_eq!(tags[1].vid(), 200);
+		assert_eq!(tags[1].pcp(), 5);
+		assert!(tags[1].dei());
+
+		let (protocol, payload) = ether.inner().unwrap();
+		assert_eq!(protocol, ether::Protocol::Ipv4);
+
+		let ip = ip::v4::Packet::new(payload).unwrap();
+		assert!(ip.is_valid());
+
+		let udp = udp::Packet::new(ip.payload()).unwrap();
+		assert!(udp.is_valid(&ip::Packet::from(&ip)));
+	}
+
+	#[test]
+	fn invalid_tags() {
+		assert!(ether::Builder::default().pcp(1).is_err());
+		assert!(ether::Builder::default().tag(ether::Protocol::Ipv4, 1).is_err());
+		assert!(ether::Builder::default().vlan(4096).is_err());
+	}
 }
@@ -16,7 +16,10 @@ mod protocol;
 pub use self::protocol::Protocol;
 
 mod packet;
-pub use self::packet::Packet;
+pub use self::packet::{Packet, TagIter};
+
+/// IEEE 802.1Q and 802.1ad VLAN tags.
+pub mod vlan;
 
 mod builder;
 pub use self::builder::Builder;
@@ -19,7 +19,7 @@ use hwaddr::HwAddr;
 
 use crate::error::*;
 use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
-use crate::ether::Protocol;
+use crate::ether::{Protocol, vlan};
 
 /// Ethernet frame parser.
 pub struct Packet<B> {
@@ -42,14 +42,24 @@ sized!(Packet,
 impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("ether::Packet")
-			.field("destination", &self.source())
-			.field("source", &self.destination())
+			.field("destination", &self.destination())
+			.field("source", &self.source())
 			.field("protocol", &self.protocol())
 			.field("payload", &self.payload())
 			.finish()
 	}
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let protocol: u16 = self.protocol().into();
+
+		write!(f, "{} > {}, ethertype {:?} (0x{:04x}), length {}",
+			self.source(), self.destination(), self.protocol(), protocol,
+			self.buffer.as_ref().len())
+	}
+}
+
 impl<B: AsRef<[u8]>> Packet<B> {
 	/// Create an Ethernet frame without checking the buffer.
 	pub fn unchecked(buffer: B) -> Packet<B> {
@@ -138,6 +148,25 @@ impl<B: AsRef<[u8]>> Packet<B> {
 	pub fn protocol(&self) -> Protocol {
 		(&self.buffer.as_ref()[12 ..]).read_u16::<BigEndian>().unwrap().into()
 	}
+
+	/// VLAN tags following the header, outermost first.
+	pub fn tags(&self) -> TagIter<'_> {
+		TagIter {
+			buffer:   &self.buffer.as_ref()[14 ..],
+			protocol: self.protocol(),
+		}
+	}
+
+	/// Protocol and payload of the inner packet, after any VLAN tag.
+	pub fn inner(&self) -> Result<(Protocol, &[u8])> {
+		let mut tags = self.tags();
+
+		for tag in &mut tags {
+			tag?;
+		}
+
+		Ok((tags.protocol(), tags.payload()))
+	}
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
@@ -164,6 +193,48 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
 	}
 }
 
+/// Iterator over VLAN tags.
+pub struct TagIter<'a> {
+	buffer:   &'a [u8],
+	protocol: Protocol,
+}
+
+impl<'a> TagIter<'a> {
+	/// Protocol following the tags walked so far.
+	pub fn protocol(&self) -> Protocol {
+		self.protocol
+	}
+
+	/// Payload following the tags walked so far.
+	pub fn payload(&self) -> &'a [u8] {
+		self.buffer
+	}
+}
+
+impl<'a> Iterator for TagIter<'a> {
+	type Item = Result<vlan::Packet<&'a [u8]>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if !self.protocol.is_tag() {
+			return None;
+		}
+
+		match vlan::Packet::new(self.buffer) {
+			Ok(tag) => {
+				self.protocol = tag.protocol();
+				self.buffer   = &self.buffer[4 ..];
+
+				Some(Ok(tag))
+			}
+
+			Err(error) => {
+				self.protocol = Protocol::Unknown(0);
+				Some(Err(error))
+			}
+		}
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use crate::packet::Packet;
@@ -186,4 +257,54 @@ mod test {
 		assert_eq!(ether.source(), "e4:b3:18:26:63:a3".parse().unwrap());
 		assert_eq!(ether.protocol(), ether::Protocol::Ipv4);
 	}
+
+	#[test]
+	fn tags() {
+		let raw = [
+			// Ethernet
+			0x00u8, 0x23, 0x69, 0x63, 0x59, 0xbe, 0xe4, 0xb3, 0x18, 0x26, 0x63, 0xa3, 0x88, 0xa8,
+			// 802.1ad
+			0x00, 0x64, 0x81, 0x00,
+			// 802.1Q
+			0xa0, 0xc8, 0x08, 0x00,
+			// IPv4
+			0x45, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0xc0, 0xa8, 0x00, 0x01, 0xc0, 0xa8, 0x00, 0x02,
+			0x05, 0x39, 0x00, 0x35, 0x00, 0x08, 0x00, 0x00,
+		];
+
+		let ether = ether::Packet::new(&raw[..]).unwrap();
+		assert_eq!(ether.protocol(), ether::Protocol::PBridge);
+
+		let tags = ether.tags().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(tags.len(), 2);
+		assert_eq!(tags[0].vid(), 100);
+		assert_eq!(tags[0].protocol(), ether::Protocol::Vlan);
+		assert_eq!(tags[1].vid(), 200);
+		assert_eq!(tags[1].pcp(), 5);
+		assert_eq!(tags[1].protocol(), ether::Protocol::Ipv4);
+
+		let (protocol, payload) = ether.inner().unwrap();
+		assert_eq!(protocol, ether::Protocol::Ipv4);
+
+		let ip = ip::v4::Packet::new(payload).unwrap();
+		assert_eq!(ip.protocol(), ip::Protocol::Udp);
+	}
+
+	#[test]
+	fn truncated_tag() {
+		let raw = [0x00u8, 0x23, 0x69, 0x63, 0x59, 0xbe, 0xe4, 0xb3, 0x18, 0x26, 0x63, 0xa3, 0x81, 0x00, 0x00, 0x64];
+
+		let ether = ether::Packet::new(&raw[..]).unwrap();
+		assert!(ether.inner().is_err());
+		assert_eq!(ether.tags().count(), 1);
+	}
+
+	#[test]
+	fn untagged() {
+		let raw = [0x00u8, 0x23, 0x69, 0x63, 0x59, 0xbe, 0xe4, 0xb3, 0x18, 0x26, 0x63, 0xa3, 0x86, 0xdd, 0x60];
+
+		let ether = ether::Packet::new(&raw[..]).unwrap();
+		assert_eq!(ether.tags().count(), 0);
+		assert_eq!(ether.inner().unwrap(), (ether::Protocol::Ipv6, &[0x60u8][..]));
+	}
 }
@@ -88,6 +88,13 @@ pub enum Protocol {
 	Unknown(u16),
 }
 
+impl Protocol {
+	/// Check if the protocol identifies a VLAN tag.
+	pub fn is_tag(&self) -> bool {
+		matches!(*self, Protocol::Vlan | Protocol::PBridge | Protocol::QinQ)
+	}
+}
+
 impl From<u16> for Protocol {
 	fn from(value: u16) -> Protocol {
 		use self::Protocol::*;
@@ -0,0 +1,236 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::io::Cursor;
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
+
+use crate::error::*;
+use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::ether::Protocol;
+
+/// IEEE 802.1Q tag parser, the tag protocol identifier is the EtherType of
+/// the enclosing header.
+pub struct Packet<B> {
+	buffer: B,
+}
+
+sized!(Packet,
+	header {
+		min:  4,
+		max:  4,
+		size: 4,
+	}
+
+	payload {
+		min:  0,
+		size: p => p.buffer.as_ref().len() - 4,
+	});
+
+impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ether::vlan::Packet")
+			.field("pcp", &self.pcp())
+			.field("dei", &self.dei())
+			.field("vid", &self.vid())
+			.field("protocol", &self.protocol())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let protocol: u16 = self.protocol().into();
+
+		write!(f, "vlan {}, p {}{}, ethertype {:?} (0x{:04x})",
+			self.vid(), self.pcp(), if self.dei() { ", DEI" } else { "" },
+			self.protocol(), protocol)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Create a VLAN tag without checking the buffer.
+	pub fn unchecked(buffer: B) -> Packet<B> {
+		Packet { buffer }
+	}
+
+	/// Parse a VLAN tag, checking the buffer contents are correct.
+	pub fn new(buffer: B) -> Result<Packet<B>> {
+		use crate::size::header::Min;
+
+		let packet = Packet::unchecked(buffer);
+
+		if packet.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		Ok(packet)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Convert the packet to its owned version.
+	///
+	/// # Notes
+	///
+	/// It would be nice if `ToOwned` could be implemented, but `Packet` already
+	/// implements `Clone` and the impl would conflict.
+	pub fn to_owned(&self) -> Packet<Vec<u8>> {
+		Packet::unchecked(self.buffer.as_ref().to_vec())
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
+	fn as_ref(&self) -> &[u8] {
+		use crate::size::Size;
+
+		&self.buffer.as_ref()[.. self.size()]
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
+	fn as_mut(&mut self) -> &mut [u8] {
+		use crate::size::Size;
+
+		let size = self.size();
+		&mut self.buffer.as_mut()[.. size]
+	}
+}
+
+impl<'a, B: AsRef<[u8]>> AsPacket<'a, Packet<&'a [u8]>> for B {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.as_ref())
+	}
+}
+
+impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Packet<&'a mut [u8]>> for B {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.as_mut())
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Packet<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		self.buffer.as_ref().split_at(4)
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Packet<B> {
+	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+		self.buffer.as_mut().split_at_mut(4)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Priority code point.
+	pub fn pcp(&self) -> u8 {
+		self.buffer.as_ref()[0] >> 5
+	}
+
+	/// Drop eligible indicator.
+	pub fn dei(&self) -> bool {
+		self.buffer.as_ref()[0] & 0b0001_0000 != 0
+	}
+
+	/// VLAN identifier.
+	pub fn vid(&self) -> u16 {
+		(&self.buffer.as_ref()[0 ..]).read_u16::<BigEndian>().unwrap() & 0x0fff
+	}
+
+	/// Protocol of the inner packet.
+	pub fn protocol(&self) -> Protocol {
+		(&self.buffer.as_ref()[2 ..]).read_u16::<BigEndian>().unwrap().into()
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
+	/// Priority code point.
+	pub fn set_pcp(&mut self, value: u8) -> Result<&mut Self> {
+		if value > 0b111 {
+			Err(Error::InvalidValue)?
+		}
+
+		let byte = &mut self.buffer.as_mut()[0];
+		*byte = (*byte & 0b0001_1111) | (value << 5);
+
+		Ok(self)
+	}
+
+	/// Drop eligible indicator.
+	pub fn set_dei(&mut self, value: bool) -> Result<&mut Self> {
+		if value {
+			self.buffer.as_mut()[0] |= 0b0001_0000;
+		}
+		else {
+			self.buffer.as_mut()[0] &= !0b0001_0000;
+		}
+
+		Ok(self)
+	}
+
+	/// VLAN identifier.
+	pub fn set_vid(&mut self, value: u16) -> Result<&mut Self> {
+		if value > 0x0fff {
+			Err(Error::InvalidValue)?
+		}
+
+		let tci = (&self.buffer.as_ref()[0 ..]).read_u16::<BigEndian>()?;
+		Cursor::new(&mut self.buffer.as_mut()[0 ..])
+			.write_u16::<BigEndian>((tci & 0xf000) | value)?;
+
+		Ok(self)
+	}
+
+	/// Protocol of the inner packet.
+	pub fn set_protocol(&mut self, value: Protocol) -> Result<&mut Self> {
+		Cursor::new(&mut self.buffer.as_mut()[2 ..])
+			.write_u16::<BigEndian>(value.into())?;
+
+		Ok(self)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::ether;
+
+	#[test]
+	fn values() {
+		let raw = [0xb0, 0x64, 0x08, 0x00];
+		let tag = ether::vlan::Packet::new(&raw[..]).unwrap();
+
+		assert_eq!(tag.pcp(), 5);
+		assert!(tag.dei());
+		assert_eq!(tag.vid(), 100);
+		assert_eq!(tag.protocol(), ether::Protocol::Ipv4);
+	}
+
+	#[test]
+	fn mutable() {
+		let mut raw = [0x00, 0x00, 0x00, 0x00];
+		let mut tag = ether::vlan::Packet::new(&mut raw[..]).unwrap();
+
+		tag.set_vid(4094).unwrap()
+			.set_pcp(3).unwrap()
+			.set_protocol(ether::Protocol::Ipv6).unwrap();
+
+		assert!(tag.set_vid(4096).is_err());
+		assert!(tag.set_pcp(8).is_err());
+
+		assert_eq!(tag.vid(), 4094);
+		assert_eq!(tag.pcp(), 3);
+		assert!(!tag.dei());
+		assert_eq!(tag.protocol(), ether::Protocol::Ipv6);
+	}
+}
@@ -0,0 +1,248 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use crate::dissect::{Layer, Stack};
+use crate::ip::{self, Protocol};
+use crate::flow::toeplitz;
+
+/// Flow 5-tuple.
+///
+/// For ICMP echo packets the identifier is used as both ports, so requests
+/// and replies belong to the same flow; other ICMP packets have no ports.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct Key {
+	/// Source address.
+	pub source: IpAddr,
+
+	/// Destination address.
+	pub destination: IpAddr,
+
+	/// Source port.
+	pub source_port: u16,
+
+	/// Destination port.
+	pub destination_port: u16,
+
+	/// Transport protocol.
+	pub protocol: Protocol,
+}
+
+impl fmt::Display for Key {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:?} ", self.protocol)?;
+
+		match (self.source, self.destination) {
+			(IpAddr::V6(source), IpAddr::V6(destination)) =>
+				write!(f, "[{}]:{} > [{}]:{}", source, self.source_port, destination, self.destination_port),
+
+			(source, destination) =>
+				write!(f, "{}:{} > {}:{}", source, self.source_port, destination, self.destination_port),
+		}
+	}
+}
+
+impl Key {
+	/// Extract the key from a dissected packet, if it has an IP layer.
+	///
+	/// Tunnelled packets use the innermost IP packet and the transport layer
+	/// following it. Without a known transport layer, like in non-first
+	/// fragments, the protocol of the IP packet is used with no ports.
+	pub fn from_stack(stack: &Stack<'_>) -> Option<Key> {
+		let (index, ip) = stack.layers().iter().enumerate().rev()
+			.find_map(|(index, layer)| if let Layer::Ip(ip) = layer { Some((index, ip)) } else { None })?;
+
+		let protocol = match *ip {
+			ip::Packet::V4(ref packet) =>
+				packet.protocol(),
+
+			ip::Packet::V6(ref packet) =>
+				packet.upper_layer().map(|(protocol, _)| protocol).unwrap_or(packet.next_header()),
+		};
+
+		let (source_port, destination_port) = match stack.layers().get(index + 1) {
+			Some(Layer::Tcp(tcp)) =>
+				(tcp.source(), tcp.destination()),
+
+			Some(Layer::Udp(udp)) =>
+				(udp.source(), udp.destination()),
+
+			Some(Layer::Icmp(icmp)) =>
+				icmp.echo().map(|echo| (echo.identifier(), echo.identifier())).unwrap_or((0, 0)),
+
+			Some(Layer::Icmpv6(icmp)) =>
+				icmp.echo().map(|echo| (echo.identifier(), echo.identifier())).unwrap_or((0, 0)),
+
+			_ =>
+				(0, 0)
+		};
+
+		Some(Key {
+			source:           ip.source(),
+			destination:      ip.destination(),
+			source_port,
+			destination_port,
+			protocol,
+		})
+	}
+
+	/// The key of the opposite direction.
+	pub fn reverse(&self) -> Key {
+		Key {
+			source:           self.destination,
+			destination:      self.source,
+			source_port:      self.destination_port,
+			destination_port: self.source_port,
+			protocol:         self.protocol,
+		}
+	}
+
+	/// Whether the source endpoint sorts before the destination one.
+	pub fn is_canonical(&self) -> bool {
+		(self.source, self.source_port) <= (self.destination, self.destination_port)
+	}
+
+	/// The same key for both directions of the flow, with the lower endpoint
+	/// as the source.
+	pub fn canonical(&self) -> Key {
+		if self.is_canonical() {
+			*self
+		}
+		else {
+			self.reverse()
+		}
+	}
+
+	/// Toeplitz hash of the key as computed by NICs for receive side scaling.
+	///
+	/// TCP and UDP flows hash the addresses and ports, anything else only the
+	/// addresses.
+	pub fn toeplitz(&self, key: &[u8]) -> u32 {
+		let mut input = Vec::with_capacity(36);
+
+		for address in &[self.source, self.destination] {
+			match *address {
+				IpAddr::V4(address) => input.extend_from_slice(&address.octets()),
+				IpAddr::V6(address) => input.extend_from_slice(&address.octets()),
+			}
+		}
+
+		if self.protocol == Protocol::Tcp || self.protocol == Protocol::Udp {
+			input.extend_from_slice(&self.source_port.to_be_bytes());
+			input.extend_from_slice(&self.destination_port.to_be_bytes());
+		}
+
+		toeplitz::hash(key, &input)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::builder::Builder;
+	use crate::dissect::{dissect, LinkType};
+	use crate::flow::{self, toeplitz};
+	use crate::ip::{self, Protocol};
+
+	#[test]
+	fn tcp() {
+		let packet = ip::v4::Builder::default()
+			.source("66.9.149.187".parse().unwrap()).unwrap()
+			.destination("161.142.100.80".parse().unwrap()).unwrap()
+			.tcp().unwrap()
+				.source(2794).unwrap()
+				.destination(1766).unwrap()
+				.build().unwrap();
+
+		let key = flow::Key::from_stack(&dissect(LinkType::Raw, &packet)).unwrap();
+		assert_eq!(key.to_string(), "Tcp 66.9.149.187:2794 > 161.142.100.80:1766");
+		assert!(key.is_canonical());
+		assert_eq!(key.reverse().canonical(), key);
+		assert_eq!(key.toeplitz(&toeplitz::DEFAULT_KEY), 0x51ccc178);
+		assert_ne!(key.reverse().toeplitz(&toeplitz::DEFAULT_KEY), 0x51ccc178);
+		assert_eq!(key.toeplitz(&toeplitz::SYMMETRIC_KEY), key.reverse().toeplitz(&toeplitz::SYMMETRIC_KEY));
+	}
+
+	#[test]
+	fn udp() {
+		let packet = ip::v6::Builder::default()
+			.source("3ffe:2501:200:1fff::7".parse().unwrap()).unwrap()
+			.destination("3ffe:2501:200:3::1".parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(2794).unwrap()
+				.destination(1766).unwrap()
+				.build().unwrap();
+
+		let key = flow::Key::from_stack(&dissect(LinkType::Raw, &packet)).unwrap();
+		assert_eq!(key.protocol, Protocol::Udp);
+		assert_eq!(key.to_string(), "Udp [3ffe:2501:200:1fff::7]:2794 > [3ffe:2501:200:3::1]:1766");
+		assert!(!key.is_canonical());
+		assert_eq!(key.toeplitz(&toeplitz::DEFAULT_KEY), 0x40207d3d);
+
+		let key = flow::Key { protocol: Protocol::Ipv6NoNxt, .. key };
+		assert_eq!(key.toeplitz(&toeplitz::DEFAULT_KEY), 0x2cc18cd5);
+	}
+
+	#[test]
+	fn tunnel() {
+		let inner = ip::v4::Builder::default()
+			.source("66.9.149.187".parse().unwrap()).unwrap()
+			.destination("161.142.100.80".parse().unwrap()).unwrap()
+			.tcp().unwrap()
+				.source(2794).unwrap()
+				.destination(1766).unwrap()
+				.build().unwrap();
+
+		let packet = ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.next_header(Protocol::Ipv4).unwrap()
+			.payload(&inner).unwrap()
+			.build().unwrap();
+
+		let key = flow::Key::from_stack(&dissect(LinkType::Raw, &packet)).unwrap();
+		assert_eq!(key.to_string(), "Tcp 66.9.149.187:2794 > 161.142.100.80:1766");
+		assert_eq!(key.toeplitz(&toeplitz::DEFAULT_KEY), 0x51ccc178);
+	}
+
+	#[test]
+	fn icmp() {
+		let request = ip::v4::Builder::default()
+			.source("10.0.0.1".parse().unwrap()).unwrap()
+			.destination("10.0.0.2".parse().unwrap()).unwrap()
+			.icmp().unwrap().echo().unwrap().request().unwrap()
+				.identifier(42).unwrap()
+				.sequence(2).unwrap()
+				.build().unwrap();
+
+		let reply = ip::v4::Builder::default()
+			.source("10.0.0.2".parse().unwrap()).unwrap()
+			.destination("10.0.0.1".parse().unwrap()).unwrap()
+			.icmp().unwrap().echo().unwrap().reply().unwrap()
+				.identifier(42).unwrap()
+				.sequence(2).unwrap()
+				.build().unwrap();
+
+		let request = flow::Key::from_stack(&dissect(LinkType::Raw, &request)).unwrap();
+		let reply   = flow::Key::from_stack(&dissect(LinkType::Raw, &reply)).unwrap();
+
+		assert_eq!(request.protocol, Protocol::Icmp);
+		assert_eq!((request.source_port, request.destination_port), (42, 42));
+		assert_eq!(request.reverse(), reply);
+		assert_eq!(request.canonical(), reply.canonical());
+
+		assert!(flow::Key::from_stack(&dissect(LinkType::Raw, &[0u8; 4])).is_none());
+	}
+}
@@ -0,0 +1,424 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::dissect::Stack;
+use crate::ip;
+use crate::tcp::Flags;
+use crate::flow::Key;
+
+/// Counters for one direction of a flow.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Counters {
+	/// Number of packets.
+	pub packets: u64,
+
+	/// Number of octets, counting the IP headers.
+	pub bytes: u64,
+
+	/// Union of the TCP flags.
+	pub flags: Flags,
+
+	/// Timestamp of the first packet.
+	pub first: Duration,
+
+	/// Timestamp of the last packet.
+	pub last: Duration,
+}
+
+impl Default for Counters {
+	fn default() -> Self {
+		Counters {
+			packets: 0,
+			bytes:   0,
+			flags:   Flags::empty(),
+			first:   Duration::default(),
+			last:    Duration::default(),
+		}
+	}
+}
+
+/// Statistics of a flow, the forward direction being the one of its first
+/// packet.
+#[derive(Clone, Debug)]
+pub struct Flow {
+	key:     Key,
+	first:   Duration,
+	last:    Duration,
+	forward: Counters,
+	reverse: Counters,
+}
+
+impl Flow {
+	fn new(key: Key, timestamp: Duration) -> Flow {
+		Flow {
+			key,
+			first:   timestamp,
+			last:    timestamp,
+			forward: Counters::default(),
+			reverse: Counters::default(),
+		}
+	}
+
+	/// Key of the forward direction.
+	pub fn key(&self) -> &Key {
+		&self.key
+	}
+
+	/// Timestamp of the first packet.
+	pub fn first(&self) -> Duration {
+		self.first
+	}
+
+	/// Timestamp of the last packet.
+	pub fn last(&self) -> Duration {
+		self.last
+	}
+
+	/// Time between the first and last packets.
+	pub fn duration(&self) -> Duration {
+		self.last - self.first
+	}
+
+	/// Counters of the forward direction.
+	pub fn forward(&self) -> &Counters {
+		&self.forward
+	}
+
+	/// Counters of the reverse direction.
+	pub fn reverse(&self) -> &Counters {
+		&self.reverse
+	}
+
+	/// Number of packets in both directions.
+	pub fn packets(&self) -> u64 {
+		self.forward.packets + self.reverse.packets
+	}
+
+	/// Number of octets in both directions.
+	pub fn bytes(&self) -> u64 {
+		self.forward.bytes + self.reverse.bytes
+	}
+
+	/// Union of the TCP flags in both directions.
+	pub fn flags(&self) -> Flags {
+		self.forward.flags | self.reverse.flags
+	}
+
+	/// Whether the TCP connection is over, reset or closed from both sides.
+	fn is_ended(&self) -> bool {
+		self.flags().contains(Flags::RST) ||
+		(self.forward.flags.contains(Flags::FIN) && self.reverse.flags.contains(Flags::FIN))
+	}
+}
+
+/// Why a flow was exported.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Reason {
+	/// No packet was seen for the idle timeout.
+	Idle,
+
+	/// The flow lasted longer than the active timeout.
+	Active,
+
+	/// The TCP connection was reset or closed.
+	End,
+
+	/// The table was flushed.
+	Flush,
+}
+
+/// Flow table, accumulating statistics per 5-tuple and exporting the flows
+/// to a callback when they time out.
+///
+/// Timestamps are only compared with each other, so the capture timestamps
+/// of the packets work.
+pub struct Table<F> {
+	flows:  HashMap<Key, Flow>,
+	idle:   Duration,
+	active: Duration,
+	export: F,
+}
+
+impl<F: FnMut(Flow, Reason)> Table<F> {
+	/// Create a table with a 15 seconds idle timeout and a 30 minutes active
+	/// timeout.
+	pub fn new(export: F) -> Table<F> {
+		Table {
+			flows:  HashMap::new(),
+			idle:   Duration::from_secs(15),
+			active: Duration::from_secs(30 * 60),
+			export,
+		}
+	}
+
+	/// Time without packets after which a flow is exported.
+	pub fn idle(mut self, value: Duration) -> Self {
+		self.idle = value;
+		self
+	}
+
+	/// Time after which a flow is exported even if still active, a new flow
+	/// being started for the following packets.
+	pub fn active(mut self, value: Duration) -> Self {
+		self.active = value;
+		self
+	}
+
+	/// Number of flows in the table.
+	pub fn len(&self) -> usize {
+		self.flows.len()
+	}
+
+	/// Whether the table is empty.
+	pub fn is_empty(&self) -> bool {
+		self.flows.is_empty()
+	}
+
+	/// The flow the key belongs to, in either direction.
+	pub fn get(&self, key: &Key) -> Option<&Flow> {
+		self.flows.get(&key.canonical())
+	}
+
+	/// Account a dissected packet, returning whether it had an IP layer.
+	pub fn push(&mut self, timestamp: Duration, stack: &Stack<'_>) -> bool {
+		let (key, ip) = match (Key::from_stack(stack), stack.ip()) {
+			(Some(key), Some(ip)) => (key, ip),
+			_                     => return false,
+		};
+
+		let canonical = key.canonical();
+
+		if let Some(flow) = self.flows.get(&canonical) {
+			let reason = if timestamp.saturating_sub(flow.last) >= self.idle {
+				Some(Reason::Idle)
+			}
+			else if timestamp.saturating_sub(flow.first) >= self.active {
+				Some(Reason::Active)
+			}
+			else {
+				None
+			};
+
+			if let Some(reason) = reason {
+				let flow = self.flows.remove(&canonical).unwrap();
+				(self.export)(flow, reason);
+			}
+		}
+
+		let flow = self.flows.entry(canonical).or_insert_with(|| Flow::new(key, timestamp));
+		flow.last = flow.last.max(timestamp);
+
+		let counters = if flow.key == key { &mut flow.forward } else { &mut flow.reverse };
+
+		if counters.packets == 0 {
+			counters.first = timestamp;
+		}
+
+		counters.last     = counters.last.max(timestamp);
+		counters.packets += 1;
+		counters.bytes   += match *ip {
+			ip::Packet::V4(ref packet) =>
+				packet.length() as u64,
+
+			ip::Packet::V6(ref packet) if !packet.is_jumbo() =>
+				40 + packet.length() as u64,
+
+			ref packet =>
+				packet.as_ref().len() as u64,
+		};
+
+		if let Some(tcp) = stack.tcp() {
+			counters.flags |= tcp.flags();
+		}
+
+		if flow.is_ended() {
+			let flow = self.flows.remove(&canonical).unwrap();
+			(self.export)(flow, Reason::End);
+		}
+
+		true
+	}
+
+	/// Export the flows that timed out, returning how many were exported.
+	pub fn expire(&mut self, now: Duration) -> usize {
+		let (idle, active) = (self.idle, self.active);
+
+		let expired = self.flows.iter()
+			.filter_map(|(key, flow)| {
+				if now.saturating_sub(flow.last) >= idle {
+					Some((*key, Reason::Idle))
+				}
+				else if now.saturating_sub(flow.first) >= active {
+					Some((*key, Reason::Active))
+				}
+				else {
+					None
+				}
+			})
+			.collect::<Vec<_>>();
+
+		for &(key, reason) in &expired {
+			let flow = self.flows.remove(&key).unwrap();
+			(self.export)(flow, reason);
+		}
+
+		expired.len()
+	}
+
+	/// Export all the flows, returning how many were exported.
+	pub fn flush(&mut self) -> usize {
+		let count = self.flows.len();
+
+		for (_, flow) in self.flows.drain() {
+			(self.export)(flow, Reason::Flush);
+		}
+
+		count
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use crate::builder::Builder;
+	use crate::dissect::{dissect, LinkType};
+	use crate::flow::{self, Reason};
+	use crate::ip;
+	use crate::tcp::Flags;
+
+	fn udp(source: &str, destination: &str, sport: u16, dport: u16) -> Vec<u8> {
+		ip::v4::Builder::default()
+			.source(source.parse().unwrap()).unwrap()
+			.destination(destination.parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(sport).unwrap()
+				.destination(dport).unwrap()
+				.payload(b"query").unwrap()
+				.build().unwrap()
+	}
+
+	fn tcp(source: &str, destination: &str, sport: u16, dport: u16, flags: Flags) -> Vec<u8> {
+		ip::v4::Builder::default()
+			.source(source.parse().unwrap()).unwrap()
+			.destination(destination.parse().unwrap()).unwrap()
+			.tcp().unwrap()
+				.source(sport).unwrap()
+				.destination(dport).unwrap()
+				.flags(flags).unwrap()
+				.build().unwrap()
+	}
+
+	#[test]
+	fn counters() {
+		let mut exported = Vec::new();
+
+		{
+			let mut table = flow::Table::new(|flow, reason| exported.push((flow, reason)));
+
+			let query = udp("10.0.0.1", "10.0.0.2", 5353, 53);
+			let reply = udp("10.0.0.2", "10.0.0.1", 53, 5353);
+
+			assert!(table.push(Duration::from_secs(1), &dissect(LinkType::Raw, &query)));
+			assert!(table.push(Duration::from_secs(2), &dissect(LinkType::Raw, &reply)));
+			assert!(table.push(Duration::from_secs(3), &dissect(LinkType::Raw, &query)));
+			assert!(!table.push(Duration::from_secs(3), &dissect(LinkType::Raw, &[0u8; 4])));
+			assert_eq!(table.len(), 1);
+
+			assert_eq!(table.expire(Duration::from_secs(10)), 0);
+			assert_eq!(table.flush(), 1);
+			assert!(table.is_empty());
+		}
+
+		let (ref flow, reason) = exported[0];
+		assert_eq!(reason, Reason::Flush);
+		assert_eq!(flow.key().source_port, 5353);
+		assert_eq!(flow.duration(), Duration::from_secs(2));
+		assert_eq!((flow.forward().packets, flow.forward().bytes), (2, 66));
+		assert_eq!((flow.reverse().packets, flow.reverse().bytes), (1, 33));
+		assert_eq!((flow.forward().first, flow.forward().last), (Duration::from_secs(1), Duration::from_secs(3)));
+		assert_eq!((flow.reverse().first, flow.reverse().last), (Duration::from_secs(2), Duration::from_secs(2)));
+		assert_eq!((flow.packets(), flow.bytes()), (3, 99));
+	}
+
+	#[test]
+	fn truncated() {
+		let mut exported = Vec::new();
+
+		{
+			let mut table = flow::Table::new(|flow: flow::Flow, _| exported.push(flow.bytes()));
+			let query     = udp("10.0.0.1", "10.0.0.2", 5353, 53);
+
+			assert!(table.push(Duration::from_secs(1), &dissect(LinkType::Raw, &query[.. 24])));
+			table.flush();
+		}
+
+		assert_eq!(exported, vec![33]);
+	}
+
+	#[test]
+	fn timeouts() {
+		let mut exported = Vec::new();
+
+		{
+			let mut table = flow::Table::new(|flow: flow::Flow, reason| exported.push((flow.packets(), reason)))
+				.idle(Duration::from_secs(10))
+				.active(Duration::from_secs(60));
+
+			let query = udp("10.0.0.1", "10.0.0.2", 5353, 53);
+
+			for second in 0 .. 8 {
+				table.push(Duration::from_secs(second * 9), &dissect(LinkType::Raw, &query));
+			}
+
+			table.push(Duration::from_secs(100), &dissect(LinkType::Raw, &query));
+			table.push(Duration::from_secs(105), &dissect(LinkType::Raw, &udp("10.0.0.3", "10.0.0.2", 1, 53)));
+			assert_eq!(table.expire(Duration::from_secs(112)), 1);
+			assert_eq!(table.len(), 1);
+		}
+
+		assert_eq!(exported, vec![(7, Reason::Active), (1, Reason::Idle), (1, Reason::Idle)]);
+	}
+
+	#[test]
+	fn end() {
+		let mut exported = Vec::new();
+
+		{
+			let mut table = flow::Table::new(|flow: flow::Flow, reason| exported.push((flow.flags(), reason)));
+
+			let packets = [
+				tcp("10.0.0.1", "10.0.0.2", 1337, 80, Flags::SYN),
+				tcp("10.0.0.2", "10.0.0.1", 80, 1337, Flags::SYN | Flags::ACK),
+				tcp("10.0.0.1", "10.0.0.2", 1337, 80, Flags::ACK | Flags::FIN),
+				tcp("10.0.0.2", "10.0.0.1", 80, 1337, Flags::ACK | Flags::FIN),
+				tcp("10.0.0.1", "10.0.0.2", 1338, 80, Flags::SYN),
+				tcp("10.0.0.2", "10.0.0.1", 80, 1338, Flags::RST),
+			];
+
+			for packet in &packets {
+				table.push(Duration::from_secs(1), &dissect(LinkType::Raw, packet));
+			}
+
+			assert!(table.is_empty());
+		}
+
+		assert_eq!(exported, vec![
+			(Flags::SYN | Flags::ACK | Flags::FIN, Reason::End),
+			(Flags::SYN | Flags::RST, Reason::End),
+		]);
+	}
+}
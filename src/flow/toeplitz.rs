@@ -0,0 +1,72 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+/// The default key from the Microsoft RSS specification, used by most NICs.
+pub const DEFAULT_KEY: [u8; 40] = [
+	0x6d, 0x5a, 0x56, 0xda, 0x25, 0x5b, 0x0e, 0xc2,
+	0x41, 0x67, 0x25, 0x3d, 0x43, 0xa3, 0x8f, 0xb0,
+	0xd0, 0xca, 0x2b, 0xcb, 0xae, 0x7b, 0x30, 0xb4,
+	0x77, 0xcb, 0x2d, 0xa3, 0x80, 0x30, 0xf2, 0x0c,
+	0x6a, 0x42, 0xb7, 0x3b, 0xbe, 0xac, 0x01, 0xfa,
+];
+
+/// A key giving the same hash for both directions of a flow.
+pub const SYMMETRIC_KEY: [u8; 40] = [
+	0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+	0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+	0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+	0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+	0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+];
+
+/// Hash the input with the given key.
+///
+/// # Panics
+///
+/// The key has to be at least 4 octets longer than the input.
+pub fn hash(key: &[u8], input: &[u8]) -> u32 {
+	assert!(key.len() >= input.len() + 4, "Toeplitz key too short for the input");
+
+	let mut result = 0u32;
+	let mut window = u32::from_be_bytes([key[0], key[1], key[2], key[3]]);
+
+	for (i, &byte) in input.iter().enumerate() {
+		let next = key[i + 4];
+
+		for bit in 0 .. 8 {
+			if byte & (0x80 >> bit) != 0 {
+				result ^= window;
+			}
+
+			window = (window << 1) | u32::from((next >> (7 - bit)) & 1);
+		}
+	}
+
+	result
+}
+
+#[cfg(test)]
+mod test {
+	use super::{hash, DEFAULT_KEY};
+
+	#[test]
+	fn verification() {
+		// From the Microsoft RSS verification suite, source 66.9.149.187:2794
+		// and destination 161.142.100.80:1766.
+		let input = [66, 9, 149, 187, 161, 142, 100, 80, 0x0a, 0xea, 0x06, 0xe6];
+
+		assert_eq!(hash(&DEFAULT_KEY, &input[.. 8]), 0x323e8fc2);
+		assert_eq!(hash(&DEFAULT_KEY, &input), 0x51ccc178);
+	}
+}
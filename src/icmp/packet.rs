@@ -49,6 +49,50 @@ impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
 	}
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.echo() {
+			Ok(echo) =>
+				write!(f, "ICMP echo {}, id {}, seq {}",
+					if echo.is_request() { "request" } else { "reply" },
+					echo.identifier(), echo.sequence())?,
+
+			Err(_) => match self.kind() {
+				Kind::Unknown(value) =>
+					write!(f, "ICMP type-#{}, code {}", value, self.code())?,
+
+				kind =>
+					write!(f, "ICMP {}, code {}", name(kind), self.code())?,
+			}
+		}
+
+		write!(f, ", length {}", self.buffer.as_ref().len())
+	}
+}
+
+/// Name of the type as printed by tcpdump.
+fn name(kind: Kind) -> &'static str {
+	match kind {
+		Kind::EchoReply              => "echo reply",
+		Kind::DestinationUnreachable => "destination unreachable",
+		Kind::SourceQuench           => "source quench",
+		Kind::RedirectMessage        => "redirect",
+		Kind::EchoRequest            => "echo request",
+		Kind::RouterAdvertisement    => "router advertisement",
+		Kind::RouterSolicitation     => "router solicitation",
+		Kind::TimeExceeded           => "time exceeded",
+		Kind::ParameterProblem       => "parameter problem",
+		Kind::TimestampRequest       => "time stamp request",
+		Kind::TimestampReply         => "time stamp reply",
+		Kind::InformationRequest     => "information request",
+		Kind::InformationReply       => "information reply",
+		Kind::AddressMaskRequest     => "address mask request",
+		Kind::AddressMaskReply       => "address mask reply",
+		Kind::TraceRoute             => "traceroute",
+		Kind::Unknown(_)             => "unknown",
+	}
+}
+
 impl<B: AsRef<[u8]>> Packet<B> {
 	/// Create an ICMP packet without checking the buffer.
 	pub fn unchecked(buffer: B) -> Packet<B> {
@@ -192,3 +236,26 @@ impl<'a, P: PM + AsRef<[u8]> + AsMut<[u8]> + 'a> Drop for Checked<'a, P> {
 			.write_u16::<BigEndian>(checksum).unwrap();
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use crate::icmp;
+
+	#[test]
+	fn display() {
+		let raw = [0x08u8, 0x00, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x02];
+
+		assert_eq!(icmp::Packet::new(&raw[..]).unwrap().to_string(),
+			"ICMP echo request, id 42, seq 2, length 8");
+
+		let raw = [0x03u8, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+		assert_eq!(icmp::Packet::new(&raw[..]).unwrap().to_string(),
+			"ICMP destination unreachable, code 1, length 8");
+
+		let raw = [0x2au8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+		assert_eq!(icmp::Packet::new(&raw[..]).unwrap().to_string(),
+			"ICMP type-#42, code 0, length 8");
+	}
+}
@@ -0,0 +1,464 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::io::Cursor;
+use byteorder::{WriteBytesExt, BigEndian};
+
+use crate::error::*;
+use crate::buffer::{self, Buffer};
+use crate::builder::{Builder as Build, Finalization};
+use crate::ip;
+use crate::icmpv6::checksum;
+use crate::icmpv6::{echo, previous, packet_too_big, parameter_problem};
+use crate::icmpv6::{router_solicitation, router_advertisement, neighbor, redirect};
+
+/// ICMPv6 packet builder.
+///
+/// The checksum needs the enclosing IPv6 packet, so it's only created through
+/// `ip::v6::Builder::icmpv6()`.
+#[derive(Debug)]
+pub struct Builder<B: Buffer = buffer::Dynamic> {
+	buffer:    B,
+	finalizer: Finalization,
+}
+
+impl<B: Buffer> Build<B> for Builder<B> {
+	fn with(buffer: B) -> Result<Self> {
+		Ok(Builder {
+			buffer,
+			finalizer: Default::default(),
+		})
+	}
+
+	fn finalizer(&mut self) -> &mut Finalization {
+		&mut self.finalizer
+	}
+
+	fn build(self) -> Result<B::Inner> {
+		Err(Error::InvalidPacket)
+	}
+}
+
+impl<B: Buffer> Builder<B> {
+	/// Build an Echo Request/Reply packet.
+	pub fn echo(self) -> Result<echo::Builder<B>> {
+		let mut echo = echo::Builder::with(self.buffer)?;
+		echo.finalizer().extend(self.finalizer);
+
+		Ok(echo)
+	}
+
+	/// Build a Destination Unreachable or Time Exceeded packet.
+	pub fn previous(self) -> Result<previous::Builder<B>> {
+		let mut previous = previous::Builder::with(self.buffer)?;
+		previous.finalizer().extend(self.finalizer);
+
+		Ok(previous)
+	}
+
+	/// Build a Packet Too Big packet.
+	pub fn packet_too_big(self) -> Result<packet_too_big::Builder<B>> {
+		let mut packet_too_big = packet_too_big::Builder::with(self.buffer)?;
+		packet_too_big.finalizer().extend(self.finalizer);
+
+		Ok(packet_too_big)
+	}
+
+	/// Build a Parameter Problem packet.
+	pub fn parameter_problem(self) -> Result<parameter_problem::Builder<B>> {
+		let mut parameter_problem = parameter_problem::Builder::with(self.buffer)?;
+		parameter_problem.finalizer().extend(self.finalizer);
+
+		Ok(parameter_problem)
+	}
+
+	/// Build a Router Solicitation packet.
+	pub fn router_solicitation(self) -> Result<router_solicitation::Builder<B>> {
+		let mut router_solicitation = router_solicitation::Builder::with(self.buffer)?;
+		router_solicitation.finalizer().extend(self.finalizer);
+
+		Ok(router_solicitation)
+	}
+
+	/// Build a Router Advertisement packet.
+	pub fn router_advertisement(self) -> Result<router_advertisement::Builder<B>> {
+		let mut router_advertisement = router_advertisement::Builder::with(self.buffer)?;
+		router_advertisement.finalizer().extend(self.finalizer);
+
+		Ok(router_advertisement)
+	}
+
+	/// Build a Neighbor Solicitation/Advertisement packet.
+	pub fn neighbor(self) -> Result<neighbor::Builder<B>> {
+		let mut neighbor = neighbor::Builder::with(self.buffer)?;
+		neighbor.finalizer().extend(self.finalizer);
+
+		Ok(neighbor)
+	}
+
+	/// Build a Redirect packet.
+	pub fn redirect(self) -> Result<redirect::Builder<B>> {
+		let mut redirect = redirect::Builder::with(self.buffer)?;
+		redirect.finalizer().extend(self.finalizer);
+
+		Ok(redirect)
+	}
+}
+
+pub(in crate::icmpv6) fn prepare<B: Buffer>(finalizer: &mut Finalization, ip: (usize, usize), buffer: &B) {
+	let offset = buffer.offset();
+	let length = buffer.length();
+
+	finalizer.add(move |out| {
+		// Split the buffer into IPv6 and ICMPv6 parts.
+		let (before, after) = out.split_at_mut(offset);
+		let ip              = &mut before[ip.0 .. ip.0 + ip.1];
+		let icmp            = &mut after[.. length];
+
+		ip::payload_length(ip, length);
+
+		// Calculate the checksum by parsing back the IPv6 packet and set it.
+		let checksum = checksum(&ip::v6::Packet::no_payload(&ip)?, icmp);
+		Cursor::new(&mut icmp[2 ..])
+			.write_u16::<BigEndian>(checksum)?;
+
+		Ok(())
+	});
+}
+
+#[cfg(test)]
+mod test {
+	use crate::builder::Builder;
+	use crate::packet::Packet;
+	use crate::ip;
+	use crate::icmpv6;
+
+	fn invoking() -> Vec<u8> {
+		ip::v6::Builder::default()
+			.hop_limit(1).unwrap()
+			.source("2001:db8::2".parse().unwrap()).unwrap()
+			.destination("2001:db8::3".parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.build().unwrap()
+	}
+
+	#[test]
+	fn echo() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(64).unwrap()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.echo().unwrap().request().unwrap()
+					.identifier(42).unwrap()
+					.sequence(2).unwrap()
+					.payload(b"test").unwrap()
+					.build().unwrap();
+
+		let ip = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(ip.next_header(), ip::Protocol::Ipv6Icmp);
+		assert_eq!(ip.length(), 12);
+
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::EchoRequest);
+		assert_eq!(icmp.checksum(), 0x3c3e);
+		assert!(icmp.is_valid(&ip));
+	}
+
+	#[test]
+	fn routing() {
+		let packet = ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::3".parse().unwrap()).unwrap()
+			.routing(ip::v6::routing::Kind::SegmentRouting).unwrap()
+				.segments_left(1).unwrap()
+				.address("2001:db8::2".parse().unwrap()).unwrap()
+				.address("2001:db8::3".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.echo().unwrap().request().unwrap()
+					.identifier(42).unwrap()
+					.sequence(2).unwrap()
+					.payload(b"test").unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(&ip.payload()[40 ..]).unwrap();
+
+		// Same checksum as the echo sent straight to the final destination.
+		assert_eq!(icmp.checksum(), 0x3c3e);
+		assert!(icmp.is_valid(&ip));
+	}
+
+	#[test]
+	fn destination_unreachable() {
+		let packet = ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.previous().unwrap()
+					.destination_unreachable(icmpv6::code::DestinationUnreachable::PortUnreachable).unwrap()
+					.payload(&invoking()).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::DestinationUnreachable);
+		assert!(icmp.is_valid(&ip));
+
+		let previous = icmp.previous().unwrap();
+		assert_eq!(previous.destination_unreachable(), Some(icmpv6::code::DestinationUnreachable::PortUnreachable));
+		assert_eq!(previous.time_exceeded(), None);
+		assert_eq!(previous.packet().unwrap().destination(), "2001:db8::3".parse::<std::net::Ipv6Addr>().unwrap());
+	}
+
+	#[test]
+	fn time_exceeded() {
+		let packet = ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.previous().unwrap()
+					.time_exceeded(icmpv6::code::TimeExceeded::HopLimitExceeded).unwrap()
+					.payload(&invoking()).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::TimeExceeded);
+		assert!(icmp.is_valid(&ip));
+		assert_eq!(icmp.previous().unwrap().time_exceeded(), Some(icmpv6::code::TimeExceeded::HopLimitExceeded));
+	}
+
+	#[test]
+	fn packet_too_big() {
+		let packet = ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.packet_too_big().unwrap()
+					.mtu(1280).unwrap()
+					.payload(&invoking()).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::PacketTooBig);
+		assert!(icmp.is_valid(&ip));
+
+		let too_big = icmp.packet_too_big().unwrap();
+		assert_eq!(too_big.mtu(), 1280);
+		assert_eq!(too_big.packet().unwrap().next_header(), ip::Protocol::Udp);
+	}
+
+	#[test]
+	fn parameter_problem() {
+		let packet = ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.parameter_problem().unwrap()
+					.code(icmpv6::code::ParameterProblem::UnrecognizedNextHeader).unwrap()
+					.pointer(6).unwrap()
+					.payload(&invoking()).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::ParameterProblem);
+		assert!(icmp.is_valid(&ip));
+
+		let problem = icmp.parameter_problem().unwrap();
+		assert_eq!(problem.code(), icmpv6::code::ParameterProblem::UnrecognizedNextHeader);
+		assert_eq!(problem.pointer(), 6);
+	}
+
+	#[test]
+	fn router_solicitation() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(255).unwrap()
+			.source("fe80::1".parse().unwrap()).unwrap()
+			.destination("ff02::2".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.router_solicitation().unwrap()
+					.source_link_layer_address("00:23:69:63:59:be".parse().unwrap()).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::RouterSolicitation);
+		assert!(icmp.is_valid(&ip));
+
+		let solicitation = icmp.router_solicitation().unwrap();
+		let options      = solicitation.options().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(options.len(), 1);
+		assert_eq!(options[0].value().unwrap(),
+			icmpv6::option::Value::SourceLinkLayerAddress("00:23:69:63:59:be".parse().unwrap()));
+	}
+
+	#[test]
+	fn router_advertisement() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(255).unwrap()
+			.source("fe80::1".parse().unwrap()).unwrap()
+			.destination("ff02::1".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.router_advertisement().unwrap()
+					.hop_limit(64).unwrap()
+					.other(true).unwrap()
+					.lifetime(1800).unwrap()
+					.reachable_time(30000).unwrap()
+					.retransmit_timer(1000).unwrap()
+					.source_link_layer_address("00:23:69:63:59:be".parse().unwrap()).unwrap()
+					.mtu(1500).unwrap()
+					.option(icmpv6::option::Value::PrefixInformation {
+						length:             64,
+						on_link:            true,
+						autonomous:         true,
+						valid_lifetime:     86400,
+						preferred_lifetime: 14400,
+						prefix:             "2001:db8::".parse().unwrap(),
+					}).unwrap()
+					.option(icmpv6::option::Value::RecursiveDnsServer {
+						lifetime: 600,
+						servers:  vec!["2001:db8::53".parse().unwrap()],
+					}).unwrap()
+					.option(icmpv6::option::Value::DnsSearchList {
+						lifetime: 600,
+						domains:  vec!["lab.example.com".into()],
+					}).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::RouterAdvertisement);
+		assert!(icmp.is_valid(&ip));
+
+		let advertisement = icmp.router_advertisement().unwrap();
+		assert_eq!(advertisement.hop_limit(), 64);
+		assert!(!advertisement.is_managed());
+		assert!(advertisement.is_other());
+		assert_eq!(advertisement.lifetime(), 1800);
+		assert_eq!(advertisement.reachable_time(), 30000);
+		assert_eq!(advertisement.retransmit_timer(), 1000);
+
+		let options = advertisement.options()
+			.map(|option| option.and_then(|option| option.value()))
+			.collect::<Result<Vec<_>, _>>().unwrap();
+
+		assert_eq!(options.len(), 5);
+		assert_eq!(options[1], icmpv6::option::Value::Mtu(1500));
+
+		match options[4] {
+			icmpv6::option::Value::DnsSearchList { lifetime, ref domains } => {
+				assert_eq!(lifetime, 600);
+				assert_eq!(domains, &["lab.example.com"]);
+			}
+
+			_ =>
+				panic!("not a DNS Search List option")
+		}
+	}
+
+	#[test]
+	fn neighbor() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(255).unwrap()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("ff02::1:ff00:2".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.neighbor().unwrap()
+					.solicitation().unwrap()
+					.target("2001:db8::2".parse().unwrap()).unwrap()
+					.source_link_layer_address("00:23:69:63:59:be".parse().unwrap()).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::NeighborSolicitation);
+		assert!(icmp.is_valid(&ip));
+
+		let solicitation = icmp.neighbor().unwrap();
+		assert!(solicitation.is_solicitation());
+		assert_eq!(solicitation.target(), "2001:db8::2".parse::<std::net::Ipv6Addr>().unwrap());
+
+		let packet = ip::v6::Builder::default()
+			.hop_limit(255).unwrap()
+			.source("2001:db8::2".parse().unwrap()).unwrap()
+			.destination("2001:db8::1".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.neighbor().unwrap()
+					.advertisement().unwrap()
+					.solicited(true).unwrap()
+					.overrides(true).unwrap()
+					.target(solicitation.target()).unwrap()
+					.target_link_layer_address("e4:b3:18:26:63:a3".parse().unwrap()).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::NeighborAdvertisement);
+		assert!(icmp.is_valid(&ip));
+
+		let advertisement = icmp.neighbor().unwrap();
+		assert!(advertisement.is_advertisement());
+		assert!(!advertisement.is_router());
+		assert!(advertisement.is_solicited());
+		assert!(advertisement.is_override());
+		assert_eq!(advertisement.options().next().unwrap().unwrap().value().unwrap(),
+			icmpv6::option::Value::TargetLinkLayerAddress("e4:b3:18:26:63:a3".parse().unwrap()));
+	}
+
+	#[test]
+	fn neighbor_kind() {
+		assert!(ip::v6::Builder::default()
+			.icmpv6().unwrap()
+				.neighbor().unwrap()
+					.build().is_err());
+	}
+
+	#[test]
+	fn redirect() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(255).unwrap()
+			.source("fe80::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::1".parse().unwrap()).unwrap()
+			.icmpv6().unwrap()
+				.redirect().unwrap()
+					.target("fe80::2".parse().unwrap()).unwrap()
+					.destination("2001:db8::3".parse().unwrap()).unwrap()
+					.redirected_header(&invoking()).unwrap()
+					.build().unwrap();
+
+		let ip   = ip::v6::Packet::new(packet).unwrap();
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::Redirect);
+		assert!(icmp.is_valid(&ip));
+
+		let redirect = icmp.redirect().unwrap();
+		assert_eq!(redirect.target(), "fe80::2".parse::<std::net::Ipv6Addr>().unwrap());
+		assert_eq!(redirect.destination(), "2001:db8::3".parse::<std::net::Ipv6Addr>().unwrap());
+
+		let option = redirect.options().next().unwrap().unwrap();
+		assert_eq!(option.number(), icmpv6::option::Number::RedirectedHeader);
+
+		if let icmpv6::option::Value::RedirectedHeader(header) = option.value().unwrap() {
+			assert_eq!(&header[.. 48], &invoking()[..]);
+		}
+		else {
+			panic!("not a Redirected Header option");
+		}
+	}
+}
@@ -0,0 +1,204 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+/// Codes for Destination Unreachable packets.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum DestinationUnreachable {
+	/// No route to destination.
+	NoRoute,
+
+	/// Communication with destination administratively prohibited.
+	AdministrativelyProhibited,
+
+	/// Beyond scope of source address.
+	BeyondScope,
+
+	/// Address unreachable.
+	AddressUnreachable,
+
+	/// Port unreachable.
+	PortUnreachable,
+
+	/// Source address failed ingress/egress policy.
+	SourceAddressFailedPolicy,
+
+	/// Reject route to destination.
+	RejectRoute,
+
+	/// Error in Source Routing Header.
+	ErrorInSourceRoutingHeader,
+
+	/// Headers too long.
+	HeadersTooLong,
+
+	/// Unknown code.
+	Unknown(u8),
+}
+
+/// Codes for Time Exceeded packets.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum TimeExceeded {
+	/// Hop limit exceeded in transit.
+	HopLimitExceeded,
+
+	/// Fragment reassembly time exceeded.
+	FragmentReassemblyTimeExceeded,
+
+	/// Unknown code.
+	Unknown(u8),
+}
+
+/// Codes for Parameter Problem packets.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum ParameterProblem {
+	/// Erroneous header field encountered.
+	ErroneousHeaderField,
+
+	/// Unrecognized Next Header type encountered.
+	UnrecognizedNextHeader,
+
+	/// Unrecognized IPv6 option encountered.
+	UnrecognizedOption,
+
+	/// IPv6 first fragment has incomplete IPv6 header chain.
+	IncompleteHeaderChain,
+
+	/// SR upper-layer header error.
+	SegmentRoutingUpperLayerHeader,
+
+	/// Unrecognized Next Header type encountered by intermediate node.
+	UnrecognizedNextHeaderByIntermediateNode,
+
+	/// Extension header too big.
+	ExtensionHeaderTooBig,
+
+	/// Extension header chain too long.
+	ExtensionHeaderChainTooLong,
+
+	/// Too many extension headers.
+	TooManyExtensionHeaders,
+
+	/// Too many options in extension header.
+	TooManyOptions,
+
+	/// Option too big.
+	OptionTooBig,
+
+	/// Unknown code.
+	Unknown(u8),
+}
+
+impl From<u8> for DestinationUnreachable {
+	fn from(value: u8) -> Self {
+		use self::DestinationUnreachable::*;
+
+		match value {
+			0 => NoRoute,
+			1 => AdministrativelyProhibited,
+			2 => BeyondScope,
+			3 => AddressUnreachable,
+			4 => PortUnreachable,
+			5 => SourceAddressFailedPolicy,
+			6 => RejectRoute,
+			7 => ErrorInSourceRoutingHeader,
+			8 => HeadersTooLong,
+			v => Unknown(v),
+		}
+	}
+}
+
+impl From<DestinationUnreachable> for u8 {
+	fn from(value: DestinationUnreachable) -> u8 {
+		use self::DestinationUnreachable::*;
+
+		match value {
+			NoRoute                    => 0,
+			AdministrativelyProhibited => 1,
+			BeyondScope                => 2,
+			AddressUnreachable         => 3,
+			PortUnreachable            => 4,
+			SourceAddressFailedPolicy  => 5,
+			RejectRoute                => 6,
+			ErrorInSourceRoutingHeader => 7,
+			HeadersTooLong             => 8,
+			Unknown(v)                 => v,
+		}
+	}
+}
+
+impl From<u8> for TimeExceeded {
+	fn from(value: u8) -> Self {
+		use self::TimeExceeded::*;
+
+		match value {
+			0 => HopLimitExceeded,
+			1 => FragmentReassemblyTimeExceeded,
+			v => Unknown(v),
+		}
+	}
+}
+
+impl From<TimeExceeded> for u8 {
+	fn from(value: TimeExceeded) -> u8 {
+		use self::TimeExceeded::*;
+
+		match value {
+			HopLimitExceeded               => 0,
+			FragmentReassemblyTimeExceeded => 1,
+			Unknown(v)                     => v,
+		}
+	}
+}
+
+impl From<u8> for ParameterProblem {
+	fn from(value: u8) -> Self {
+		use self::ParameterProblem::*;
+
+		match value {
+			0  => ErroneousHeaderField,
+			1  => UnrecognizedNextHeader,
+			2  => UnrecognizedOption,
+			3  => IncompleteHeaderChain,
+			4  => SegmentRoutingUpperLayerHeader,
+			5  => UnrecognizedNextHeaderByIntermediateNode,
+			6  => ExtensionHeaderTooBig,
+			7  => ExtensionHeaderChainTooLong,
+			8  => TooManyExtensionHeaders,
+			9  => TooManyOptions,
+			10 => OptionTooBig,
+			v  => Unknown(v),
+		}
+	}
+}
+
+impl From<ParameterProblem> for u8 {
+	fn from(value: ParameterProblem) -> u8 {
+		use self::ParameterProblem::*;
+
+		match value {
+			ErroneousHeaderField                     => 0,
+			UnrecognizedNextHeader                   => 1,
+			UnrecognizedOption                       => 2,
+			IncompleteHeaderChain                    => 3,
+			SegmentRoutingUpperLayerHeader           => 4,
+			UnrecognizedNextHeaderByIntermediateNode => 5,
+			ExtensionHeaderTooBig                    => 6,
+			ExtensionHeaderChainTooLong              => 7,
+			TooManyExtensionHeaders                  => 8,
+			TooManyOptions                           => 9,
+			OptionTooBig                             => 10,
+			Unknown(v)                               => v,
+		}
+	}
+}
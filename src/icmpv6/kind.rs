@@ -0,0 +1,148 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+/// ICMPv6 packet types.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Kind {
+	/// Destination unreachable.
+	DestinationUnreachable,
+
+	/// Packet too big.
+	PacketTooBig,
+
+	/// Time exceeded.
+	TimeExceeded,
+
+	/// Parameter problem.
+	ParameterProblem,
+
+	/// Echo request.
+	EchoRequest,
+
+	/// Echo reply.
+	EchoReply,
+
+	/// Multicast Listener Query.
+	MulticastListenerQuery,
+
+	/// Multicast Listener Report.
+	MulticastListenerReport,
+
+	/// Multicast Listener Done.
+	MulticastListenerDone,
+
+	/// Router Solicitation.
+	RouterSolicitation,
+
+	/// Router Advertisement.
+	RouterAdvertisement,
+
+	/// Neighbor Solicitation.
+	NeighborSolicitation,
+
+	/// Neighbor Advertisement.
+	NeighborAdvertisement,
+
+	/// Redirect.
+	Redirect,
+
+	/// Router Renumbering.
+	RouterRenumbering,
+
+	/// ICMP Node Information Query.
+	NodeInformationQuery,
+
+	/// ICMP Node Information Response.
+	NodeInformationResponse,
+
+	/// Inverse Neighbor Discovery Solicitation.
+	InverseNeighborDiscoverySolicitation,
+
+	/// Inverse Neighbor Discovery Advertisement.
+	InverseNeighborDiscoveryAdvertisement,
+
+	/// Version 2 Multicast Listener Report.
+	MulticastListenerReportV2,
+
+	/// Unknown type.
+	Unknown(u8),
+}
+
+impl Kind {
+	/// Check if the type is an error message.
+	pub fn is_error(&self) -> bool {
+		let value: u8 = (*self).into();
+		value < 128
+	}
+}
+
+impl From<u8> for Kind {
+	fn from(value: u8) -> Kind {
+		use self::Kind::*;
+
+		match value {
+			1   => DestinationUnreachable,
+			2   => PacketTooBig,
+			3   => TimeExceeded,
+			4   => ParameterProblem,
+			128 => EchoRequest,
+			129 => EchoReply,
+			130 => MulticastListenerQuery,
+			131 => MulticastListenerReport,
+			132 => MulticastListenerDone,
+			133 => RouterSolicitation,
+			134 => RouterAdvertisement,
+			135 => NeighborSolicitation,
+			136 => NeighborAdvertisement,
+			137 => Redirect,
+			138 => RouterRenumbering,
+			139 => NodeInformationQuery,
+			140 => NodeInformationResponse,
+			141 => InverseNeighborDiscoverySolicitation,
+			142 => InverseNeighborDiscoveryAdvertisement,
+			143 => MulticastListenerReportV2,
+			v   => Unknown(v),
+		}
+	}
+}
+
+impl From<Kind> for u8 {
+	fn from(value: Kind) -> u8 {
+		use self::Kind::*;
+
+		match value {
+			DestinationUnreachable                => 1,
+			PacketTooBig                          => 2,
+			TimeExceeded                          => 3,
+			ParameterProblem                      => 4,
+			EchoRequest                           => 128,
+			EchoReply                             => 129,
+			MulticastListenerQuery                => 130,
+			MulticastListenerReport               => 131,
+			MulticastListenerDone                 => 132,
+			RouterSolicitation                    => 133,
+			RouterAdvertisement                   => 134,
+			NeighborSolicitation                  => 135,
+			NeighborAdvertisement                 => 136,
+			Redirect                              => 137,
+			RouterRenumbering                     => 138,
+			NodeInformationQuery                  => 139,
+			NodeInformationResponse               => 140,
+			InverseNeighborDiscoverySolicitation  => 141,
+			InverseNeighborDiscoveryAdvertisement => 142,
+			MulticastListenerReportV2             => 143,
+			Unknown(v)                            => v,
+		}
+	}
+}
@@ -0,0 +1,106 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+mod kind;
+pub use self::kind::Kind;
+
+/// ICMPv6 codes.
+pub mod code;
+
+mod packet;
+pub use self::packet::Packet;
+
+mod builder;
+pub use self::builder::Builder;
+
+/// Echo Request/Reply.
+pub mod echo;
+
+/// Destination Unreachable and Time Exceeded.
+pub mod previous;
+
+/// Packet Too Big.
+pub mod packet_too_big;
+
+/// Parameter Problem.
+pub mod parameter_problem;
+
+/// Neighbor Discovery options.
+pub mod option;
+pub use self::option::Option;
+
+/// Router Solicitation.
+pub mod router_solicitation;
+
+/// Router Advertisement.
+pub mod router_advertisement;
+
+/// Neighbor Solicitation/Advertisement.
+pub mod neighbor;
+
+/// Redirect.
+pub mod redirect;
+
+use crate::ip;
+use crate::ip::Protocol;
+
+/// Calculate the checksum for an ICMPv6 packet.
+///
+/// # Note
+///
+/// Since the checksum for ICMPv6 packets includes a pseudo-header based on
+/// the enclosing IPv6 packet, one has to be given.
+pub fn checksum<B: AsRef<[u8]>>(ip: &ip::v6::Packet<B>, buffer: &[u8]) -> u16 {
+	use std::io::Cursor;
+	use byteorder::{ReadBytesExt, BigEndian};
+
+	let packet           = ip::Packet::V6(ip::v6::Packet::unchecked(ip.as_ref()));
+	let (prefix, length) = ip::pseudo_header(&packet, Protocol::Ipv6Icmp, buffer.len());
+
+	let mut result = 0x0000u32;
+	let mut prefix = Cursor::new(&prefix[.. length]);
+	let mut buffer = Cursor::new(buffer);
+
+	while let Ok(value) = prefix.read_u16::<BigEndian>() {
+		result += u32::from(value);
+
+		if result > 0xffff {
+			result -= 0xffff;
+		}
+	}
+
+	while let Ok(value) = buffer.read_u16::<BigEndian>() {
+		// Skip checksum field.
+		if buffer.position() == 4 {
+			continue;
+		}
+
+		result += u32::from(value);
+
+		if result > 0xffff {
+			result -= 0xffff;
+		}
+	}
+
+	// Pad the trailing byte, if any.
+	if buffer.get_ref().len() % 2 == 1 {
+		result += u32::from(*buffer.get_ref().last().unwrap()) << 8;
+
+		if result > 0xffff {
+			result -= 0xffff;
+		}
+	}
+
+	!result as u16
+}
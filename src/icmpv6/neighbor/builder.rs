@@ -0,0 +1,135 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::net::Ipv6Addr;
+use hwaddr::HwAddr;
+
+use crate::error::*;
+use crate::buffer::{self, Buffer};
+use crate::builder::{Builder as Build, Finalization};
+use crate::packet::{AsPacket, AsPacketMut};
+use crate::icmpv6::builder;
+use crate::icmpv6::option::{self, Value};
+use crate::icmpv6::neighbor::Packet;
+
+/// Neighbor Solicitation/Advertisement packet builder.
+#[derive(Debug)]
+pub struct Builder<B: Buffer = buffer::Dynamic> {
+	buffer:    B,
+	finalizer: Finalization,
+
+	ip:   (usize, usize),
+	kind: bool,
+}
+
+impl<B: Buffer> Build<B> for Builder<B> {
+	fn with(mut buffer: B) -> Result<Self> {
+		let ip = (buffer.offset(), buffer.length());
+		buffer.next(24)?;
+
+		Ok(Builder {
+			buffer,
+			finalizer: Default::default(),
+
+			ip,
+			kind: false,
+		})
+	}
+
+	fn finalizer(&mut self) -> &mut Finalization {
+		&mut self.finalizer
+	}
+
+	fn build(mut self) -> Result<B::Inner> {
+		if !self.kind {
+			Err(Error::InvalidPacket)?
+		}
+
+		builder::prepare(&mut self.finalizer, self.ip, &self.buffer);
+
+		let mut buffer = self.buffer.into_inner();
+		self.finalizer.finalize(buffer.as_mut())?;
+		Ok(buffer)
+	}
+}
+
+impl<'a, B: Buffer> AsPacket<'a, Packet<&'a [u8]>> for Builder<B> {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.buffer.data())
+	}
+}
+
+impl<'a, B: Buffer> AsPacketMut<'a, Packet<&'a mut [u8]>> for Builder<B> {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.buffer.data_mut())
+	}
+}
+
+impl<B: Buffer> Builder<B> {
+	/// Make it a solicitation.
+	pub fn solicitation(mut self) -> Result<Self> {
+		self.kind = true;
+		Packet::unchecked(self.buffer.data_mut()).make_solicitation()?;
+
+		Ok(self)
+	}
+
+	/// Make it an advertisement.
+	pub fn advertisement(mut self) -> Result<Self> {
+		self.kind = true;
+		Packet::unchecked(self.buffer.data_mut()).make_advertisement()?;
+
+		Ok(self)
+	}
+
+	/// The sender is a router.
+	pub fn router(mut self, value: bool) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_router(value)?;
+		Ok(self)
+	}
+
+	/// The advertisement was sent in response to a solicitation.
+	pub fn solicited(mut self, value: bool) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_solicited(value)?;
+		Ok(self)
+	}
+
+	/// The advertisement should override an existing cache entry.
+	pub fn overrides(mut self, value: bool) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_override(value)?;
+		Ok(self)
+	}
+
+	/// Target address of the solicitation or advertisement.
+	pub fn target(mut self, value: Ipv6Addr) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_target(value)?;
+		Ok(self)
+	}
+
+	/// Add a Source Link-Layer Address option.
+	pub fn source_link_layer_address(self, value: HwAddr) -> Result<Self> {
+		self.option(Value::SourceLinkLayerAddress(value))
+	}
+
+	/// Add a Target Link-Layer Address option.
+	pub fn target_link_layer_address(self, value: HwAddr) -> Result<Self> {
+		self.option(Value::TargetLinkLayerAddress(value))
+	}
+
+	/// Add a Neighbor Discovery option.
+	pub fn option(mut self, value: Value) -> Result<Self> {
+		option::append(&mut self.buffer, &value)?;
+		Ok(self)
+	}
+}
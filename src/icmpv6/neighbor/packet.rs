@@ -0,0 +1,223 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::net::Ipv6Addr;
+
+use crate::error::*;
+use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::icmpv6::Kind;
+use crate::icmpv6::option::OptionIter;
+
+/// Neighbor Solicitation/Advertisement packet parser.
+pub struct Packet<B> {
+	buffer: B,
+}
+
+sized!(Packet,
+	header {
+		min:  24,
+		max:  24,
+		size: 24,
+	}
+
+	payload {
+		min:  0,
+		size: p => p.buffer.as_ref().len() - 24,
+	});
+
+impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("icmpv6::neighbor::Packet")
+			.field("solicitation", &self.is_solicitation())
+			.field("is_router", &self.is_router())
+			.field("is_solicited", &self.is_solicited())
+			.field("is_override", &self.is_override())
+			.field("target", &self.target())
+			.field("options", &self.options().collect::<Vec<_>>())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Create a Neighbor Solicitation/Advertisement packet without checking the buffer.
+	pub fn unchecked(buffer: B) -> Packet<B> {
+		Packet { buffer }
+	}
+
+	/// Parse a Neighbor Solicitation/Advertisement packet, checking the buffer contents are
+	/// correct.
+	pub fn new(buffer: B) -> Result<Packet<B>> {
+		use crate::size::header::Min;
+
+		let packet = Packet::unchecked(buffer);
+
+		if packet.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		match Kind::from(packet.buffer.as_ref()[0]) {
+			Kind::NeighborSolicitation |
+			Kind::NeighborAdvertisement =>
+				(),
+
+			_ =>
+				Err(Error::InvalidPacket)?
+		}
+
+		Ok(packet)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Convert the packet to its owned version.
+	///
+	/// # Notes
+	///
+	/// It would be nice if `ToOwned` could be implemented, but `Packet` already
+	/// implements `Clone` and the impl would conflict.
+	pub fn to_owned(&self) -> Packet<Vec<u8>> {
+		Packet::unchecked(self.buffer.as_ref().to_vec())
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
+	fn as_ref(&self) -> &[u8] {
+		use crate::size::Size;
+
+		&self.buffer.as_ref()[.. self.size()]
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
+	fn as_mut(&mut self) -> &mut [u8] {
+		use crate::size::Size;
+
+		let size = self.size();
+		&mut self.buffer.as_mut()[.. size]
+	}
+}
+
+impl<'a, B: AsRef<[u8]>> AsPacket<'a, Packet<&'a [u8]>> for B {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.as_ref())
+	}
+}
+
+impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Packet<&'a mut [u8]>> for B {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.as_mut())
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Packet<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		self.buffer.as_ref().split_at(24)
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Packet<B> {
+	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+		self.buffer.as_mut().split_at_mut(24)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Check if it's a Solicitation packet.
+	pub fn is_solicitation(&self) -> bool {
+		Kind::from(self.buffer.as_ref()[0]) == Kind::NeighborSolicitation
+	}
+
+	/// Check if it's an Advertisement packet.
+	pub fn is_advertisement(&self) -> bool {
+		Kind::from(self.buffer.as_ref()[0]) == Kind::NeighborAdvertisement
+	}
+
+	/// The sender is a router, only meaningful for advertisements.
+	pub fn is_router(&self) -> bool {
+		self.buffer.as_ref()[4] & 0x80 != 0
+	}
+
+	/// The advertisement was sent in response to a solicitation.
+	pub fn is_solicited(&self) -> bool {
+		self.buffer.as_ref()[4] & 0x40 != 0
+	}
+
+	/// The advertisement should override an existing cache entry.
+	pub fn is_override(&self) -> bool {
+		self.buffer.as_ref()[4] & 0x20 != 0
+	}
+
+	/// Target address of the solicitation or advertisement.
+	pub fn target(&self) -> Ipv6Addr {
+		let mut octets = [0u8; 16];
+		octets.copy_from_slice(&self.buffer.as_ref()[8 .. 24]);
+
+		octets.into()
+	}
+
+	/// Neighbor Discovery options.
+	pub fn options(&self) -> OptionIter<'_> {
+		OptionIter::new(&self.buffer.as_ref()[24 ..])
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
+	/// Make the packet a Neighbor Solicitation.
+	pub fn make_solicitation(&mut self) -> Result<&mut Self> {
+		self.buffer.as_mut()[0] = Kind::NeighborSolicitation.into();
+
+		Ok(self)
+	}
+
+	/// Make the packet a Neighbor Advertisement.
+	pub fn make_advertisement(&mut self) -> Result<&mut Self> {
+		self.buffer.as_mut()[0] = Kind::NeighborAdvertisement.into();
+
+		Ok(self)
+	}
+
+	/// The sender is a router.
+	pub fn set_router(&mut self, value: bool) -> Result<&mut Self> {
+		self.set_flag(0x80, value)
+	}
+
+	/// The advertisement was sent in response to a solicitation.
+	pub fn set_solicited(&mut self, value: bool) -> Result<&mut Self> {
+		self.set_flag(0x40, value)
+	}
+
+	/// The advertisement should override an existing cache entry.
+	pub fn set_override(&mut self, value: bool) -> Result<&mut Self> {
+		self.set_flag(0x20, value)
+	}
+
+	/// Target address of the solicitation or advertisement.
+	pub fn set_target(&mut self, value: Ipv6Addr) -> Result<&mut Self> {
+		self.buffer.as_mut()[8 .. 24].copy_from_slice(&value.octets());
+
+		Ok(self)
+	}
+
+	fn set_flag(&mut self, flag: u8, value: bool) -> Result<&mut Self> {
+		if value {
+			self.buffer.as_mut()[4] |= flag;
+		}
+		else {
+			self.buffer.as_mut()[4] &= !flag;
+		}
+
+		Ok(self)
+	}
+}
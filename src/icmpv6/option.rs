@@ -0,0 +1,576 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::net::Ipv6Addr;
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
+use hwaddr::HwAddr;
+
+use crate::error::*;
+use crate::buffer::Buffer;
+use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+
+/// Neighbor Discovery option parser.
+pub struct Option<B> {
+	buffer: B,
+}
+
+sized!(Option,
+	header {
+		min:  2,
+		max:  2,
+		size: 2,
+	}
+
+	payload {
+		min:  0,
+		max:  255 * 8 - 2,
+		size: p => (p.length() as usize * 8).saturating_sub(2),
+	});
+
+/// Neighbor Discovery option number.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Number {
+	/// Source Link-layer Address.
+	SourceLinkLayerAddress,
+
+	/// Target Link-layer Address.
+	TargetLinkLayerAddress,
+
+	/// Prefix Information.
+	PrefixInformation,
+
+	/// Redirected Header.
+	RedirectedHeader,
+
+	/// MTU.
+	Mtu,
+
+	/// Recursive DNS Server.
+	RecursiveDnsServer,
+
+	/// DNS Search List.
+	DnsSearchList,
+
+	/// Unknown option.
+	Unknown(u8),
+}
+
+/// Decoded Neighbor Discovery option.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum Value {
+	/// Link-layer address of the sender.
+	SourceLinkLayerAddress(HwAddr),
+
+	/// Link-layer address of the target.
+	TargetLinkLayerAddress(HwAddr),
+
+	/// Prefix for on-link determination and address autoconfiguration.
+	PrefixInformation {
+		/// Number of leading bits of the prefix that are valid.
+		length: u8,
+
+		/// The prefix can be used for on-link determination.
+		on_link: bool,
+
+		/// The prefix can be used for address autoconfiguration.
+		autonomous: bool,
+
+		/// Seconds the prefix is valid for on-link determination.
+		valid_lifetime: u32,
+
+		/// Seconds addresses generated from the prefix remain preferred.
+		preferred_lifetime: u32,
+
+		/// The prefix.
+		prefix: Ipv6Addr,
+	},
+
+	/// Leading part of the packet being redirected.
+	RedirectedHeader(Vec<u8>),
+
+	/// Link MTU.
+	Mtu(u32),
+
+	/// Recursive DNS servers.
+	RecursiveDnsServer {
+		/// Seconds the servers can be used for.
+		lifetime: u32,
+
+		/// Server addresses.
+		servers: Vec<Ipv6Addr>,
+	},
+
+	/// DNS search list.
+	DnsSearchList {
+		/// Seconds the domains can be used for.
+		lifetime: u32,
+
+		/// Domain names.
+		domains: Vec<String>,
+	},
+
+	/// Option without a known decoding, with its payload.
+	Unknown(u8, Vec<u8>),
+}
+
+impl<B: AsRef<[u8]>> fmt::Debug for Option<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("icmpv6::Option")
+			.field("number", &self.number())
+			.field("length", &self.length())
+			.field("payload", &self.payload())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> Option<B> {
+	/// Parse a Neighbor Discovery option, checking the buffer contents are
+	/// correct.
+	pub fn new(buffer: B) -> Result<Option<B>> {
+		use crate::size::header::Min;
+
+		let option = Option {
+			buffer,
+		};
+
+		if option.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		if option.length() == 0 {
+			Err(Error::InvalidPacket)?
+		}
+
+		if option.buffer.as_ref().len() < option.length() as usize * 8 {
+			Err(Error::SmallBuffer)?
+		}
+
+		Ok(option)
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Option<B> {
+	fn as_ref(&self) -> &[u8] {
+		use crate::size::Size;
+
+		&self.buffer.as_ref()[.. self.size()]
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Option<B> {
+	fn as_mut(&mut self) -> &mut [u8] {
+		use crate::size::Size;
+
+		let size = self.size();
+		&mut self.buffer.as_mut()[.. size]
+	}
+}
+
+impl<'a, B: AsRef<[u8]>> AsPacket<'a, Option<&'a [u8]>> for B {
+	fn as_packet(&self) -> Result<Option<&[u8]>> {
+		Option::new(self.as_ref())
+	}
+}
+
+impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Option<&'a mut [u8]>> for B {
+	fn as_packet_mut(&mut self) -> Result<Option<&mut [u8]>> {
+		Option::new(self.as_mut())
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Option<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		let length = self.length() as usize * 8;
+		self.buffer.as_ref()[.. length].split_at(2)
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Option<B> {
+	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+		let length = self.length() as usize * 8;
+		self.buffer.as_mut()[.. length].split_at_mut(2)
+	}
+}
+
+impl<B: AsRef<[u8]>> Option<B> {
+	/// Option number.
+	pub fn number(&self) -> Number {
+		self.buffer.as_ref()[0].into()
+	}
+
+	/// Option length in 8 octet units, including the header.
+	pub fn length(&self) -> u8 {
+		self.buffer.as_ref()[1]
+	}
+
+	/// Decode the option value.
+	pub fn value(&self) -> Result<Value> {
+		let payload = self.payload();
+
+		Ok(match self.number() {
+			Number::SourceLinkLayerAddress =>
+				Value::SourceLinkLayerAddress(address(payload)?),
+
+			Number::TargetLinkLayerAddress =>
+				Value::TargetLinkLayerAddress(address(payload)?),
+
+			Number::PrefixInformation => {
+				if payload.len() < 30 {
+					Err(Error::InvalidPacket)?
+				}
+
+				let mut prefix = [0u8; 16];
+				prefix.copy_from_slice(&payload[14 .. 30]);
+
+				Value::PrefixInformation {
+					length:             payload[0],
+					on_link:            payload[1] & 0x80 != 0,
+					autonomous:         payload[1] & 0x40 != 0,
+					valid_lifetime:     (&payload[2 ..]).read_u32::<BigEndian>()?,
+					preferred_lifetime: (&payload[6 ..]).read_u32::<BigEndian>()?,
+					prefix:             prefix.into(),
+				}
+			}
+
+			Number::RedirectedHeader => {
+				if payload.len() < 6 {
+					Err(Error::InvalidPacket)?
+				}
+
+				Value::RedirectedHeader(payload[6 ..].to_vec())
+			}
+
+			Number::Mtu =>
+				Value::Mtu((&payload[2 ..]).read_u32::<BigEndian>()?),
+
+			Number::RecursiveDnsServer => {
+				if payload.len() < 6 || !(payload.len() - 6).is_multiple_of(16) {
+					Err(Error::InvalidPacket)?
+				}
+
+				let servers = payload[6 ..].chunks(16).map(|chunk| {
+					let mut octets = [0u8; 16];
+					octets.copy_from_slice(chunk);
+					Ipv6Addr::from(octets)
+				}).collect();
+
+				Value::RecursiveDnsServer {
+					lifetime: (&payload[2 ..]).read_u32::<BigEndian>()?,
+					servers,
+				}
+			}
+
+			Number::DnsSearchList => {
+				if payload.len() < 6 {
+					Err(Error::InvalidPacket)?
+				}
+
+				Value::DnsSearchList {
+					lifetime: (&payload[2 ..]).read_u32::<BigEndian>()?,
+					domains:  domains(&payload[6 ..])?,
+				}
+			}
+
+			Number::Unknown(number) =>
+				Value::Unknown(number, payload.to_vec()),
+		})
+	}
+}
+
+fn address(payload: &[u8]) -> Result<HwAddr> {
+	if payload.len() < 6 {
+		Err(Error::InvalidPacket)?
+	}
+
+	Ok(payload[.. 6].into())
+}
+
+fn domains(mut buffer: &[u8]) -> Result<Vec<String>> {
+	let mut domains = Vec::new();
+	let mut labels  = Vec::new();
+
+	while let Some((&length, rest)) = buffer.split_first() {
+		let length = length as usize;
+
+		// A zero length terminates the domain, any trailing one is padding.
+		if length == 0 {
+			if !labels.is_empty() {
+				domains.push(labels.join("."));
+				labels.clear();
+			}
+
+			buffer = rest;
+			continue;
+		}
+
+		// Compression is not allowed, so the length has to fit in 6 bits.
+		if length > 63 || rest.len() < length {
+			Err(Error::InvalidPacket)?
+		}
+
+		labels.push(String::from_utf8(rest[.. length].to_vec())
+			.map_err(|_| Error::InvalidPacket)?);
+
+		buffer = &rest[length ..];
+	}
+
+	if !labels.is_empty() {
+		Err(Error::InvalidPacket)?
+	}
+
+	Ok(domains)
+}
+
+impl Value {
+	/// Option number for the value.
+	pub fn number(&self) -> Number {
+		match *self {
+			Value::SourceLinkLayerAddress(..) => Number::SourceLinkLayerAddress,
+			Value::TargetLinkLayerAddress(..) => Number::TargetLinkLayerAddress,
+			Value::PrefixInformation { .. }   => Number::PrefixInformation,
+			Value::RedirectedHeader(..)       => Number::RedirectedHeader,
+			Value::Mtu(..)                    => Number::Mtu,
+			Value::RecursiveDnsServer { .. }  => Number::RecursiveDnsServer,
+			Value::DnsSearchList { .. }       => Number::DnsSearchList,
+			Value::Unknown(number, ..)        => Number::Unknown(number),
+		}
+	}
+
+	/// Encode the option payload, without padding.
+	fn payload(&self) -> Result<Vec<u8>> {
+		let mut payload = Vec::new();
+
+		match *self {
+			Value::SourceLinkLayerAddress(address) |
+			Value::TargetLinkLayerAddress(address) =>
+				payload.extend_from_slice(&address.octets()),
+
+			Value::PrefixInformation { length, on_link, autonomous, valid_lifetime, preferred_lifetime, prefix } => {
+				if length > 128 {
+					Err(Error::InvalidValue)?
+				}
+
+				let mut flags = 0;
+
+				if on_link {
+					flags |= 0x80;
+				}
+
+				if autonomous {
+					flags |= 0x40;
+				}
+
+				payload.push(length);
+				payload.push(flags);
+				payload.write_u32::<BigEndian>(valid_lifetime)?;
+				payload.write_u32::<BigEndian>(preferred_lifetime)?;
+				payload.write_u32::<BigEndian>(0)?;
+				payload.extend_from_slice(&prefix.octets());
+			}
+
+			Value::RedirectedHeader(ref packet) => {
+				payload.extend_from_slice(&[0; 6]);
+				payload.extend_from_slice(packet);
+			}
+
+			Value::Mtu(mtu) => {
+				payload.write_u16::<BigEndian>(0)?;
+				payload.write_u32::<BigEndian>(mtu)?;
+			}
+
+			Value::RecursiveDnsServer { lifetime, ref servers } => {
+				payload.write_u16::<BigEndian>(0)?;
+				payload.write_u32::<BigEndian>(lifetime)?;
+
+				for server in servers {
+					payload.extend_from_slice(&server.octets());
+				}
+			}
+
+			Value::DnsSearchList { lifetime, ref domains } => {
+				payload.write_u16::<BigEndian>(0)?;
+				payload.write_u32::<BigEndian>(lifetime)?;
+
+				for domain in domains {
+					for label in domain.split('.').filter(|l| !l.is_empty()) {
+						if label.len() > 63 {
+							Err(Error::InvalidValue)?
+						}
+
+						payload.push(label.len() as u8);
+						payload.extend_from_slice(label.as_bytes());
+					}
+
+					payload.push(0);
+				}
+			}
+
+			Value::Unknown(_, ref value) =>
+				payload.extend_from_slice(value),
+		}
+
+		Ok(payload)
+	}
+}
+
+/// Append an option to the current layer of the buffer, padding it to a
+/// multiple of 8 octets.
+pub(in crate::icmpv6) fn append<B: Buffer>(buffer: &mut B, value: &Value) -> Result<()> {
+	let payload = value.payload()?;
+	let length  = (payload.len() + 2).div_ceil(8);
+
+	if length > u8::MAX as usize {
+		Err(Error::InvalidValue)?
+	}
+
+	let offset = buffer.length();
+	buffer.more(length * 8)?;
+
+	let data = &mut buffer.data_mut()[offset ..];
+	data[0] = value.number().into();
+	data[1] = length as u8;
+	data[2 .. 2 + payload.len()].copy_from_slice(&payload);
+
+	Ok(())
+}
+
+impl From<u8> for Number {
+	fn from(value: u8) -> Self {
+		use self::Number::*;
+
+		match value {
+			1  => SourceLinkLayerAddress,
+			2  => TargetLinkLayerAddress,
+			3  => PrefixInformation,
+			4  => RedirectedHeader,
+			5  => Mtu,
+			25 => RecursiveDnsServer,
+			31 => DnsSearchList,
+			n  => Unknown(n),
+		}
+	}
+}
+
+impl From<Number> for u8 {
+	fn from(value: Number) -> u8 {
+		use self::Number::*;
+
+		match value {
+			SourceLinkLayerAddress => 1,
+			TargetLinkLayerAddress => 2,
+			PrefixInformation      => 3,
+			RedirectedHeader       => 4,
+			Mtu                    => 5,
+			RecursiveDnsServer     => 25,
+			DnsSearchList          => 31,
+			Unknown(n)             => n,
+		}
+	}
+}
+
+/// Iterator over Neighbor Discovery options.
+pub struct OptionIter<'a> {
+	buffer: &'a [u8],
+}
+
+impl<'a> OptionIter<'a> {
+	pub(in crate::icmpv6) fn new(buffer: &'a [u8]) -> Self {
+		OptionIter {
+			buffer,
+		}
+	}
+}
+
+impl<'a> Iterator for OptionIter<'a> {
+	type Item = Result<Option<&'a [u8]>>;
+
+	fn next(&mut self) -> std::option::Option<Self::Item> {
+		if self.buffer.is_empty() {
+			return None;
+		}
+
+		match Option::new(self.buffer) {
+			Ok(option) => {
+				self.buffer = &self.buffer[option.length() as usize * 8 ..];
+				Some(Ok(option))
+			}
+
+			Err(error) => {
+				self.buffer = &[];
+				Some(Err(error))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::Ipv6Addr;
+	use crate::buffer::{self, Buffer};
+	use crate::icmpv6::option::{self, Value, Number, OptionIter};
+
+	#[test]
+	fn values() {
+		let values = vec![
+			Value::SourceLinkLayerAddress("00:23:69:63:59:be".parse().unwrap()),
+			Value::PrefixInformation {
+				length:             64,
+				on_link:            true,
+				autonomous:         true,
+				valid_lifetime:     86400,
+				preferred_lifetime: 14400,
+				prefix:             "2001:db8::".parse().unwrap(),
+			},
+			Value::Mtu(1500),
+			Value::RecursiveDnsServer {
+				lifetime: 600,
+				servers:  vec!["2001:db8::53".parse().unwrap()],
+			},
+			Value::DnsSearchList {
+				lifetime: 600,
+				domains:  vec!["example.com".into(), "lab".into()],
+			},
+			Value::Unknown(42, vec![1, 2, 3, 4, 5, 6]),
+		];
+
+		let mut buffer = buffer::Dynamic::default();
+		buffer.next(0).unwrap();
+
+		for value in &values {
+			option::append(&mut buffer, value).unwrap();
+		}
+
+		let buffer  = buffer.into_inner();
+		let options = OptionIter::new(&buffer).collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(options.iter().map(|o| o.length()).collect::<Vec<_>>(), vec![1, 4, 1, 3, 4, 1]);
+		assert_eq!(options[2].number(), Number::Mtu);
+
+		let decoded = options.iter().map(|o| o.value()).collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(decoded, values);
+
+		if let Value::RecursiveDnsServer { ref servers, .. } = decoded[3] {
+			assert_eq!(servers[0], "2001:db8::53".parse::<Ipv6Addr>().unwrap());
+		}
+	}
+
+	#[test]
+	fn zero_length() {
+		let raw      = [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+		let mut iter = OptionIter::new(&raw);
+
+		assert!(iter.next().unwrap().is_err());
+		assert!(iter.next().is_none());
+	}
+}
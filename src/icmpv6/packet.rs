@@ -0,0 +1,321 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::io::Cursor;
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
+
+use crate::error::*;
+use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::ip;
+use crate::icmpv6::Kind;
+use crate::icmpv6::checksum;
+
+/// ICMPv6 packet parser.
+pub struct Packet<B> {
+	buffer: B,
+}
+
+sized!(Packet,
+	header {
+		min:  4,
+		max:  4,
+		size: 4,
+	}
+
+	payload {
+		min:  0,
+		size: p => p.buffer.as_ref().len() - 4,
+	});
+
+impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("icmpv6::Packet")
+			.field("kind", &self.kind())
+			.field("code", &self.code())
+			.field("checksum", &self.checksum())
+			.field("payload", &self.payload())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Ok(echo) = self.echo() {
+			write!(f, "ICMP6, echo {}, id {}, seq {}",
+				if echo.is_request() { "request" } else { "reply" },
+				echo.identifier(), echo.sequence())?;
+		}
+		else if let Ok(neighbor) = self.neighbor() {
+			if neighbor.is_solicitation() {
+				write!(f, "ICMP6, neighbor solicitation, who has {}", neighbor.target())?;
+			}
+			else {
+				write!(f, "ICMP6, neighbor advertisement, tgt is {}", neighbor.target())?;
+			}
+		}
+		else if let Kind::Unknown(value) = self.kind() {
+			write!(f, "ICMP6, unknown icmp6 type ({}), code {}", value, self.code())?;
+		}
+		else {
+			write!(f, "ICMP6, {}, code {}", name(self.kind()), self.code())?;
+		}
+
+		write!(f, ", length {}", self.buffer.as_ref().len())
+	}
+}
+
+/// Name of the type as printed by tcpdump.
+fn name(kind: Kind) -> &'static str {
+	match kind {
+		Kind::DestinationUnreachable                => "destination unreachable",
+		Kind::PacketTooBig                          => "packet too big",
+		Kind::TimeExceeded                          => "time exceeded in-transit",
+		Kind::ParameterProblem                      => "parameter problem",
+		Kind::EchoRequest                           => "echo request",
+		Kind::EchoReply                             => "echo reply",
+		Kind::MulticastListenerQuery                => "multicast listener query",
+		Kind::MulticastListenerReport               => "multicast listener report",
+		Kind::MulticastListenerDone                 => "multicast listener done",
+		Kind::RouterSolicitation                    => "router solicitation",
+		Kind::RouterAdvertisement                   => "router advertisement",
+		Kind::NeighborSolicitation                  => "neighbor solicitation",
+		Kind::NeighborAdvertisement                 => "neighbor advertisement",
+		Kind::Redirect                              => "redirect",
+		Kind::RouterRenumbering                     => "router renumbering",
+		Kind::NodeInformationQuery                  => "who-are-you request",
+		Kind::NodeInformationResponse               => "who-are-you reply",
+		Kind::InverseNeighborDiscoverySolicitation  => "inverse neighbor solicitation",
+		Kind::InverseNeighborDiscoveryAdvertisement => "inverse neighbor advertisement",
+		Kind::MulticastListenerReportV2             => "multicast listener report v2",
+		Kind::Unknown(_)                            => "unknown",
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Create an ICMPv6 packet without checking the buffer.
+	pub fn unchecked(buffer: B) -> Packet<B> {
+		Packet { buffer }
+	}
+
+	/// Parse an ICMPv6 packet, checking the buffer contents are correct.
+	pub fn new(buffer: B) -> Result<Packet<B>> {
+		use crate::size::header::Min;
+
+		let packet = Packet::unchecked(buffer);
+
+		if packet.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		Ok(packet)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Convert the packet to its owned version.
+	///
+	/// # Notes
+	///
+	/// It would be nice if `ToOwned` could be implemented, but `Packet` already
+	/// implements `Clone` and the impl would conflict.
+	pub fn to_owned(&self) -> Packet<Vec<u8>> {
+		Packet::unchecked(self.buffer.as_ref().to_vec())
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
+	fn as_ref(&self) -> &[u8] {
+		use crate::size::Size;
+
+		&self.buffer.as_ref()[.. self.size()]
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
+	fn as_mut(&mut self) -> &mut [u8] {
+		use crate::size::Size;
+
+		let size = self.size();
+		&mut self.buffer.as_mut()[.. size]
+	}
+}
+
+impl<'a, B: AsRef<[u8]>> AsPacket<'a, Packet<&'a [u8]>> for B {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.as_ref())
+	}
+}
+
+impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Packet<&'a mut [u8]>> for B {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.as_mut())
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Packet<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		self.buffer.as_ref().split_at(4)
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Packet<B> {
+	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+		self.buffer.as_mut().split_at_mut(4)
+	}
+}
+
+macro_rules! kind {
+	($(#[$attr:meta])* fn $module:ident[$mutable:ident]) => (
+		$(#[$attr])*
+		pub fn $module(&self) -> Result<crate::icmpv6::$module::Packet<&B>> {
+			crate::icmpv6::$module::Packet::new(&self.buffer)
+		}
+
+		$(#[$attr])*
+		pub fn $mutable(&mut self) -> Result<crate::icmpv6::$module::Packet<&mut B>> {
+			crate::icmpv6::$module::Packet::new(&mut self.buffer)
+		}
+	)
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Packet type.
+	pub fn kind(&self) -> Kind {
+		Kind::from(self.buffer.as_ref()[0])
+	}
+
+	/// Packet code.
+	pub fn code(&self) -> u8 {
+		self.buffer.as_ref()[1]
+	}
+
+	/// Packet checksum.
+	pub fn checksum(&self) -> u16 {
+		(&self.buffer.as_ref()[2 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Verify the packet is valid by calculating the checksum with the
+	/// pseudo-header from the enclosing IPv6 packet.
+	pub fn is_valid<I: AsRef<[u8]>>(&self, ip: &ip::v6::Packet<I>) -> bool {
+		checksum(ip, self.buffer.as_ref()) == self.checksum()
+	}
+
+	kind!(/// Parse an Echo Request/Reply packet.
+		fn echo[echo_mut]);
+
+	kind!(/// Parse a Destination Unreachable or Time Exceeded packet.
+		fn previous[previous_mut]);
+
+	kind!(/// Parse a Packet Too Big packet.
+		fn packet_too_big[packet_too_big_mut]);
+
+	kind!(/// Parse a Parameter Problem packet.
+		fn parameter_problem[parameter_problem_mut]);
+
+	kind!(/// Parse a Router Solicitation packet.
+		fn router_solicitation[router_solicitation_mut]);
+
+	kind!(/// Parse a Router Advertisement packet.
+		fn router_advertisement[router_advertisement_mut]);
+
+	kind!(/// Parse a Neighbor Solicitation/Advertisement packet.
+		fn neighbor[neighbor_mut]);
+
+	kind!(/// Parse a Redirect packet.
+		fn redirect[redirect_mut]);
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
+	/// Recalculate and set the checksum with the pseudo-header from the
+	/// enclosing IPv6 packet.
+	pub fn update_checksum<I: AsRef<[u8]>>(&mut self, ip: &ip::v6::Packet<I>) -> Result<&mut Self> {
+		let checksum = checksum(ip, self.buffer.as_ref());
+		Cursor::new(&mut self.buffer.as_mut()[2 ..])
+			.write_u16::<BigEndian>(checksum)?;
+
+		Ok(self)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::packet::Packet;
+	use crate::ip;
+	use crate::icmpv6;
+
+	#[test]
+	fn echo() {
+		let raw = [
+			// IPv6
+			0x60u8, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x3a, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+			// ICMPv6
+			0x80, 0x00, 0x3c, 0x3e, 0x00, 0x2a, 0x00, 0x02, 0x74, 0x65, 0x73, 0x74,
+		];
+
+		let ip = ip::v6::Packet::new(&raw[..]).unwrap();
+		assert_eq!(ip.next_header(), ip::Protocol::Ipv6Icmp);
+
+		let icmp = icmpv6::Packet::new(ip.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmpv6::Kind::EchoRequest);
+		assert_eq!(icmp.code(), 0);
+		assert!(icmp.is_valid(&ip));
+
+		let echo = icmp.echo().unwrap();
+		assert!(echo.is_request());
+		assert_eq!(echo.identifier(), 42);
+		assert_eq!(echo.sequence(), 2);
+		assert_eq!(echo.payload(), b"test");
+
+		assert_eq!(icmp.to_string(), "ICMP6, echo request, id 42, seq 2, length 12");
+	}
+
+	#[test]
+	fn display() {
+		let raw = [
+			0x87u8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+		];
+
+		assert_eq!(icmpv6::Packet::new(&raw[..]).unwrap().to_string(),
+			"ICMP6, neighbor solicitation, who has 2001:db8::2, length 24");
+
+		let raw = [0x85u8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+		assert_eq!(icmpv6::Packet::new(&raw[..]).unwrap().to_string(),
+			"ICMP6, router solicitation, code 0, length 8");
+
+		let raw = [0x2au8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+		assert_eq!(icmpv6::Packet::new(&raw[..]).unwrap().to_string(),
+			"ICMP6, unknown icmp6 type (42), code 0, length 8");
+	}
+
+	#[test]
+	fn mutable() {
+		let raw = [
+			0x60u8, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x3a, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+			0x81, 0x00, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x02, 0x74, 0x65, 0x73, 0x74,
+		];
+
+		let ip       = ip::v6::Packet::new(&raw[..]).unwrap();
+		let mut data = ip.payload().to_vec();
+
+		let mut icmp = icmpv6::Packet::new(&mut data[..]).unwrap();
+		assert!(!icmp.is_valid(&ip));
+
+		icmp.update_checksum(&ip).unwrap();
+		assert!(icmp.is_valid(&ip));
+	}
+}
@@ -0,0 +1,126 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use hwaddr::HwAddr;
+
+use crate::error::*;
+use crate::buffer::{self, Buffer};
+use crate::builder::{Builder as Build, Finalization};
+use crate::packet::{AsPacket, AsPacketMut};
+use crate::icmpv6::builder;
+use crate::icmpv6::Kind;
+use crate::icmpv6::option::{self, Value};
+use crate::icmpv6::router_advertisement::Packet;
+
+/// Router Advertisement packet builder.
+#[derive(Debug)]
+pub struct Builder<B: Buffer = buffer::Dynamic> {
+	buffer:    B,
+	finalizer: Finalization,
+
+	ip: (usize, usize),
+}
+
+impl<B: Buffer> Build<B> for Builder<B> {
+	fn with(mut buffer: B) -> Result<Self> {
+		let ip = (buffer.offset(), buffer.length());
+		buffer.next(16)?;
+		buffer.data_mut()[0] = Kind::RouterAdvertisement.into();
+
+		Ok(Builder {
+			buffer,
+			finalizer: Default::default(),
+
+			ip,
+		})
+	}
+
+	fn finalizer(&mut self) -> &mut Finalization {
+		&mut self.finalizer
+	}
+
+	fn build(mut self) -> Result<B::Inner> {
+		builder::prepare(&mut self.finalizer, self.ip, &self.buffer);
+
+		let mut buffer = self.buffer.into_inner();
+		self.finalizer.finalize(buffer.as_mut())?;
+		Ok(buffer)
+	}
+}
+
+impl<'a, B: Buffer> AsPacket<'a, Packet<&'a [u8]>> for Builder<B> {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.buffer.data())
+	}
+}
+
+impl<'a, B: Buffer> AsPacketMut<'a, Packet<&'a mut [u8]>> for Builder<B> {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.buffer.data_mut())
+	}
+}
+
+impl<B: Buffer> Builder<B> {
+	/// Hop limit hosts should use for outgoing packets.
+	pub fn hop_limit(mut self, value: u8) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_hop_limit(value)?;
+		Ok(self)
+	}
+
+	/// Addresses are available via DHCPv6.
+	pub fn managed(mut self, value: bool) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_managed(value)?;
+		Ok(self)
+	}
+
+	/// Other configuration is available via DHCPv6.
+	pub fn other(mut self, value: bool) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_other(value)?;
+		Ok(self)
+	}
+
+	/// Seconds the router can be used as a default router.
+	pub fn lifetime(mut self, value: u16) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_lifetime(value)?;
+		Ok(self)
+	}
+
+	/// Milliseconds a neighbor is assumed reachable after a confirmation.
+	pub fn reachable_time(mut self, value: u32) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_reachable_time(value)?;
+		Ok(self)
+	}
+
+	/// Milliseconds between retransmitted Neighbor Solicitation messages.
+	pub fn retransmit_timer(mut self, value: u32) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_retransmit_timer(value)?;
+		Ok(self)
+	}
+
+	/// Add a Source Link-Layer Address option.
+	pub fn source_link_layer_address(self, value: HwAddr) -> Result<Self> {
+		self.option(Value::SourceLinkLayerAddress(value))
+	}
+
+	/// Add an MTU option.
+	pub fn mtu(self, value: u32) -> Result<Self> {
+		self.option(Value::Mtu(value))
+	}
+
+	/// Add a Neighbor Discovery option.
+	pub fn option(mut self, value: Value) -> Result<Self> {
+		option::append(&mut self.buffer, &value)?;
+		Ok(self)
+	}
+}
@@ -21,8 +21,81 @@ pub use self::packet::Packet;
 mod builder;
 pub use self::builder::Builder;
 
+/// Fragment reassembly policies.
+pub mod reassembly;
+pub use self::reassembly::Policy;
+
 /// IPv4 packet parser and builder.
 pub mod v4;
 
 /// IPv6 packet parser and builder.
 pub mod v6;
+
+/// Build the pseudo-header upper-layer checksums are calculated on, returning
+/// the buffer and how much of it is used.
+///
+/// For IPv6 the upper-layer length is the given length, so any extension
+/// header between the IPv6 header and the upper-layer header is ignored, and
+/// the destination is the final one when a Routing header has segments left.
+pub(crate) fn pseudo_header<B: AsRef<[u8]>>(ip: &Packet<B>, protocol: Protocol, length: usize) -> ([u8; 40], usize) {
+	use std::io::Cursor;
+	use byteorder::{WriteBytesExt, BigEndian};
+
+	let mut prefix = [0u8; 40];
+
+	match *ip {
+		Packet::V4(ref packet) => {
+			prefix[0 .. 4].copy_from_slice(&packet.source().octets());
+			prefix[4 .. 8].copy_from_slice(&packet.destination().octets());
+
+			prefix[9] = protocol.into();
+			Cursor::new(&mut prefix[10 ..])
+				.write_u16::<BigEndian>(length as u16).unwrap();
+
+			(prefix, 12)
+		}
+
+		Packet::V6(ref packet) => {
+			let destination = packet.extensions()
+				.filter_map(|extension| extension.ok())
+				.find_map(|extension| match extension {
+					v6::Extension::Routing(ref routing) if routing.segments_left() != 0 =>
+						routing.final_destination(),
+
+					_ =>
+						None
+				})
+				.unwrap_or_else(|| packet.destination());
+
+			prefix[0 .. 16].copy_from_slice(&packet.source().octets());
+			prefix[16 .. 32].copy_from_slice(&destination.octets());
+
+			Cursor::new(&mut prefix[32 ..])
+				.write_u32::<BigEndian>(length as u32).unwrap();
+			prefix[39] = protocol.into();
+
+			(prefix, 40)
+		}
+	}
+}
+
+/// Write the payload length of an IPv6 packet made of the given headers and an
+/// upper-layer packet of the given length, unless it's not IPv6 or a jumbogram.
+///
+/// The IPv6 builder only writes the length once the upper layer is done, which
+/// is too late to find the extension headers for the pseudo-header.
+pub(crate) fn payload_length(headers: &mut [u8], length: usize) {
+	use std::io::Cursor;
+	use byteorder::{WriteBytesExt, BigEndian};
+
+	if headers.len() < 40 || headers[0] >> 4 != 6 {
+		return;
+	}
+
+	let length = headers.len() - 40 + length;
+
+	if length <= u16::MAX as usize {
+		Cursor::new(&mut headers[4 ..])
+			.write_u16::<BigEndian>(length as u16).unwrap();
+	}
+}
@@ -12,6 +12,9 @@
 //
 //  0. You just DO WHAT THE FUCK YOU WANT TO.
 
+use std::fmt;
+use std::net::IpAddr;
+
 use crate::error::*;
 use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
 use crate::size;
@@ -27,6 +30,18 @@ pub enum Packet<B: AsRef<[u8]>> {
 	V6(v6::Packet<B>),
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			Packet::V4(ref packet) =>
+				fmt::Display::fmt(packet, f),
+
+			Packet::V6(ref packet) =>
+				fmt::Display::fmt(packet, f),
+		}
+	}
+}
+
 impl<B: AsRef<[u8]>> Packet<B> {
 	/// Create an IP packet without checking the buffer.
 	///
@@ -75,6 +90,30 @@ impl<B: AsRef<[u8]>> Packet<B> {
 	}
 }
 
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Source IP address.
+	pub fn source(&self) -> IpAddr {
+		match *self {
+			Packet::V4(ref packet) =>
+				packet.source().into(),
+
+			Packet::V6(ref packet) =>
+				packet.source().into(),
+		}
+	}
+
+	/// Destination IP address.
+	pub fn destination(&self) -> IpAddr {
+		match *self {
+			Packet::V4(ref packet) =>
+				packet.destination().into(),
+
+			Packet::V6(ref packet) =>
+				packet.destination().into(),
+		}
+	}
+}
+
 impl<B: AsRef<[u8]>> From<v4::Packet<B>> for Packet<B> {
 	fn from(value: v4::Packet<B>) -> Packet<B> {
 		Packet::V4(value)
@@ -0,0 +1,396 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Duration;
+
+use crate::error::*;
+
+/// How overlapping fragment data is resolved.
+///
+/// Different stacks keep different bytes when fragments overlap, so an
+/// analyzer has to pick the policy of the host it is reassembling for.
+#[derive(Eq, PartialEq, Copy, Clone, Default, Debug)]
+pub enum Policy {
+	/// The data that arrived first is kept.
+	First,
+
+	/// The data that arrived last is kept.
+	Last,
+
+	/// The data that arrived first is kept, unless the new fragment starts
+	/// before the old one.
+	#[default]
+	Bsd,
+
+	/// Like `Bsd`, but a new fragment starting at the same offset as an old
+	/// one replaces its data.
+	Linux,
+}
+
+/// Most fragments kept for a datagram, anything more drops it.
+const PIECES: usize = 64;
+
+/// Fragments received for a datagram.
+#[derive(Debug)]
+pub(crate) struct Fragments {
+	started: Duration,
+	header:  Option<Vec<u8>>,
+	total:   Option<usize>,
+	pieces:  Vec<(usize, Vec<u8>)>,
+	covered: Vec<(usize, usize)>,
+	size:    usize,
+}
+
+impl Fragments {
+	/// Start tracking a datagram.
+	pub fn new(started: Duration) -> Fragments {
+		Fragments {
+			started,
+			header:  None,
+			total:   None,
+			pieces:  Vec::new(),
+			covered: Vec::new(),
+			size:    0,
+		}
+	}
+
+	/// When the first fragment was received.
+	pub fn started(&self) -> Duration {
+		self.started
+	}
+
+	/// Octets of header and data held.
+	pub fn size(&self) -> usize {
+		self.size + self.header.as_ref().map(Vec::len).unwrap_or(0)
+	}
+
+	/// The header of the first fragment.
+	pub fn header(&self) -> Option<&[u8]> {
+		self.header.as_deref()
+	}
+
+	/// Set the header of the first fragment, the first one received is kept.
+	pub fn set_header(&mut self, header: &[u8]) {
+		if self.header.is_none() {
+			self.header = Some(header.to_vec());
+		}
+	}
+
+	/// Whether the data overlaps with any data already received.
+	pub fn overlaps(&self, offset: usize, length: usize) -> bool {
+		self.pieces.iter().any(|&(start, ref data)|
+			offset < start + data.len() && start < offset + length)
+	}
+
+	/// Whether the exact same data has already been received.
+	pub fn contains(&self, offset: usize, data: &[u8]) -> bool {
+		self.pieces.iter().any(|&(start, ref piece)| start == offset && piece[..] == *data)
+	}
+
+	/// Add fragment data at the given offset, `last` marks the fragment
+	/// carrying the end of the datagram.
+	///
+	/// Exact duplicates are ignored, and there can be at most 64 fragments.
+	pub fn insert(&mut self, offset: usize, data: &[u8], last: bool) -> Result<()> {
+		let end = offset + data.len();
+
+		if last {
+			if self.total.is_some() && self.total != Some(end) {
+				Err(Error::InvalidPacket)?
+			}
+
+			if self.pieces.iter().any(|&(start, ref data)| start + data.len() > end) {
+				Err(Error::InvalidPacket)?
+			}
+
+			self.total = Some(end);
+		}
+		else if self.total.is_some_and(|total| end > total) {
+			Err(Error::InvalidPacket)?
+		}
+
+		if self.contains(offset, data) {
+			return Ok(());
+		}
+
+		if self.pieces.len() >= PIECES {
+			Err(Error::InvalidPacket)?
+		}
+
+		self.pieces.push((offset, data.to_vec()));
+		self.size += data.len();
+		self.cover(offset, end);
+
+		Ok(())
+	}
+
+	/// Add a range to the sorted and disjoint ranges of data received.
+	fn cover(&mut self, mut start: usize, mut end: usize) {
+		if start == end {
+			return;
+		}
+
+		// Merge with every range touching the new one.
+		let first    = self.covered.partition_point(|&(_, covered)| covered < start);
+		let mut last = first;
+
+		while last < self.covered.len() && self.covered[last].0 <= end {
+			start = start.min(self.covered[last].0);
+			end   = end.max(self.covered[last].1);
+			last += 1;
+		}
+
+		self.covered.splice(first .. last, Some((start, end)));
+	}
+
+	/// Whether the header, the end and every octet in between are known.
+	pub fn is_complete(&self) -> bool {
+		let total = match self.total {
+			Some(total) if self.header.is_some() => total,
+			_ => return false,
+		};
+
+		let covered = match self.covered.first() {
+			Some(&(0, end)) => end,
+			_               => 0,
+		};
+
+		covered >= total
+	}
+
+	/// Join the fragment data, resolving overlaps with the given policy.
+	pub fn assemble(&self, policy: Policy) -> Vec<u8> {
+		// Write the fragments from the lowest priority to the highest, so the
+		// data that has to be kept is written last.
+		let mut order = (0 .. self.pieces.len()).collect::<Vec<_>>();
+
+		match policy {
+			Policy::First =>
+				order.reverse(),
+
+			Policy::Last =>
+				(),
+
+			Policy::Bsd =>
+				order.sort_by(|&a, &b| self.pieces[b].0.cmp(&self.pieces[a].0).then(b.cmp(&a))),
+
+			Policy::Linux =>
+				order.sort_by(|&a, &b| self.pieces[b].0.cmp(&self.pieces[a].0).then(a.cmp(&b))),
+		}
+
+		let mut buffer = vec![0; self.total.unwrap_or(0)];
+
+		for index in order {
+			let (start, ref data) = self.pieces[index];
+			buffer[start .. start + data.len()].copy_from_slice(data);
+		}
+
+		buffer
+	}
+}
+
+/// Incomplete datagrams, bounded in time and memory.
+#[derive(Debug)]
+pub(crate) struct Table<K> {
+	timeout: Duration,
+	limit:   usize,
+
+	pending: HashMap<K, Fragments>,
+	memory:  usize,
+}
+
+impl<K: Eq + Hash + Copy> Table<K> {
+	/// Create a table with the given timeout and memory limit.
+	pub fn new(timeout: Duration, limit: usize) -> Table<K> {
+		Table {
+			timeout,
+			limit,
+
+			pending: HashMap::new(),
+			memory:  0,
+		}
+	}
+
+	/// Time after which an incomplete datagram is dropped.
+	pub fn set_timeout(&mut self, value: Duration) {
+		self.timeout = value;
+	}
+
+	/// Maximum number of octets held.
+	pub fn set_limit(&mut self, value: usize) {
+		self.limit = value;
+	}
+
+	/// Number of incomplete datagrams.
+	pub fn len(&self) -> usize {
+		self.pending.len()
+	}
+
+	/// Number of octets held.
+	pub fn memory(&self) -> usize {
+		self.memory
+	}
+
+	/// Fragments received for the datagram.
+	pub fn get(&self, key: &K) -> Option<&Fragments> {
+		self.pending.get(key)
+	}
+
+	/// Drop the datagrams older than the timeout, returning how many were
+	/// dropped.
+	pub fn expire(&mut self, now: Duration) -> usize {
+		let timeout = self.timeout;
+		let before  = self.pending.len();
+
+		self.pending.retain(|_, fragments| now.saturating_sub(fragments.started()) < timeout);
+		self.memory = self.pending.values().map(Fragments::size).sum();
+
+		before - self.pending.len()
+	}
+
+	/// Add a fragment, the header being the one of the first fragment,
+	/// returning the fragments once the datagram is complete.
+	///
+	/// The oldest datagrams are dropped to stay within the memory limit, and
+	/// a fragment inconsistent with the ones already received drops its
+	/// datagram.
+	pub fn insert(&mut self, now: Duration, key: K, header: Option<&[u8]>, offset: usize, data: &[u8], last: bool) -> Result<Option<Fragments>> {
+		let size = data.len() + header.map(<[u8]>::len).unwrap_or(0);
+		if size > self.limit {
+			return Ok(None);
+		}
+
+		while self.memory + size > self.limit {
+			self.evict();
+		}
+
+		let fragments = self.pending.entry(key).or_insert_with(|| Fragments::new(now));
+		let before    = fragments.size();
+
+		if let Some(header) = header {
+			fragments.set_header(header);
+		}
+
+		let result   = fragments.insert(offset, data, last);
+		let complete = fragments.is_complete();
+		self.memory += fragments.size() - before;
+
+		if let Err(error) = result {
+			self.remove(&key);
+			Err(error)?
+		}
+
+		if !complete {
+			return Ok(None);
+		}
+
+		Ok(self.remove(&key))
+	}
+
+	/// Stop tracking the datagram.
+	pub fn remove(&mut self, key: &K) -> Option<Fragments> {
+		let fragments = self.pending.remove(key)?;
+		self.memory -= fragments.size();
+
+		Some(fragments)
+	}
+
+	fn evict(&mut self) {
+		let oldest = self.pending.iter()
+			.min_by_key(|&(_, fragments)| fragments.started())
+			.map(|(key, _)| *key);
+
+		if let Some(key) = oldest {
+			self.remove(&key);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use super::{Fragments, Policy};
+
+	#[test]
+	fn policies() {
+		let mut fragments = Fragments::new(Duration::from_secs(0));
+		fragments.set_header(&[]);
+		fragments.insert(8, &[b'A'; 16], false).unwrap();
+		fragments.insert(0, &[b'B'; 16], false).unwrap();
+		fragments.insert(16, &[b'C'; 8], true).unwrap();
+		fragments.insert(8, &[b'D'; 8], false).unwrap();
+		fragments.insert(0, &[b'E'; 8], false).unwrap();
+		assert!(fragments.is_complete());
+
+		let blocks = |policy| fragments.assemble(policy).chunks(8).map(|c| c[0]).collect::<Vec<_>>();
+		assert_eq!(blocks(Policy::First), b"BAA");
+		assert_eq!(blocks(Policy::Last), b"EDC");
+		assert_eq!(blocks(Policy::Bsd), b"BBA");
+		assert_eq!(blocks(Policy::Linux), b"EBA");
+	}
+
+	#[test]
+	fn complete() {
+		let mut fragments = Fragments::new(Duration::from_secs(0));
+		fragments.insert(8, &[0; 8], true).unwrap();
+		assert!(!fragments.is_complete());
+
+		fragments.set_header(&[]);
+		assert!(!fragments.is_complete());
+
+		fragments.insert(0, &[0; 8], false).unwrap();
+		assert!(fragments.is_complete());
+
+		assert!(fragments.insert(16, &[0; 8], false).is_err());
+		assert!(fragments.insert(0, &[0; 24], true).is_err());
+	}
+
+	#[test]
+	fn coverage() {
+		let mut fragments = Fragments::new(Duration::from_secs(0));
+		fragments.set_header(&[]);
+		fragments.insert(32, &[0; 8], true).unwrap();
+		fragments.insert(8, &[0; 8], false).unwrap();
+		fragments.insert(24, &[0; 8], false).unwrap();
+		assert_eq!(fragments.covered, [(8, 16), (24, 40)]);
+
+		fragments.insert(12, &[0; 16], false).unwrap();
+		assert_eq!(fragments.covered, [(8, 40)]);
+		assert!(!fragments.is_complete());
+
+		fragments.insert(0, &[0; 8], false).unwrap();
+		assert_eq!(fragments.covered, [(0, 40)]);
+		assert!(fragments.is_complete());
+	}
+
+	#[test]
+	fn duplicates() {
+		let mut fragments = Fragments::new(Duration::from_secs(0));
+
+		for _ in 0 .. 1000 {
+			fragments.insert(0, &[0; 8], false).unwrap();
+		}
+
+		assert_eq!(fragments.size(), 8);
+
+		for index in 1 .. 63 {
+			fragments.insert(index * 8, &[0; 8], false).unwrap();
+		}
+
+		fragments.insert(0, &[1; 8], false).unwrap();
+		assert!(fragments.insert(8, &[1; 8], false).is_err());
+	}
+}
@@ -23,6 +23,7 @@ use crate::packet::{AsPacket, AsPacketMut};
 use crate::ip::Protocol;
 use crate::ip::v4::Packet;
 use crate::ip::v4::Flags;
+use crate::ip::v4::option::{Class, Number};
 use crate::ip::v4::checksum;
 
 /// IPv4 packet builder.
@@ -31,7 +32,7 @@ pub struct Builder<B: Buffer = buffer::Dynamic> {
 	buffer:    B,
 	finalizer: Finalization,
 
-	options: bool,
+	options: usize,
 	payload: bool,
 }
 
@@ -52,7 +53,7 @@ impl<B: Buffer> Build<B> for Builder<B> {
 			buffer:    buffer,
 			finalizer: Default::default(),
 
-			options: false,
+			options: 0,
 			payload: false,
 		})
 	}
@@ -62,6 +63,7 @@ impl<B: Buffer> Build<B> for Builder<B> {
 	}
 
 	fn build(mut self) -> Result<B::Inner> {
+		self.pad()?;
 		self.prepare();
 
 		let mut buffer = self.buffer.into_inner();
@@ -97,6 +99,7 @@ macro_rules! protocol {
 			}
 
 			self = self.protocol(Protocol::$protocol)?;
+			self.pad()?;
 			self.prepare();
 
 			let mut builder = crate::$module::Builder::with(self.buffer)?;
@@ -162,12 +165,93 @@ impl<B: Buffer> Builder<B> {
 		Ok(self)
 	}
 
+	/// Record Route option with room for the given number of addresses.
+	pub fn record_route(self, slots: usize) -> Result<Self> {
+		if slots == 0 {
+			Err(Error::InvalidValue)?
+		}
+
+		let mut payload = vec![0; 1 + slots * 4];
+		payload[0] = 4;
+
+		self.raw(false, Class::Control, Number::RecordRoute, &payload)
+	}
+
+	/// Loose Source and Record Route option through the given addresses.
+	pub fn loose_source_route(self, route: &[Ipv4Addr]) -> Result<Self> {
+		self.source_route(Number::LooseSourceRoute, route)
+	}
+
+	/// Strict Source and Record Route option through the given addresses.
+	pub fn strict_source_route(self, route: &[Ipv4Addr]) -> Result<Self> {
+		self.source_route(Number::StrictSourceRoute, route)
+	}
+
+	/// Timestamp option with room for the given number of timestamps.
+	pub fn timestamp(self, slots: usize) -> Result<Self> {
+		self.timestamps(0, slots, &[])
+	}
+
+	/// Timestamp option with room for the given number of address and
+	/// timestamp pairs.
+	pub fn timestamp_addresses(self, slots: usize) -> Result<Self> {
+		self.timestamps(1, slots, &[])
+	}
+
+	/// Timestamp option with timestamps only from the given addresses.
+	pub fn timestamp_prespecified(self, addresses: &[Ipv4Addr]) -> Result<Self> {
+		self.timestamps(3, addresses.len(), addresses)
+	}
+
+	/// Router Alert option.
+	pub fn router_alert(self, value: u16) -> Result<Self> {
+		self.raw(true, Class::Control, Number::RouterAlert, &value.to_be_bytes())
+	}
+
+	/// Append a raw option.
+	///
+	/// # Note
+	///
+	/// Options are padded with End of Option List to a 32-bit boundary, use
+	/// `Number::NoOperation` to align options within the list.
+	pub fn raw(mut self, copied: bool, class: Class, number: Number, payload: &[u8]) -> Result<Self> {
+		let class:  u8 = class.into();
+		let number: u8 = number.into();
+
+		if class > 3 || number > 31 {
+			Err(Error::InvalidValue)?
+		}
+
+		let kind = (copied as u8) << 7 | class << 5 | number;
+
+		if number == 0 || number == 1 {
+			if !payload.is_empty() {
+				Err(Error::InvalidValue)?
+			}
+
+			self.append(&[kind])?;
+		}
+		else {
+			if payload.len() > 38 {
+				Err(Error::InvalidValue)?
+			}
+
+			let mut option = vec![kind, payload.len() as u8 + 2];
+			option.extend_from_slice(payload);
+
+			self.append(&option)?;
+		}
+
+		Ok(self)
+	}
+
 	/// Payload for the packet.
 	pub fn payload<'a, T: IntoIterator<Item = &'a u8>>(mut self, value: T) -> Result<Self> {
 		if self.payload {
 			Err(Error::AlreadyDefined)?
 		}
 
+		self.pad()?;
 		self.payload = true;
 
 		for byte in value {
@@ -178,13 +262,71 @@ impl<B: Buffer> Builder<B> {
 		Ok(self)
 	}
 
+	fn source_route(self, number: Number, route: &[Ipv4Addr]) -> Result<Self> {
+		if route.is_empty() {
+			Err(Error::InvalidValue)?
+		}
+
+		let mut payload = vec![4];
+
+		for address in route {
+			payload.extend_from_slice(&address.octets());
+		}
+
+		self.raw(true, Class::Control, number, &payload)
+	}
+
+	fn timestamps(self, flag: u8, slots: usize, addresses: &[Ipv4Addr]) -> Result<Self> {
+		if slots == 0 {
+			Err(Error::InvalidValue)?
+		}
+
+		let size = if flag == 0 { 4 } else { 8 };
+		let mut payload = vec![0; 2 + slots * size];
+		payload[0] = 5;
+		payload[1] = flag;
+
+		for (i, address) in addresses.iter().enumerate() {
+			payload[2 + i * 8 .. 2 + i * 8 + 4].copy_from_slice(&address.octets());
+		}
+
+		self.raw(false, Class::Debugging, Number::TimeStamp, &payload)
+	}
+
+	fn append(&mut self, option: &[u8]) -> Result<()> {
+		if self.payload {
+			Err(Error::AlreadyDefined)?
+		}
+
+		if self.options + option.len() > 40 {
+			Err(Error::InvalidValue)?
+		}
+
+		let offset = 20 + self.options;
+		self.buffer.more(option.len())?;
+		self.buffer.data_mut()[offset .. offset + option.len()].copy_from_slice(option);
+		self.options += option.len();
+
+		Ok(())
+	}
+
+	fn pad(&mut self) -> Result<()> {
+		let padding = self.options.next_multiple_of(4) - self.options;
+
+		if padding > 0 {
+			self.append(&[0; 3][.. padding])?;
+		}
+
+		Ok(())
+	}
+
 	fn prepare(&mut self) {
 		let offset = self.buffer.offset();
 		let length = self.buffer.length();
+		let header = (20 + self.options) / 4;
 
 		self.finalizer.add(move |out| {
 			// Set the version to 4 and the header length.
-			let header = length / 4;
 			out[offset] = (4 << 4) | header as u8;
 
 			// Calculate and write the total length of the packet.
@@ -215,6 +357,7 @@ impl<B: Buffer> Builder<B> {
 mod test {
 	use std::net::Ipv4Addr;
 	use crate::builder::Builder;
+	use crate::packet::Packet;
 	use crate::ip;
 	use crate::tcp;
 
@@ -267,4 +410,75 @@ mod test {
 		assert_eq!(packet.destination(), "192.168.0.79".parse::<Ipv4Addr>().unwrap());
 		assert!(packet.is_valid());
 	}
+
+	#[test]
+	fn options() {
+		let packet = ip::v4::Builder::default()
+			.source("10.0.0.1".parse().unwrap()).unwrap()
+			.destination("10.0.0.2".parse().unwrap()).unwrap()
+			.router_alert(0).unwrap()
+			.loose_source_route(&["10.0.1.1".parse().unwrap(), "10.0.2.1".parse().unwrap()]).unwrap()
+			.tcp().unwrap()
+				.source(1337).unwrap()
+				.destination(9001).unwrap()
+				.flags(tcp::flag::SYN).unwrap()
+				.build().unwrap();
+
+		let packet = ip::v4::Packet::new(packet).unwrap();
+		assert_eq!(packet.header(), 5 + 4);
+		assert_eq!(packet.length(), 36 + 20);
+		assert!(packet.is_valid());
+
+		let options = packet.options().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(options.len(), 2);
+		assert_eq!(options[0].number(), ip::v4::option::Number::RouterAlert);
+		assert!(options[0].is_copied());
+		assert_eq!(options[1].number(), ip::v4::option::Number::LooseSourceRoute);
+		assert_eq!(options[1].length(), 11);
+		assert_eq!(&packet.as_ref()[31 .. 36], &[10, 0, 2, 1, 0]);
+
+		let tcp = tcp::Packet::new(packet.payload()).unwrap();
+		assert!(tcp.is_valid(&ip::Packet::from(&packet)));
+	}
+
+	#[test]
+	fn options_payload() {
+		let packet = ip::v4::Builder::default()
+			.record_route(9).unwrap()
+			.payload(b"lol").unwrap()
+			.build().unwrap();
+
+		let packet = ip::v4::Packet::new(packet).unwrap();
+		assert_eq!(packet.header(), 15);
+		assert_eq!(packet.length(), 60 + 3);
+		assert_eq!(packet.payload(), b"lol");
+		assert!(packet.is_valid());
+
+		let options = packet.options().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(options.len(), 1);
+		assert_eq!(options[0].number(), ip::v4::option::Number::RecordRoute);
+		assert_eq!(options[0].length(), 39);
+	}
+
+	#[test]
+	fn options_limit() {
+		assert!(ip::v4::Builder::default()
+			.record_route(10).is_err());
+
+		assert!(ip::v4::Builder::default()
+			.timestamp(9).unwrap()
+			.router_alert(0).is_err());
+
+		assert!(ip::v4::Builder::default()
+			.payload(b"lol").unwrap()
+			.router_alert(0).is_err());
+
+		let packet = ip::v4::Builder::default()
+			.timestamp_addresses(4).unwrap()
+			.build().unwrap();
+
+		let packet = ip::v4::Packet::new(packet).unwrap();
+		assert_eq!(packet.header(), 14);
+		assert_eq!(&packet.as_ref()[20 .. 24], &[0x44, 36, 5, 1]);
+	}
 }
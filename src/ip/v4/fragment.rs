@@ -0,0 +1,155 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use byteorder::{ByteOrder, BigEndian};
+
+use crate::error::*;
+use crate::packet::Packet as P;
+use crate::ip::v4::{Packet, Flags};
+use crate::ip::v4::option::Number;
+
+/// Split the packet in fragments fitting in the given MTU.
+///
+/// The first fragment keeps all the options, the following ones only keep
+/// the options that have to be copied. A packet that already fits is returned
+/// as is.
+///
+/// # Errors
+///
+/// Fails with `Error::InvalidPacket` if the packet needs fragmenting but has
+/// `Flags::DONT_FRAGMENT` set, and with `Error::InvalidValue` if the MTU
+/// cannot fit at least 8 octets of payload.
+pub fn fragment<B: AsRef<[u8]>>(packet: &Packet<B>, mtu: usize) -> Result<Vec<Packet<Vec<u8>>>> {
+	let (header, payload) = packet.split();
+
+	if header.len() + payload.len() <= mtu {
+		return Ok(vec![Packet::unchecked(packet.as_ref().to_vec())]);
+	}
+
+	if packet.flags().contains(Flags::DONT_FRAGMENT) {
+		Err(Error::InvalidPacket)?
+	}
+
+	let copied = copied(packet)?;
+	let offset = packet.offset() as usize * 8;
+	let more   = packet.flags().contains(Flags::MORE_FRAGMENTS);
+
+	let mut fragments = Vec::new();
+	let mut position  = 0;
+
+	while position < payload.len() {
+		let options = if position == 0 { &header[20 ..] } else { &copied[..] };
+		let room    = mtu.saturating_sub(20 + options.len()) / 8 * 8;
+
+		if room == 0 {
+			Err(Error::InvalidValue)?
+		}
+
+		let length = room.min(payload.len() - position);
+		let last   = position + length == payload.len();
+
+		let mut buffer = Vec::with_capacity(20 + options.len() + length);
+		buffer.extend_from_slice(&header[.. 20]);
+		buffer.extend_from_slice(options);
+		buffer.extend_from_slice(&payload[position .. position + length]);
+		buffer[0] = (4 << 4) | ((20 + options.len()) / 4) as u8;
+		BigEndian::write_u16(&mut buffer[2 ..], (20 + options.len() + length) as u16);
+
+		let mut fragment = Packet::unchecked(buffer);
+		fragment.set_offset(((offset + position) / 8) as u16)?;
+		fragment.set_flags(if last && !more { Flags::empty() } else { Flags::MORE_FRAGMENTS })?;
+		fragment.update_checksum()?;
+
+		fragments.push(fragment);
+		position += length;
+	}
+
+	Ok(fragments)
+}
+
+/// Options to put in non-first fragments, padded to a multiple of 4 octets.
+fn copied<B: AsRef<[u8]>>(packet: &Packet<B>) -> Result<Vec<u8>> {
+	let mut options = Vec::new();
+
+	for option in packet.options() {
+		let option = option?;
+
+		if option.is_copied() && option.number() != Number::NoOperation {
+			options.extend_from_slice(option.as_ref());
+		}
+	}
+
+	options.resize(options.len().next_multiple_of(4), 0);
+
+	Ok(options)
+}
+
+#[cfg(test)]
+mod test {
+	use crate::builder::Builder;
+	use crate::packet::Packet;
+	use crate::ip;
+
+	fn build(flags: ip::v4::Flags) -> ip::v4::Packet<Vec<u8>> {
+		ip::v4::Packet::new(ip::v4::Builder::default()
+			.id(0x2d87).unwrap()
+			.flags(flags).unwrap()
+			.source("10.0.0.1".parse().unwrap()).unwrap()
+			.destination("10.0.0.2".parse().unwrap()).unwrap()
+			.loose_source_route(&["10.0.1.1".parse().unwrap()]).unwrap()
+			.record_route(2).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(9001).unwrap()
+				.payload(&[0x42; 100]).unwrap()
+				.build().unwrap()).unwrap()
+	}
+
+	#[test]
+	fn fragment() {
+		let packet    = build(ip::v4::Flags::empty());
+		let fragments = ip::v4::fragment(&packet, 80).unwrap();
+
+		assert_eq!(fragments.len(), 3);
+		assert_eq!(fragments.iter().map(|f| f.length()).collect::<Vec<_>>(), vec![80, 76, 48]);
+		assert_eq!(fragments.iter().map(|f| f.header()).collect::<Vec<_>>(), vec![10, 7, 7]);
+		assert_eq!(fragments.iter().map(|f| f.offset()).collect::<Vec<_>>(), vec![0, 5, 11]);
+		assert_eq!(fragments.iter().map(|f| f.flags()).collect::<Vec<_>>(), vec![
+			ip::v4::Flags::MORE_FRAGMENTS,
+			ip::v4::Flags::MORE_FRAGMENTS,
+			ip::v4::Flags::empty(),
+		]);
+
+		for fragment in &fragments {
+			assert!(fragment.is_valid());
+			assert_eq!(fragment.id(), 0x2d87);
+			assert_eq!(fragment.protocol(), ip::Protocol::Udp);
+		}
+
+		let numbers = fragments[1].options().map(|o| o.unwrap().number()).collect::<Vec<_>>();
+		assert_eq!(numbers, vec![ip::v4::option::Number::LooseSourceRoute]);
+
+		let payload = fragments.iter().flat_map(|f| f.payload().to_vec()).collect::<Vec<_>>();
+		assert_eq!(&payload[..], packet.payload());
+	}
+
+	#[test]
+	fn unfragmented() {
+		let packet = build(ip::v4::Flags::DONT_FRAGMENT);
+
+		assert_eq!(ip::v4::fragment(&packet, 1500).unwrap()[0].as_ref(), packet.as_ref());
+		assert!(ip::v4::fragment(&packet, 80).is_err());
+		assert!(ip::v4::fragment(&build(ip::v4::Flags::empty()), 44).is_err());
+	}
+}
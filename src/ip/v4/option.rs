@@ -13,8 +13,11 @@
 //  0. You just DO WHAT THE FUCK YOU WANT TO.
 
 use std::fmt;
+use std::net::Ipv4Addr;
+use byteorder::{ByteOrder, BigEndian};
 
 use crate::error::*;
+use crate::size;
 use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
 
 /// IPv4 Option parser.
@@ -54,6 +57,99 @@ pub enum Class {
 	Reserved(u8),
 }
 
+/// Decoded IPv4 option value.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum Value {
+	/// End of the option list.
+	End,
+
+	/// Padding between options.
+	NoOperation,
+
+	/// Basic security option, with the classification level and the
+	/// protection authority flags.
+	Security {
+		/// Security classification level.
+		classification: u8,
+
+		/// Protection authority flags.
+		authority: Vec<u8>,
+	},
+
+	/// Route the packet has to go through, possibly skipping hops.
+	LooseSourceRoute(Route),
+
+	/// Timestamps recorded along the route.
+	Timestamp(Timestamp),
+
+	/// Addresses recorded along the route.
+	RecordRoute(Route),
+
+	/// Identifier of the stream the packet belongs to.
+	StreamId(u16),
+
+	/// Route the packet has to go through exactly.
+	StrictSourceRoute(Route),
+
+	/// Routers should examine the packet, with the alert value.
+	RouterAlert(u16),
+
+	/// Any other option, with its number and payload.
+	Other(Number, Vec<u8>),
+}
+
+/// Route carried by the record route and source route options.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Route {
+	/// Offset of the next slot, starting at 4.
+	pub pointer: u8,
+
+	/// All the address slots, filled or not.
+	pub addresses: Vec<Ipv4Addr>,
+}
+
+/// Timestamps carried by the timestamp option.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Timestamp {
+	/// Offset of the next slot, starting at 5.
+	pub pointer: u8,
+
+	/// Number of hops that could not record a timestamp.
+	pub overflow: u8,
+
+	/// What the entries contain.
+	pub flag: Flag,
+
+	/// All the entry slots, filled or not.
+	pub entries: Vec<Entry>,
+}
+
+/// Content of the timestamp option entries.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Flag {
+	/// Timestamps only.
+	Timestamp,
+
+	/// Addresses and timestamps.
+	Address,
+
+	/// Timestamps of prespecified addresses.
+	Prespecified,
+
+	/// Unknown flag.
+	Unknown(u8),
+}
+
+/// Timestamp option entry.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Entry {
+	/// Timestamp alone.
+	Timestamp(u32),
+
+	/// Address of the hop and its timestamp.
+	Address(Ipv4Addr, u32),
+}
+
 /// IPv4 Option number.
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Number {
@@ -145,19 +241,39 @@ impl<B: AsRef<[u8]>> fmt::Debug for Option<B> {
 	}
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Option<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.value() {
+			Ok(value) =>
+				fmt::Display::fmt(&value, f),
+
+			Err(_) =>
+				write!(f, "opt-{}:bad", Into::<u8>::into(self.number())),
+		}
+	}
+}
+
 impl<B: AsRef<[u8]>> Option<B> {
 	/// Parse an IPv4 option, checking the buffer contents are correct.
 	pub fn new(buffer: B) -> Result<Option<B>> {
-		use crate::size::header::Min;
-
 		let option = Option {
 			buffer: buffer,
 		};
 
-		if option.buffer.as_ref().len() < Self::min() {
+		if option.buffer.as_ref().len() < <Self as size::header::Min>::min() {
 			Err(Error::SmallBuffer)?
 		}
 
+		if option.number() != Number::End && option.number() != Number::NoOperation {
+			if option.buffer.as_ref().len() < 2 {
+				Err(Error::SmallBuffer)?
+			}
+
+			if option.length() < 2 {
+				Err(Error::InvalidPacket)?
+			}
+		}
+
 		if option.buffer.as_ref().len() < option.length() as usize {
 			Err(Error::SmallBuffer)?
 		}
@@ -251,6 +367,208 @@ impl<B: AsRef<[u8]>> Option<B> {
 				self.buffer.as_ref()[1]
 		}
 	}
+
+	/// Decode the option value, checking the length is the one expected for
+	/// the option.
+	pub fn value(&self) -> Result<Value> {
+		let payload = self.payload();
+
+		Ok(match (self.number(), payload.len()) {
+			(Number::End, _) =>
+				Value::End,
+
+			(Number::NoOperation, _) =>
+				Value::NoOperation,
+
+			(Number::Security, length) if length >= 1 =>
+				Value::Security {
+					classification: payload[0],
+					authority:      payload[1 ..].to_vec(),
+				},
+
+			(Number::LooseSourceRoute, _) =>
+				Value::LooseSourceRoute(Route::parse(payload)?),
+
+			(Number::TimeStamp, _) =>
+				Value::Timestamp(Timestamp::parse(payload)?),
+
+			(Number::RecordRoute, _) =>
+				Value::RecordRoute(Route::parse(payload)?),
+
+			(Number::StreamId, 2) =>
+				Value::StreamId(BigEndian::read_u16(payload)),
+
+			(Number::StrictSourceRoute, _) =>
+				Value::StrictSourceRoute(Route::parse(payload)?),
+
+			(Number::RouterAlert, 2) =>
+				Value::RouterAlert(BigEndian::read_u16(payload)),
+
+			(Number::Security, _) |
+			(Number::StreamId, _) |
+			(Number::RouterAlert, _) =>
+				Err(Error::InvalidPacket)?,
+
+			(number, _) =>
+				Value::Other(number, payload.to_vec()),
+		})
+	}
+}
+
+impl Value {
+	/// Option number for the value.
+	pub fn number(&self) -> Number {
+		match *self {
+			Value::End                   => Number::End,
+			Value::NoOperation           => Number::NoOperation,
+			Value::Security { .. }       => Number::Security,
+			Value::LooseSourceRoute(..)  => Number::LooseSourceRoute,
+			Value::Timestamp(..)         => Number::TimeStamp,
+			Value::RecordRoute(..)       => Number::RecordRoute,
+			Value::StreamId(..)          => Number::StreamId,
+			Value::StrictSourceRoute(..) => Number::StrictSourceRoute,
+			Value::RouterAlert(..)       => Number::RouterAlert,
+			Value::Other(number, ..)     => number,
+		}
+	}
+}
+
+impl Route {
+	fn parse(payload: &[u8]) -> Result<Route> {
+		if payload.is_empty() || !(payload.len() - 1).is_multiple_of(4) || payload[0] < 4 {
+			Err(Error::InvalidPacket)?
+		}
+
+		Ok(Route {
+			pointer:   payload[0],
+			addresses: payload[1 ..].chunks(4).map(|b| Ipv4Addr::from(BigEndian::read_u32(b))).collect(),
+		})
+	}
+
+	/// The addresses in the slots before the pointer.
+	pub fn recorded(&self) -> &[Ipv4Addr] {
+		let filled = (self.pointer as usize).saturating_sub(4) / 4;
+		&self.addresses[.. filled.min(self.addresses.len())]
+	}
+}
+
+impl Timestamp {
+	fn parse(payload: &[u8]) -> Result<Timestamp> {
+		if payload.len() < 2 || payload[0] < 5 {
+			Err(Error::InvalidPacket)?
+		}
+
+		let flag = Flag::from(payload[1] & 0b1111);
+		let data = &payload[2 ..];
+
+		let entries = match flag {
+			Flag::Timestamp if data.len().is_multiple_of(4) =>
+				data.chunks(4).map(|b| Entry::Timestamp(BigEndian::read_u32(b))).collect(),
+
+			Flag::Address | Flag::Prespecified if data.len().is_multiple_of(8) =>
+				data.chunks(8).map(|b| Entry::Address(Ipv4Addr::from(BigEndian::read_u32(b)),
+					BigEndian::read_u32(&b[4 ..]))).collect(),
+
+			_ =>
+				Err(Error::InvalidPacket)?
+		};
+
+		Ok(Timestamp {
+			pointer:  payload[0],
+			overflow: payload[1] >> 4,
+			flag,
+			entries,
+		})
+	}
+
+	/// The entries in the slots before the pointer.
+	pub fn recorded(&self) -> &[Entry] {
+		let size = match self.flag {
+			Flag::Timestamp => 4,
+			_               => 8,
+		};
+
+		let filled = (self.pointer as usize).saturating_sub(5) / size;
+		&self.entries[.. filled.min(self.entries.len())]
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			Value::End =>
+				f.write_str("EOL"),
+
+			Value::NoOperation =>
+				f.write_str("NOP"),
+
+			Value::Security { classification, .. } =>
+				write!(f, "security 0x{:02x}", classification),
+
+			Value::LooseSourceRoute(ref route) =>
+				write!(f, "LSRR {}", route),
+
+			Value::Timestamp(ref timestamp) =>
+				write!(f, "TS{{{}}}", timestamp),
+
+			Value::RecordRoute(ref route) =>
+				write!(f, "RR {}", route),
+
+			Value::StreamId(value) =>
+				write!(f, "SID {}", value),
+
+			Value::StrictSourceRoute(ref route) =>
+				write!(f, "SSRR {}", route),
+
+			Value::RouterAlert(value) =>
+				write!(f, "RA value {}", value),
+
+			Value::Other(number, _) =>
+				write!(f, "opt-{}", Into::<u8>::into(number)),
+		}
+	}
+}
+
+impl fmt::Display for Route {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, address) in self.addresses.iter().enumerate() {
+			if i > 0 {
+				f.write_str(", ")?;
+			}
+
+			write!(f, "{}", address)?;
+
+			if i + 1 == self.recorded().len() {
+				f.write_str("*")?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl fmt::Display for Timestamp {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, entry) in self.recorded().iter().enumerate() {
+			if i > 0 {
+				f.write_str(" ")?;
+			}
+
+			match *entry {
+				Entry::Timestamp(value) =>
+					write!(f, "{}", value)?,
+
+				Entry::Address(address, value) =>
+					write!(f, "{}@{}", address, value)?,
+			}
+		}
+
+		if self.overflow > 0 {
+			write!(f, " [{} hops not recorded]", self.overflow)?;
+		}
+
+		Ok(())
+	}
 }
 
 impl From<u8> for Class {
@@ -277,6 +595,30 @@ impl Into<u8> for Class {
 	}
 }
 
+impl From<u8> for Flag {
+	fn from(value: u8) -> Self {
+		use self::Flag::*;
+
+		match value {
+			0 => Timestamp,
+			1 => Address,
+			3 => Prespecified,
+			n => Unknown(n),
+		}
+	}
+}
+
+impl From<Flag> for u8 {
+	fn from(value: Flag) -> u8 {
+		match value {
+			Flag::Timestamp    => 0,
+			Flag::Address      => 1,
+			Flag::Prespecified => 3,
+			Flag::Unknown(n)   => n,
+		}
+	}
+}
+
 impl From<u8> for Number {
 	fn from(value: u8) -> Self {
 		use self::Number::*;
@@ -19,6 +19,7 @@ use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
 
 use crate::error::*;
 use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::dissect::{dissect, LinkType};
 use crate::ip::Protocol;
 use crate::ip::v4::Flags;
 use crate::ip::v4::option;
@@ -64,6 +65,12 @@ impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
 	}
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&dissect(LinkType::Raw, self.as_ref()), f)
+	}
+}
+
 impl<B: AsRef<[u8]>> Packet<B> {
 	/// Create an IPv4 packet without checking the buffer.
 	pub fn unchecked(buffer: B) -> Packet<B> {
@@ -119,7 +126,15 @@ impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
 	fn as_ref(&self) -> &[u8] {
 		use crate::size::Size;
 
-		&self.buffer.as_ref()[.. self.size()]
+		let size   = self.size();
+		let buffer = self.buffer.as_ref();
+
+		if buffer.len() < size {
+			buffer
+		}
+		else {
+			&buffer[.. size]
+		}
 	}
 }
 
@@ -127,8 +142,15 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
 	fn as_mut(&mut self) -> &mut [u8] {
 		use crate::size::Size;
 
-		let size = self.size();
-		&mut self.buffer.as_mut()[.. size]
+		let size   = self.size();
+		let buffer = self.buffer.as_mut();
+
+		if buffer.len() < size {
+			buffer
+		}
+		else {
+			&mut buffer[.. size]
+		}
 	}
 }
 
@@ -215,8 +237,8 @@ impl<B: AsRef<[u8]>> Packet<B> {
 
 	/// Flags of the packet.
 	pub fn flags(&self) -> Flags {
-		Flags::from_bits((&self.buffer.as_ref()[6 ..])
-			.read_u16::<BigEndian>().unwrap() >> 13).unwrap()
+		Flags::from_bits_truncate((&self.buffer.as_ref()[6 ..])
+			.read_u16::<BigEndian>().unwrap() >> 13)
 	}
 
 	/// Offset of the packet.
@@ -305,16 +327,24 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
 
 	/// Packet flags.
 	pub fn set_flags(&mut self, value: Flags) -> Result<&mut Self> {
+		let offset = self.offset();
+
 		Cursor::new(&mut self.header_mut()[6 ..])
-			.write_u16::<BigEndian>(value.bits())?;
+			.write_u16::<BigEndian>(value.bits() << 13 | offset)?;
 
 		Ok(self)
 	}
 
-	/// Packet fragment offset.
+	/// Packet fragment offset, in units of 8 octets.
 	pub fn set_offset(&mut self, value: u16) -> Result<&mut Self> {
+		if value > 0x1fff {
+			Err(Error::InvalidValue)?
+		}
+
+		let flags = self.flags();
+
 		Cursor::new(&mut self.header_mut()[6 ..])
-			.write_u16::<BigEndian>(value)?;
+			.write_u16::<BigEndian>(flags.bits() << 13 | value)?;
 
 		Ok(self)
 	}
@@ -465,8 +495,10 @@ impl<'a> Iterator for OptionIter<'a> {
 				Some(Ok(option))
 			}
 
-			Err(error) =>
+			Err(error) => {
+				self.buffer = &[];
 				Some(Err(error))
+			}
 		}
 	}
 }
@@ -499,6 +531,57 @@ mod test {
 		assert_eq!(ip.destination(), "192.168.0.79".parse::<Ipv4Addr>().unwrap());
 	}
 
+	#[test]
+	fn options() {
+		let raw = [0x4du8, 0x00, 0x00, 0x34, 0x00, 0x00, 0x00, 0x00, 0x40, 0x01, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+			0x07, 0x0b, 0x08, 0x0a, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00,
+			0x44, 0x0c, 0x0d, 0x21, 0x0a, 0x00, 0x01, 0x01, 0x00, 0x00, 0x03, 0xe8,
+			0x94, 0x04, 0x00, 0x00,
+			0x88, 0x04, 0x12, 0x34,
+			0x00];
+
+		let ip     = ip::v4::Packet::no_payload(&raw[..]).unwrap();
+		let values = ip.options().map(|o| o.unwrap().value().unwrap()).collect::<Vec<_>>();
+
+		let route = ip::v4::option::Route {
+			pointer:   8,
+			addresses: vec![Ipv4Addr::new(10, 0, 1, 1), Ipv4Addr::new(0, 0, 0, 0)],
+		};
+
+		let timestamp = ip::v4::option::Timestamp {
+			pointer:  13,
+			overflow: 2,
+			flag:     ip::v4::option::Flag::Address,
+			entries:  vec![ip::v4::option::Entry::Address(Ipv4Addr::new(10, 0, 1, 1), 1000)],
+		};
+
+		assert_eq!(route.recorded(), &[Ipv4Addr::new(10, 0, 1, 1)]);
+		assert_eq!(timestamp.recorded().len(), 1);
+		assert_eq!(values, vec![
+			ip::v4::option::Value::RecordRoute(route),
+			ip::v4::option::Value::Timestamp(timestamp),
+			ip::v4::option::Value::RouterAlert(0),
+			ip::v4::option::Value::StreamId(0x1234),
+		]);
+
+		let summary = ip.options().map(|o| o.unwrap().to_string()).collect::<Vec<_>>();
+		assert_eq!(summary, vec!["RR 10.0.1.1*, 0.0.0.0", "TS{10.0.1.1@1000 [2 hops not recorded]}", "RA value 0", "SID 4660"]);
+
+		assert!(ip::v4::Option::new(&[0x07, 0x06, 0x04, 0, 0, 0][..]).unwrap().value().is_err());
+		assert!(ip::v4::Option::new(&[0x07, 0x07, 0x03, 0, 0, 0, 0][..]).unwrap().value().is_err());
+		assert!(ip::v4::Option::new(&[0x44, 0x08, 0x05, 0x01, 0, 0, 0, 0][..]).unwrap().value().is_err());
+		assert!(ip::v4::Option::new(&[0x94, 0x03, 0x00][..]).unwrap().value().is_err());
+		assert!(ip::v4::Option::new(&[0x07, 0x01][..]).is_err());
+		assert!(ip::v4::Option::new(&[0x07][..]).is_err());
+
+		let mut raw = raw;
+		raw[21] = 0x01;
+		let ip = ip::v4::Packet::no_payload(&raw[..]).unwrap();
+		let mut options = ip.options();
+		assert!(options.next().unwrap().is_err());
+		assert!(options.next().is_none());
+	}
+
 	#[test]
 	fn owned() {
 		let raw: Vec<u8> = vec![0x45, 0x00, 0x00, 0x34, 0x2d, 0x87, 0x00, 0x00, 0x2c, 0x06, 0x5c, 0x74, 0x42, 0x66, 0x01, 0x6c, 0xc0, 0xa8, 0x00, 0x4f];
@@ -522,6 +605,13 @@ mod test {
 
 		ip.update_checksum().unwrap();
 		assert!(ip.is_valid());
+
+		ip.set_flags(ip::v4::Flags::MORE_FRAGMENTS).unwrap();
+		ip.set_offset(0x1234).unwrap();
+		assert_eq!(ip.flags(), ip::v4::Flags::MORE_FRAGMENTS);
+		assert_eq!(ip.offset(), 0x1234);
+		assert_eq!(&crate::packet::Packet::header(&ip)[6 .. 8], &[0x32, 0x34]);
+		assert!(ip.set_offset(0x2000).is_err());
 	}
 
 	#[test]
@@ -0,0 +1,281 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+use byteorder::{ByteOrder, BigEndian};
+
+use crate::error::*;
+use crate::packet::Packet as P;
+use crate::ip::Protocol;
+use crate::ip::reassembly::{Policy, Table};
+use crate::ip::v4::{Packet, Flags};
+
+/// Fragments belonging to the same datagram share the key.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct Key {
+	/// Source address.
+	pub source: Ipv4Addr,
+
+	/// Destination address.
+	pub destination: Ipv4Addr,
+
+	/// Transport protocol.
+	pub protocol: Protocol,
+
+	/// Identification.
+	pub id: u16,
+}
+
+impl<'a, B: AsRef<[u8]>> From<&'a Packet<B>> for Key {
+	fn from(packet: &'a Packet<B>) -> Key {
+		Key {
+			source:      packet.source(),
+			destination: packet.destination(),
+			protocol:    packet.protocol(),
+			id:          packet.id(),
+		}
+	}
+}
+
+/// IPv4 fragment reassembler.
+///
+/// Timestamps are only used to expire datagrams, so any monotonic clock
+/// works, like the capture timestamps of the packets.
+#[derive(Debug)]
+pub struct Reassembler {
+	policy: Policy,
+	table:  Table<Key>,
+}
+
+impl Default for Reassembler {
+	fn default() -> Self {
+		Reassembler::new(Policy::default())
+	}
+}
+
+impl Reassembler {
+	/// Create a reassembler using the given overlap policy, with a 30 seconds
+	/// timeout and a 4 MiB memory limit.
+	pub fn new(policy: Policy) -> Reassembler {
+		Reassembler {
+			policy,
+			table:  Table::new(Duration::from_secs(30), 4 * 1024 * 1024),
+		}
+	}
+
+	/// Time after which an incomplete datagram is dropped.
+	pub fn timeout(mut self, value: Duration) -> Self {
+		self.table.set_timeout(value);
+		self
+	}
+
+	/// Maximum number of octets held for incomplete datagrams, the oldest
+	/// datagrams are dropped to make room.
+	pub fn limit(mut self, value: usize) -> Self {
+		self.table.set_limit(value);
+		self
+	}
+
+	/// Number of incomplete datagrams.
+	pub fn len(&self) -> usize {
+		self.table.len()
+	}
+
+	/// Whether there are no incomplete datagrams.
+	pub fn is_empty(&self) -> bool {
+		self.table.len() == 0
+	}
+
+	/// Number of octets held for incomplete datagrams.
+	pub fn memory(&self) -> usize {
+		self.table.memory()
+	}
+
+	/// Add a packet, returning the datagram once all its fragments have been
+	/// received.
+	///
+	/// Packets that are not fragments are returned as is. Duplicate fragments
+	/// are ignored, while a fragment that is inconsistent with the ones already
+	/// received, or more than 64 fragments, drops the whole datagram.
+	pub fn push<B: AsRef<[u8]>>(&mut self, timestamp: Duration, packet: &Packet<B>) -> Result<Option<Packet<Vec<u8>>>> {
+		self.expire(timestamp);
+
+		let more   = packet.flags().contains(Flags::MORE_FRAGMENTS);
+		let offset = packet.offset() as usize * 8;
+
+		if offset == 0 && !more {
+			return Ok(Some(packet.to_owned()));
+		}
+
+		let (header, payload) = packet.split();
+
+		if (more && !payload.len().is_multiple_of(8)) || header.len() + offset + payload.len() > 0xffff {
+			Err(Error::InvalidPacket)?
+		}
+
+		let first     = if offset == 0 { Some(header) } else { None };
+		let fragments = match self.table.insert(timestamp, Key::from(packet), first, offset, payload, !more)? {
+			Some(fragments) => fragments,
+			None            => return Ok(None),
+		};
+
+		let header  = fragments.header().unwrap();
+		let payload = fragments.assemble(self.policy);
+
+		// The first fragment can carry more options than the others, so the
+		// datagram may not fit even if every fragment did.
+		if header.len() + payload.len() > 0xffff {
+			Err(Error::InvalidPacket)?
+		}
+
+		let mut buffer = Vec::with_capacity(header.len() + payload.len());
+		buffer.extend_from_slice(header);
+		buffer.extend_from_slice(&payload);
+
+		let length = buffer.len() as u16;
+		BigEndian::write_u16(&mut buffer[2 ..], length);
+
+		let mut packet = Packet::unchecked(buffer);
+		let flags      = packet.flags() - Flags::MORE_FRAGMENTS;
+		packet.set_flags(flags)?;
+		packet.set_offset(0)?;
+		packet.update_checksum()?;
+
+		Ok(Some(packet))
+	}
+
+	/// Drop the incomplete datagrams older than the timeout, returning how
+	/// many were dropped.
+	pub fn expire(&mut self, now: Duration) -> usize {
+		self.table.expire(now)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use crate::builder::Builder;
+	use crate::packet::Packet;
+	use crate::ip;
+
+	fn build(id: u16) -> ip::v4::Packet<Vec<u8>> {
+		ip::v4::Packet::new(ip::v4::Builder::default()
+			.id(id).unwrap()
+			.source("10.0.0.1".parse().unwrap()).unwrap()
+			.destination("10.0.0.2".parse().unwrap()).unwrap()
+			.record_route(2).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.payload((0 .. 200u8).collect::<Vec<_>>().iter()).unwrap()
+				.build().unwrap()).unwrap()
+	}
+
+	#[test]
+	fn reassemble() {
+		let packet    = build(0x2d87);
+		let fragments = ip::v4::fragment(&packet, 60).unwrap();
+		assert_eq!(fragments.len(), 6);
+
+		let mut reassembler = ip::v4::Reassembler::default();
+		let second = Duration::from_secs(1);
+
+		for fragment in fragments.iter().skip(1).rev() {
+			assert!(reassembler.push(second, fragment).unwrap().is_none());
+		}
+
+		assert_eq!(reassembler.len(), 1);
+		assert!(reassembler.memory() > 0);
+
+		let datagram = reassembler.push(second, &fragments[0]).unwrap().unwrap();
+		assert_eq!(datagram.as_ref(), packet.as_ref());
+		assert!(datagram.is_valid());
+		assert!(reassembler.is_empty());
+		assert_eq!(reassembler.memory(), 0);
+
+		let whole = reassembler.push(second, &packet).unwrap().unwrap();
+		assert_eq!(whole.as_ref(), packet.as_ref());
+	}
+
+	#[test]
+	fn expire() {
+		let fragments = ip::v4::fragment(&build(1), 60).unwrap();
+
+		let mut reassembler = ip::v4::Reassembler::default()
+			.timeout(Duration::from_secs(10));
+
+		assert!(reassembler.push(Duration::from_secs(0), &fragments[0]).unwrap().is_none());
+		assert_eq!(reassembler.expire(Duration::from_secs(5)), 0);
+		assert!(reassembler.push(Duration::from_secs(11), &fragments[1]).unwrap().is_none());
+		assert_eq!(reassembler.len(), 1);
+		assert_eq!(reassembler.memory(), fragments[1].payload().len());
+	}
+
+	#[test]
+	fn limit() {
+		let first  = ip::v4::fragment(&build(1), 60).unwrap();
+		let second = ip::v4::fragment(&build(2), 60).unwrap();
+
+		let mut reassembler = ip::v4::Reassembler::default()
+			.limit(100);
+
+		reassembler.push(Duration::from_secs(0), &first[0]).unwrap();
+		reassembler.push(Duration::from_secs(1), &second[1]).unwrap();
+		reassembler.push(Duration::from_secs(2), &second[2]).unwrap();
+		assert_eq!(reassembler.len(), 1);
+		assert!(reassembler.memory() <= 100);
+	}
+
+	#[test]
+	fn inconsistent() {
+		let fragments = ip::v4::fragment(&build(1), 60).unwrap();
+		let mut reassembler = ip::v4::Reassembler::default();
+
+		reassembler.push(Duration::from_secs(0), &fragments[5]).unwrap();
+		reassembler.push(Duration::from_secs(0), &fragments[0]).unwrap();
+		assert!(reassembler.push(Duration::from_secs(0), &fragments[4]).unwrap().is_none());
+
+		let mut bogus = fragments[5].clone();
+		bogus.set_offset(fragments[5].offset() + 1).unwrap();
+		assert!(reassembler.push(Duration::from_secs(0), &bogus).is_err());
+		assert!(reassembler.is_empty());
+		assert_eq!(reassembler.memory(), 0);
+	}
+
+	#[test]
+	fn oversized() {
+		let first = ip::v4::Packet::new(ip::v4::Builder::default()
+			.id(1).unwrap()
+			.flags(ip::v4::Flags::MORE_FRAGMENTS).unwrap()
+			.record_route(9).unwrap()
+			.protocol(ip::Protocol::Udp).unwrap()
+			.payload(&[0; 8]).unwrap()
+			.build().unwrap()).unwrap();
+
+		let last = ip::v4::Packet::new(ip::v4::Builder::default()
+			.id(1).unwrap()
+			.offset(1).unwrap()
+			.protocol(ip::Protocol::Udp).unwrap()
+			.payload(&vec![0; 65500]).unwrap()
+			.build().unwrap()).unwrap();
+
+		assert_eq!(first.header(), 15);
+
+		let mut reassembler = ip::v4::Reassembler::default();
+		assert!(reassembler.push(Duration::from_secs(0), &last).unwrap().is_none());
+		assert!(reassembler.push(Duration::from_secs(0), &first).is_err());
+		assert!(reassembler.is_empty());
+	}
+}
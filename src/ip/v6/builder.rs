@@ -12,24 +12,49 @@
 //
 //  0. You just DO WHAT THE FUCK YOU WANT TO.
 
-
-
+use std::io::Cursor;
+use std::net::Ipv6Addr;
+use byteorder::{WriteBytesExt, BigEndian};
 
 use crate::error::*;
 use crate::buffer::{self, Buffer};
 use crate::builder::{Builder as Build, Finalization};
+use crate::packet::{AsPacket, AsPacketMut};
+use crate::ip::Protocol;
+use crate::ip::v6::Packet;
+use crate::ip::v6::{hop_by_hop, destination, routing, fragment};
+use crate::ip::v6::option::Number;
 
 /// IPv6 packet builder.
+#[derive(Debug)]
 pub struct Builder<B: Buffer = buffer::Dynamic> {
 	buffer:    B,
 	finalizer: Finalization,
+
+	next:    usize,
+	jumbo:   bool,
+	payload: bool,
 }
 
 impl<B: Buffer> Build<B> for Builder<B> {
-	fn with(buffer: B) -> Result<Self> {
+	fn with(mut buffer: B) -> Result<Self> {
+		use crate::size::header::Min;
+		buffer.next(Packet::<()>::min())?;
+
+		// Set version to 6, the TCP and UDP builders base their finalizer on
+		// extracting the parent IP packet.
+		buffer.data_mut()[0] = 6 << 4;
+
+		// Default to no next header, in case no payload is defined.
+		buffer.data_mut()[6] = Protocol::Ipv6NoNxt.into();
+
 		Ok(Builder {
 			buffer:    buffer,
 			finalizer: Default::default(),
+
+			next:    6,
+			jumbo:   false,
+			payload: false,
 		})
 	}
 
@@ -37,8 +62,12 @@ impl<B: Buffer> Build<B> for Builder<B> {
 		&mut self.finalizer
 	}
 
-	fn build(self) -> Result<B::Inner> {
-		Err(Error::InvalidPacket)
+	fn build(mut self) -> Result<B::Inner> {
+		self.prepare();
+
+		let mut buffer = self.buffer.into_inner();
+		self.finalizer.finalize(buffer.as_mut())?;
+		Ok(buffer)
 	}
 }
 
@@ -47,3 +76,528 @@ impl Default for Builder<buffer::Dynamic> {
 		Builder::with(buffer::Dynamic::default()).unwrap()
 	}
 }
+
+impl<'a, B: Buffer> AsPacket<'a, Packet<&'a [u8]>> for Builder<B> {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.buffer.data())
+	}
+}
+
+impl<'a, B: Buffer> AsPacketMut<'a, Packet<&'a mut [u8]>> for Builder<B> {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.buffer.data_mut())
+	}
+}
+
+macro_rules! protocol {
+	($(#[$attr:meta])* fn $module:ident($protocol:ident)) => (
+		$(#[$attr])*
+		pub fn $module(mut self) -> Result<crate::$module::Builder<B>> {
+			if self.payload {
+				Err(Error::AlreadyDefined)?
+			}
+
+			self = self.next_header(Protocol::$protocol)?;
+			self.prepare();
+
+			let mut builder = crate::$module::Builder::with(self.buffer)?;
+			builder.finalizer().extend(self.finalizer);
+
+			Ok(builder)
+		}
+	)
+}
+
+impl<B: Buffer> Builder<B> {
+	/// Traffic class.
+	pub fn traffic_class(mut self, value: u8) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_traffic_class(value)?;
+		Ok(self)
+	}
+
+	/// Differentiated Services Code Point.
+	pub fn dscp(mut self, value: u8) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_dscp(value)?;
+		Ok(self)
+	}
+
+	/// Explicit Congestion Notification.
+	pub fn ecn(mut self, value: u8) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_ecn(value)?;
+		Ok(self)
+	}
+
+	/// Flow label.
+	pub fn flow_label(mut self, value: u32) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_flow_label(value)?;
+		Ok(self)
+	}
+
+	/// Hop limit.
+	pub fn hop_limit(mut self, value: u8) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_hop_limit(value)?;
+		Ok(self)
+	}
+
+	/// Source address.
+	pub fn source(mut self, value: Ipv6Addr) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_source(value)?;
+		Ok(self)
+	}
+
+	/// Destination address.
+	pub fn destination(mut self, value: Ipv6Addr) -> Result<Self> {
+		Packet::unchecked(self.buffer.data_mut()).set_destination(value)?;
+		Ok(self)
+	}
+
+	/// Protocol of the next header, when extension headers are present this
+	/// is set on the last one.
+	pub fn next_header(mut self, value: Protocol) -> Result<Self> {
+		let next = self.next;
+		self.buffer.data_mut()[next] = value.into();
+
+		Ok(self)
+	}
+
+	/// Add a Hop-by-Hop Options header, it has to be the first extension
+	/// header.
+	pub fn hop_by_hop(mut self) -> Result<hop_by_hop::Builder<B>> {
+		if self.next != 6 {
+			Err(Error::InvalidPacket)?
+		}
+
+		let offset = self.extension(Protocol::Hopopt, 2)?;
+		Ok(hop_by_hop::Builder::new(self, offset))
+	}
+
+	/// Add a Destination Options header.
+	pub fn destination_options(mut self) -> Result<destination::Builder<B>> {
+		let offset = self.extension(Protocol::Ipv6Opts, 2)?;
+		Ok(destination::Builder::new(self, offset))
+	}
+
+	/// Add a Routing header of the given type.
+	pub fn routing(mut self, kind: routing::Kind) -> Result<routing::Builder<B>> {
+		let offset = self.extension(Protocol::Ipv6Route, 8)?;
+		self.buffer.data_mut()[offset + 2] = kind.into();
+
+		Ok(routing::Builder::new(self, offset))
+	}
+
+	/// Add a Fragment header.
+	pub fn fragment(mut self) -> Result<fragment::Builder<B>> {
+		let offset = self.extension(Protocol::Ipv6Frag, 8)?;
+		Ok(fragment::Builder::new(self, offset))
+	}
+
+	/// Payload for the packet, the next header has to be set first.
+	pub fn payload<'a, T: IntoIterator<Item = &'a u8>>(mut self, value: T) -> Result<Self> {
+		if self.payload {
+			Err(Error::AlreadyDefined)?
+		}
+
+		if self.buffer.data()[self.next] == Protocol::Ipv6NoNxt.into() {
+			Err(Error::InvalidPacket)?
+		}
+
+		self.payload = true;
+
+		for byte in value {
+			self.buffer.more(1)?;
+			*self.buffer.data_mut().last_mut().unwrap() = *byte;
+		}
+
+		Ok(self)
+	}
+
+	/// Append an extension header of the given size, chaining it to the
+	/// previous header, and return its offset within the layer.
+	pub(in crate::ip::v6) fn extension(&mut self, protocol: Protocol, size: usize) -> Result<usize> {
+		if self.payload {
+			Err(Error::AlreadyDefined)?
+		}
+
+		let offset = self.buffer.length();
+		self.buffer.more(size)?;
+
+		let next = self.next;
+		let data = self.buffer.data_mut();
+		data[next]   = protocol.into();
+		data[offset] = Protocol::Ipv6NoNxt.into();
+
+		self.next = offset;
+
+		Ok(offset)
+	}
+
+	/// Get the extension header at the given offset within the layer.
+	pub(in crate::ip::v6) fn header_mut(&mut self, offset: usize) -> &mut [u8] {
+		&mut self.buffer.data_mut()[offset ..]
+	}
+
+	/// Append data to the extension header being built.
+	pub(in crate::ip::v6) fn append(&mut self, value: &[u8]) -> Result<()> {
+		let offset = self.buffer.length();
+		self.buffer.more(value.len())?;
+		self.buffer.data_mut()[offset ..].copy_from_slice(value);
+
+		Ok(())
+	}
+
+	/// Append an option to the options header at the given offset, aligning it
+	/// to `x * n + y` octets from the start of the header, and return the
+	/// offset of the option within the layer.
+	pub(in crate::ip::v6) fn option(&mut self, offset: usize, align: (usize, usize), number: Number, value: &[u8]) -> Result<usize> {
+		if value.len() > u8::MAX as usize {
+			Err(Error::InvalidValue)?
+		}
+
+		let (x, y)   = align;
+		let position = self.buffer.length() - offset;
+		self.padding((x + y - position % x) % x)?;
+
+		let start = self.buffer.length();
+		self.append(&[number.into(), value.len() as u8])?;
+		self.append(value)?;
+
+		Ok(start)
+	}
+
+	/// Close the options header at the given offset, padding it to a multiple
+	/// of 8 octets and setting its length.
+	pub(in crate::ip::v6) fn options(&mut self, offset: usize) -> Result<()> {
+		let length = self.buffer.length() - offset;
+		self.padding((8 - length % 8) % 8)?;
+
+		let length = (self.buffer.length() - offset) / 8 - 1;
+		if length > u8::MAX as usize {
+			Err(Error::InvalidPacket)?
+		}
+
+		self.buffer.data_mut()[offset + 1] = length as u8;
+
+		Ok(())
+	}
+
+	/// Append padding to the options header, using Pad1 for a single octet
+	/// and PadN for anything bigger.
+	fn padding(&mut self, size: usize) -> Result<()> {
+		match size {
+			0 =>
+				Ok(()),
+
+			1 =>
+				self.append(&[Number::Pad1.into()]),
+
+			n => {
+				self.append(&[Number::PadN.into(), (n - 2) as u8])?;
+				self.append(&vec![0; n - 2])
+			}
+		}
+	}
+
+	/// Add a Jumbo Payload option at the given offset, the payload length is
+	/// written in the option instead of the fixed header.
+	pub(in crate::ip::v6) fn jumbo(&mut self, offset: usize) -> Result<()> {
+		let option = self.option(offset, (4, 2), Number::JumboPayload, &[0; 4])?;
+		let offset = self.buffer.offset();
+
+		self.jumbo = true;
+		self.finalizer.add(move |out| {
+			let length = out.len() - (offset + 40);
+
+			if length <= u16::MAX as usize || length > u32::MAX as usize {
+				Err(Error::InvalidPacket)?
+			}
+
+			Cursor::new(&mut out[offset + option + 2 ..])
+				.write_u32::<BigEndian>(length as u32)?;
+
+			Ok(())
+		});
+
+		Ok(())
+	}
+
+	fn prepare(&mut self) {
+		let offset = self.buffer.offset();
+		let jumbo  = self.jumbo;
+
+		self.finalizer.add(move |out| {
+			// Calculate and write the payload length, anything following the
+			// fixed header is payload, the length is zero for jumbograms.
+			let length = if jumbo {
+				0
+			}
+			else {
+				out.len() - (offset + 40)
+			};
+
+			if length > u16::MAX as usize {
+				Err(Error::InvalidPacket)?
+			}
+
+			Cursor::new(&mut out[offset + 4 ..])
+				.write_u16::<BigEndian>(length as u16)?;
+
+			Ok(())
+		});
+	}
+
+	protocol!(/// Build an ICMP packet.
+		fn icmp(Icmp));
+
+	protocol!(/// Build an ICMPv6 packet.
+		fn icmpv6(Ipv6Icmp));
+
+	protocol!(/// Build a TCP packet.
+		fn tcp(Tcp));
+
+	protocol!(/// Build a UDP packet.
+		fn udp(Udp));
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::Ipv6Addr;
+	use crate::builder::Builder;
+	use crate::packet::Packet;
+	use crate::ether;
+	use crate::ip;
+	use crate::icmp;
+	use crate::udp;
+
+	#[test]
+	fn payload() {
+		let packet = ip::v6::Builder::default()
+			.traffic_class(0xb8).unwrap()
+			.flow_label(0x12345).unwrap()
+			.hop_limit(64).unwrap()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.next_header(ip::Protocol::Udp).unwrap()
+			.payload(&[0x05, 0x39, 0x00, 0x35, 0x00, 0x08, 0x00, 0x00]).unwrap()
+			.build().unwrap();
+
+		let packet = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(packet.version(), 6);
+		assert_eq!(packet.traffic_class(), 0xb8);
+		assert_eq!(packet.flow_label(), 0x12345);
+		assert_eq!(packet.length(), 8);
+		assert_eq!(packet.next_header(), ip::Protocol::Udp);
+		assert_eq!(packet.hop_limit(), 64);
+		assert_eq!(packet.source(), "2001:db8::1".parse::<Ipv6Addr>().unwrap());
+		assert_eq!(packet.destination(), "2001:db8::2".parse::<Ipv6Addr>().unwrap());
+		assert_eq!(packet.payload(), &[0x05, 0x39, 0x00, 0x35, 0x00, 0x08, 0x00, 0x00]);
+	}
+
+	#[test]
+	fn empty() {
+		let packet = ip::v6::Builder::default()
+			.source("::1".parse().unwrap()).unwrap()
+			.destination("::1".parse().unwrap()).unwrap()
+			.build().unwrap();
+
+		let packet = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(packet.length(), 0);
+		assert_eq!(packet.next_header(), ip::Protocol::Ipv6NoNxt);
+
+		assert!(ip::v6::Builder::default().payload(b"lol").is_err());
+	}
+
+	#[test]
+	fn icmp() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(64).unwrap()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.icmp().unwrap()
+				.echo().unwrap().request().unwrap()
+					.identifier(42).unwrap()
+					.sequence(2).unwrap()
+					.payload(b"test").unwrap()
+					.build().unwrap();
+
+		let packet = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(packet.length(), 12);
+		assert_eq!(packet.next_header(), ip::Protocol::Icmp);
+
+		let icmp = icmp::Packet::new(packet.payload()).unwrap();
+		assert_eq!(icmp.kind(), icmp::Kind::EchoRequest);
+		assert!(icmp.is_valid());
+	}
+
+	#[test]
+	fn ether() {
+		let packet = ether::Builder::default()
+			.destination("00:23:69:63:59:be".parse().unwrap()).unwrap()
+			.source("e4:b3:18:26:63:a3".parse().unwrap()).unwrap()
+			.ip().unwrap().v6().unwrap()
+				.hop_limit(64).unwrap()
+				.source("2001:db8::1".parse().unwrap()).unwrap()
+				.destination("2001:db8::2".parse().unwrap()).unwrap()
+				.next_header(ip::Protocol::Test1).unwrap()
+				.payload(b"lol").unwrap()
+				.build().unwrap();
+
+		let ether = ether::Packet::new(packet).unwrap();
+		assert_eq!(ether.protocol(), ether::Protocol::Ipv6);
+
+		let ip = ip::v6::Packet::new(ether.payload()).unwrap();
+		assert_eq!(ip.length(), 3);
+		assert_eq!(ip.next_header(), ip::Protocol::Test1);
+		assert_eq!(ip.hop_limit(), 64);
+		assert_eq!(ip.payload(), b"lol");
+	}
+
+	#[test]
+	fn extensions() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(64).unwrap()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.hop_by_hop().unwrap()
+				.router_alert(0).unwrap()
+			.routing(ip::v6::routing::Kind::SegmentRouting).unwrap()
+				.segments_left(1).unwrap()
+				.tag(42).unwrap()
+				.address("2001:db8::3".parse().unwrap()).unwrap()
+				.address("2001:db8::2".parse().unwrap()).unwrap()
+			.fragment().unwrap()
+				.identification(0xdeadbeef).unwrap()
+			.destination_options().unwrap()
+				.option(ip::v6::option::Number::Unknown(0x1e), &[0x01, 0x02]).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.payload(b"lol").unwrap()
+				.build().unwrap();
+
+		let ip = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(ip.length(), 8 + 40 + 8 + 8 + 11);
+		assert_eq!(ip.upper_layer().unwrap(), (ip::Protocol::Udp, 104));
+
+		let extensions = ip.extensions().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(extensions.len(), 4);
+
+		if let ip::v6::Extension::HopByHop(ref header) = extensions[0] {
+			assert_eq!(header.length(), 0);
+
+			let options = header.options().collect::<Result<Vec<_>, _>>().unwrap();
+			assert_eq!(options.len(), 2);
+			assert_eq!(options[0].number(), ip::v6::option::Number::RouterAlert);
+			assert_eq!(options[0].payload(), &[0x00, 0x00]);
+			assert_eq!(options[1].number(), ip::v6::option::Number::PadN);
+		}
+		else {
+			panic!("not a Hop-by-Hop Options header");
+		}
+
+		if let ip::v6::Extension::Routing(ref header) = extensions[1] {
+			assert_eq!(header.length(), 4);
+			assert_eq!(header.kind(), ip::v6::routing::Kind::SegmentRouting);
+			assert_eq!(header.segments_left(), 1);
+			assert_eq!(header.last_entry(), 1);
+			assert_eq!(header.tag(), 42);
+			assert_eq!(header.final_destination(), Some("2001:db8::3".parse().unwrap()));
+		}
+		else {
+			panic!("not a Routing header");
+		}
+
+		if let ip::v6::Extension::Fragment(ref header) = extensions[2] {
+			assert_eq!(header.identification(), 0xdeadbeef);
+			assert!(header.is_atomic());
+		}
+		else {
+			panic!("not a Fragment header");
+		}
+
+		if let ip::v6::Extension::Destination(ref header) = extensions[3] {
+			let options = header.options().collect::<Result<Vec<_>, _>>().unwrap();
+			assert_eq!(options.len(), 2);
+			assert_eq!(options[0].payload(), &[0x01, 0x02]);
+			assert_eq!(options[1].number(), ip::v6::option::Number::PadN);
+			assert_eq!(options[1].length(), 0);
+		}
+		else {
+			panic!("not a Destination Options header");
+		}
+
+		let udp = udp::Packet::new(&ip.payload()[64 ..]).unwrap();
+		assert_eq!(udp.payload(), b"lol");
+		assert!(udp.is_valid(&ip::Packet::from(&ip)));
+
+		// The checksum uses the final destination, like a packet sent straight
+		// to it.
+		let direct = ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::3".parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.payload(b"lol").unwrap()
+				.build().unwrap();
+
+		let direct = ip::v6::Packet::new(direct).unwrap();
+		assert_eq!(udp.checksum(), udp::Packet::new(direct.payload()).unwrap().checksum());
+
+		assert!(ip::v6::Builder::default()
+			.routing(ip::v6::routing::Kind::SegmentRouting).unwrap()
+			.build().is_err());
+	}
+
+	#[test]
+	fn padding() {
+		let packet = ip::v6::Builder::default()
+			.destination_options().unwrap()
+				.option(ip::v6::option::Number::Unknown(0x1e), &[0x01]).unwrap()
+				.option(ip::v6::option::Number::Unknown(0x1f), &[0x02]).unwrap()
+			.build().unwrap();
+
+		let ip = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(ip.length(), 8);
+		assert_eq!(ip.next_header(), ip::Protocol::Ipv6Opts);
+		assert_eq!(ip.payload(), &[0x3b, 0x00, 0x1e, 0x01, 0x01, 0x1f, 0x01, 0x02]);
+
+		let packet = ip::v6::Builder::default()
+			.destination_options().unwrap()
+				.option(ip::v6::option::Number::Unknown(0x1e), &[0x01, 0x02, 0x03]).unwrap()
+			.build().unwrap();
+
+		let ip = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(ip.payload(), &[0x3b, 0x00, 0x1e, 0x03, 0x01, 0x02, 0x03, 0x00]);
+	}
+
+	#[test]
+	fn jumbo() {
+		let packet = ip::v6::Builder::default()
+			.hop_by_hop().unwrap()
+				.jumbo_payload().unwrap()
+			.next_header(ip::Protocol::Test1).unwrap()
+			.payload(&vec![0; 70000]).unwrap()
+			.build().unwrap();
+
+		let ip = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(ip.length(), 0);
+		assert!(ip.is_jumbo());
+		assert_eq!(ip.payload().len(), 70008);
+
+		if let Some(Ok(ip::v6::Extension::HopByHop(header))) = ip.extensions().next() {
+			let option = header.options().next().unwrap().unwrap();
+			assert_eq!(option.number(), ip::v6::option::Number::JumboPayload);
+			assert_eq!(option.payload(), &[0x00, 0x01, 0x11, 0x78]);
+		}
+		else {
+			panic!("not a Hop-by-Hop Options header");
+		}
+
+		assert!(ip::v6::Builder::default()
+			.hop_by_hop().unwrap()
+				.jumbo_payload().unwrap()
+			.next_header(ip::Protocol::Test1).unwrap()
+			.payload(b"lol").unwrap()
+			.build().is_err());
+	}
+}
@@ -0,0 +1,47 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use crate::error::*;
+use crate::buffer::{self, Buffer};
+use crate::ip;
+use crate::ip::v6::option::Number;
+
+/// Destination Options header builder.
+#[derive(Debug)]
+pub struct Builder<B: Buffer = buffer::Dynamic> {
+	ip:     ip::v6::Builder<B>,
+	offset: usize,
+}
+
+impl<B: Buffer> Builder<B> {
+	pub(in crate::ip::v6) fn new(ip: ip::v6::Builder<B>, offset: usize) -> Self {
+		Builder {
+			ip,
+			offset,
+		}
+	}
+
+	/// Add an option with the given value.
+	pub fn option(mut self, number: Number, value: &[u8]) -> Result<Self> {
+		self.ip.option(self.offset, (1, 0), number, value)?;
+		Ok(self)
+	}
+
+	fn close(mut self) -> Result<ip::v6::Builder<B>> {
+		self.ip.options(self.offset)?;
+		Ok(self.ip)
+	}
+
+	extension!();
+}
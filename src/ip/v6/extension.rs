@@ -0,0 +1,66 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use crate::packet::Packet as P;
+use crate::ip::Protocol;
+use crate::ip::v6::{hop_by_hop, routing, fragment, destination};
+
+/// IPv6 extension header.
+#[derive(Debug)]
+pub enum Extension<B: AsRef<[u8]>> {
+	/// Hop-by-Hop Options header.
+	HopByHop(hop_by_hop::Packet<B>),
+
+	/// Routing header.
+	Routing(routing::Packet<B>),
+
+	/// Fragment header.
+	Fragment(fragment::Packet<B>),
+
+	/// Destination Options header.
+	Destination(destination::Packet<B>),
+}
+
+impl<B: AsRef<[u8]>> Extension<B> {
+	/// Protocol number identifying the extension header.
+	pub fn protocol(&self) -> Protocol {
+		match *self {
+			Extension::HopByHop(_)    => Protocol::Hopopt,
+			Extension::Routing(_)     => Protocol::Ipv6Route,
+			Extension::Fragment(_)    => Protocol::Ipv6Frag,
+			Extension::Destination(_) => Protocol::Ipv6Opts,
+		}
+	}
+
+	/// Protocol of the next header.
+	pub fn next_header(&self) -> Protocol {
+		match *self {
+			Extension::HopByHop(ref packet)    => packet.next_header(),
+			Extension::Routing(ref packet)     => packet.next_header(),
+			Extension::Fragment(ref packet)    => packet.next_header(),
+			Extension::Destination(ref packet) => packet.next_header(),
+		}
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Extension<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		match *self {
+			Extension::HopByHop(ref packet)    => packet.split(),
+			Extension::Routing(ref packet)     => packet.split(),
+			Extension::Fragment(ref packet)    => packet.split(),
+			Extension::Destination(ref packet) => packet.split(),
+		}
+	}
+}
@@ -0,0 +1,58 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use crate::error::*;
+use crate::buffer::{self, Buffer};
+use crate::ip;
+use crate::ip::v6::fragment::Packet;
+
+/// Fragment header builder.
+#[derive(Debug)]
+pub struct Builder<B: Buffer = buffer::Dynamic> {
+	ip:     ip::v6::Builder<B>,
+	offset: usize,
+}
+
+impl<B: Buffer> Builder<B> {
+	pub(in crate::ip::v6) fn new(ip: ip::v6::Builder<B>, offset: usize) -> Self {
+		Builder {
+			ip,
+			offset,
+		}
+	}
+
+	/// Fragment offset in 8 octet units.
+	pub fn offset(mut self, value: u16) -> Result<Self> {
+		Packet::unchecked(self.ip.header_mut(self.offset)).set_offset(value)?;
+		Ok(self)
+	}
+
+	/// More fragments follow.
+	pub fn more(mut self, value: bool) -> Result<Self> {
+		Packet::unchecked(self.ip.header_mut(self.offset)).set_more(value)?;
+		Ok(self)
+	}
+
+	/// Fragment identification.
+	pub fn identification(mut self, value: u32) -> Result<Self> {
+		Packet::unchecked(self.ip.header_mut(self.offset)).set_identification(value)?;
+		Ok(self)
+	}
+
+	fn close(self) -> Result<ip::v6::Builder<B>> {
+		Ok(self.ip)
+	}
+
+	extension!();
+}
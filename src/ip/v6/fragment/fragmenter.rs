@@ -0,0 +1,154 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use byteorder::{ByteOrder, BigEndian};
+
+use crate::error::*;
+use crate::ip::Protocol;
+use crate::ip::v6::{Packet, Extension};
+
+/// Split the packet in fragments fitting in the given MTU, using the given
+/// identification.
+///
+/// The unfragmentable part, the IPv6 header and any Hop-by-Hop or Routing
+/// header with the Destination Options before them, is repeated in every
+/// fragment. A packet that already fits is returned as is.
+///
+/// # Errors
+///
+/// Fails with `Error::InvalidPacket` if the packet is a jumbogram or already
+/// has a Fragment header, and with `Error::InvalidValue` if the MTU cannot
+/// fit at least 8 octets of fragment data.
+pub fn fragment<B: AsRef<[u8]>>(packet: &Packet<B>, mtu: usize, identification: u32) -> Result<Vec<Packet<Vec<u8>>>> {
+	let buffer = packet.as_ref();
+
+	if buffer.len() <= mtu {
+		return Ok(vec![packet.to_owned()]);
+	}
+
+	if packet.is_jumbo() {
+		Err(Error::InvalidPacket)?
+	}
+
+	// Find where the unfragmentable part ends and which header carries the
+	// Next Header field to point at the Fragment header.
+	let mut extensions = packet.extensions();
+	let mut end        = 40;
+	let mut next       = 6;
+
+	loop {
+		let start = extensions.offset();
+
+		match extensions.next() {
+			Some(Ok(Extension::HopByHop(_))) | Some(Ok(Extension::Routing(_))) => {
+				end  = extensions.offset();
+				next = start;
+			}
+
+			Some(Ok(Extension::Fragment(_))) =>
+				Err(Error::InvalidPacket)?,
+
+			Some(Ok(Extension::Destination(_))) =>
+				(),
+
+			Some(Err(error)) =>
+				Err(error)?,
+
+			None =>
+				break,
+		}
+	}
+
+	let (unfragmentable, data) = buffer.split_at(end);
+	let protocol = Protocol::from(buffer[next]);
+	let room     = mtu.saturating_sub(end + 8) / 8 * 8;
+
+	if room == 0 {
+		Err(Error::InvalidValue)?
+	}
+
+	let mut fragments = Vec::new();
+	let mut position  = 0;
+
+	while position < data.len() {
+		let length = room.min(data.len() - position);
+
+		let mut buffer = Vec::with_capacity(end + 8 + length);
+		buffer.extend_from_slice(unfragmentable);
+		buffer.extend_from_slice(&[0; 8]);
+		buffer.extend_from_slice(&data[position .. position + length]);
+		buffer[next] = Protocol::Ipv6Frag.into();
+		BigEndian::write_u16(&mut buffer[4 ..], (end - 40 + 8 + length) as u16);
+
+		crate::ip::v6::fragment::Packet::unchecked(&mut buffer[end .. end + 8])
+			.set_next_header(protocol)?
+			.set_offset((position / 8) as u16)?
+			.set_more(position + length < data.len())?
+			.set_identification(identification)?;
+
+		fragments.push(Packet::unchecked(buffer));
+		position += length;
+	}
+
+	Ok(fragments)
+}
+
+#[cfg(test)]
+mod test {
+	use crate::builder::Builder;
+	use crate::packet::Packet;
+	use crate::ip;
+
+	#[test]
+	fn fragment() {
+		let packet = ip::v6::Packet::new(ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.hop_by_hop().unwrap()
+				.router_alert(0).unwrap()
+			.destination_options().unwrap()
+				.option(ip::v6::option::Number::Unknown(0x1e), &[0x01, 0x02]).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.payload(&[0x42; 200]).unwrap()
+				.build().unwrap()).unwrap();
+
+		let fragments = ip::v6::fragment(&packet, 120, 0xdeadbeef).unwrap();
+		assert_eq!(fragments.len(), 4);
+		assert_eq!(fragments.iter().map(|f| f.length()).collect::<Vec<_>>(), vec![80, 80, 80, 40]);
+
+		for (i, fragment) in fragments.iter().enumerate() {
+			let extensions = fragment.extensions().collect::<Result<Vec<_>, _>>().unwrap();
+			assert_eq!(extensions[0].next_header(), ip::Protocol::Ipv6Frag);
+
+			if let ip::v6::Extension::Fragment(ref header) = extensions[1] {
+				assert_eq!(header.next_header(), ip::Protocol::Ipv6Opts);
+				assert_eq!(header.offset(), i as u16 * 8);
+				assert_eq!(header.more(), i != 3);
+				assert_eq!(header.identification(), 0xdeadbeef);
+			}
+			else {
+				panic!("not a Fragment header");
+			}
+		}
+
+		let data = fragments.iter().flat_map(|f| f.payload()[16 ..].to_vec()).collect::<Vec<_>>();
+		assert_eq!(&data[..], &packet.payload()[8 ..]);
+
+		assert_eq!(ip::v6::fragment(&packet, 1280, 1).unwrap()[0].as_ref(), packet.as_ref());
+		assert!(ip::v6::fragment(&packet, 56, 1).is_err());
+		assert!(ip::v6::fragment(&fragments[0], 64, 1).is_err());
+	}
+}
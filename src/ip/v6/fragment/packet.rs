@@ -0,0 +1,220 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::io::Cursor;
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
+
+use crate::error::*;
+use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::ip::Protocol;
+
+/// Fragment header parser.
+pub struct Packet<B> {
+	buffer: B,
+}
+
+sized!(Packet,
+	header {
+		min:  8,
+		max:  8,
+		size: 8,
+	}
+
+	payload {
+		min:  0,
+		size: p => p.buffer.as_ref().len() - 8,
+	});
+
+impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ip::v6::fragment::Packet")
+			.field("next_header", &self.next_header())
+			.field("offset", &self.offset())
+			.field("more", &self.more())
+			.field("identification", &self.identification())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Create a Fragment header without checking the buffer.
+	pub fn unchecked(buffer: B) -> Packet<B> {
+		Packet { buffer }
+	}
+
+	/// Parse a Fragment header, checking the buffer contents are correct.
+	pub fn new(buffer: B) -> Result<Packet<B>> {
+		use crate::size::header::Min;
+
+		let packet = Packet::unchecked(buffer);
+
+		if packet.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		Ok(packet)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Convert the packet to its owned version.
+	///
+	/// # Notes
+	///
+	/// It would be nice if `ToOwned` could be implemented, but `Packet` already
+	/// implements `Clone` and the impl would conflict.
+	pub fn to_owned(&self) -> Packet<Vec<u8>> {
+		Packet::unchecked(self.buffer.as_ref().to_vec())
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
+	fn as_ref(&self) -> &[u8] {
+		&self.buffer.as_ref()[.. 8]
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
+	fn as_mut(&mut self) -> &mut [u8] {
+		&mut self.buffer.as_mut()[.. 8]
+	}
+}
+
+impl<'a, B: AsRef<[u8]>> AsPacket<'a, Packet<&'a [u8]>> for B {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.as_ref())
+	}
+}
+
+impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Packet<&'a mut [u8]>> for B {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.as_mut())
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Packet<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		self.buffer.as_ref().split_at(8)
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Packet<B> {
+	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+		self.buffer.as_mut().split_at_mut(8)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Protocol of the next header.
+	pub fn next_header(&self) -> Protocol {
+		self.buffer.as_ref()[0].into()
+	}
+
+	/// Offset of the fragment in 8 octet units.
+	pub fn offset(&self) -> u16 {
+		(&self.buffer.as_ref()[2 ..]).read_u16::<BigEndian>().unwrap() >> 3
+	}
+
+	/// Whether more fragments follow.
+	pub fn more(&self) -> bool {
+		self.buffer.as_ref()[3] & 0b1 == 1
+	}
+
+	/// Identification of the fragmented packet.
+	pub fn identification(&self) -> u32 {
+		(&self.buffer.as_ref()[4 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Whether the fragment header is the only fragment of the packet.
+	pub fn is_atomic(&self) -> bool {
+		self.offset() == 0 && !self.more()
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
+	/// Protocol of the next header.
+	pub fn set_next_header(&mut self, value: Protocol) -> Result<&mut Self> {
+		self.buffer.as_mut()[0] = value.into();
+
+		Ok(self)
+	}
+
+	/// Offset of the fragment in 8 octet units.
+	pub fn set_offset(&mut self, value: u16) -> Result<&mut Self> {
+		if value > 0x1fff {
+			Err(Error::InvalidValue)?
+		}
+
+		let flags = self.buffer.as_ref()[3] & 0b111;
+		Cursor::new(&mut self.buffer.as_mut()[2 ..])
+			.write_u16::<BigEndian>(value << 3 | u16::from(flags))?;
+
+		Ok(self)
+	}
+
+	/// Whether more fragments follow.
+	pub fn set_more(&mut self, value: bool) -> Result<&mut Self> {
+		let old = self.buffer.as_ref()[3] & !0b1;
+		self.buffer.as_mut()[3] = old | value as u8;
+
+		Ok(self)
+	}
+
+	/// Identification of the fragmented packet.
+	pub fn set_identification(&mut self, value: u32) -> Result<&mut Self> {
+		Cursor::new(&mut self.buffer.as_mut()[4 ..])
+			.write_u32::<BigEndian>(value)?;
+
+		Ok(self)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::packet::Packet;
+	use crate::ip;
+
+	#[test]
+	fn values() {
+		let raw = [0x11u8, 0x00, 0x05, 0x39, 0xde, 0xad, 0xbe, 0xef, 0x00];
+		let fragment = ip::v6::fragment::Packet::new(&raw[..]).unwrap();
+
+		assert_eq!(fragment.next_header(), ip::Protocol::Udp);
+		assert_eq!(fragment.offset(), 0xa7);
+		assert!(fragment.more());
+		assert_eq!(fragment.identification(), 0xdeadbeef);
+		assert!(!fragment.is_atomic());
+		assert_eq!(fragment.payload(), &[0x00]);
+	}
+
+	#[test]
+	fn mutable() {
+		let mut raw  = [0u8; 8];
+		let mut fragment = ip::v6::fragment::Packet::new(&mut raw[..]).unwrap();
+		assert!(fragment.is_atomic());
+
+		fragment.set_offset(0x1fff).unwrap();
+		fragment.set_more(true).unwrap();
+		fragment.set_identification(42).unwrap();
+		assert_eq!(fragment.offset(), 0x1fff);
+		assert!(fragment.more());
+		assert_eq!(fragment.identification(), 42);
+
+		fragment.set_more(false).unwrap();
+		assert_eq!(fragment.offset(), 0x1fff);
+		assert!(!fragment.more());
+		assert!(fragment.set_offset(0x2000).is_err());
+	}
+}
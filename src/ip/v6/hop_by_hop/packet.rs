@@ -0,0 +1,220 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+
+use crate::error::*;
+use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::ip::Protocol;
+use crate::ip::v6::option;
+
+/// Hop-by-Hop Options header parser, Destination Options headers share the
+/// same format.
+pub struct Packet<B> {
+	buffer: B,
+}
+
+sized!(Packet,
+	header {
+		min:  8,
+		max:  2048,
+		size: p => (p.length() as usize + 1) * 8,
+	}
+
+	payload {
+		min:  0,
+		size: p => p.buffer.as_ref().len() - (p.length() as usize + 1) * 8,
+	});
+
+impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ip::v6::hop_by_hop::Packet")
+			.field("next_header", &self.next_header())
+			.field("length", &self.length())
+			.field("options", &self.options().collect::<Vec<_>>())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Create an options header without checking the buffer.
+	pub fn unchecked(buffer: B) -> Packet<B> {
+		Packet { buffer }
+	}
+
+	/// Parse an options header, checking the buffer contents are correct.
+	pub fn new(buffer: B) -> Result<Packet<B>> {
+		use crate::size::header::{Min, Size};
+
+		let packet = Packet::unchecked(buffer);
+
+		if packet.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		if packet.buffer.as_ref().len() < packet.size() {
+			Err(Error::SmallBuffer)?
+		}
+
+		Ok(packet)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Convert the packet to its owned version.
+	///
+	/// # Notes
+	///
+	/// It would be nice if `ToOwned` could be implemented, but `Packet` already
+	/// implements `Clone` and the impl would conflict.
+	pub fn to_owned(&self) -> Packet<Vec<u8>> {
+		Packet::unchecked(self.buffer.as_ref().to_vec())
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
+	fn as_ref(&self) -> &[u8] {
+		use crate::size::Size;
+
+		&self.buffer.as_ref()[.. self.size()]
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
+	fn as_mut(&mut self) -> &mut [u8] {
+		use crate::size::Size;
+
+		let size = self.size();
+		&mut self.buffer.as_mut()[.. size]
+	}
+}
+
+impl<'a, B: AsRef<[u8]>> AsPacket<'a, Packet<&'a [u8]>> for B {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.as_ref())
+	}
+}
+
+impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Packet<&'a mut [u8]>> for B {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.as_mut())
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Packet<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		let header = (self.length() as usize + 1) * 8;
+		self.buffer.as_ref().split_at(header)
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Packet<B> {
+	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+		let header = (self.length() as usize + 1) * 8;
+		self.buffer.as_mut().split_at_mut(header)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Protocol of the next header.
+	pub fn next_header(&self) -> Protocol {
+		self.buffer.as_ref()[0].into()
+	}
+
+	/// Length of the header in 8 octet units, not including the first 8
+	/// octets.
+	pub fn length(&self) -> u8 {
+		self.buffer.as_ref()[1]
+	}
+
+	/// Options in the header.
+	pub fn options(&self) -> OptionIter<'_> {
+		OptionIter {
+			buffer: &self.buffer.as_ref()[2 .. (self.length() as usize + 1) * 8],
+		}
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
+	/// Protocol of the next header.
+	pub fn set_next_header(&mut self, value: Protocol) -> Result<&mut Self> {
+		self.buffer.as_mut()[0] = value.into();
+
+		Ok(self)
+	}
+}
+
+/// Iterator over Hop-by-Hop and Destination options, padding included.
+pub struct OptionIter<'a> {
+	buffer: &'a [u8],
+}
+
+impl<'a> Iterator for OptionIter<'a> {
+	type Item = Result<option::Option<&'a [u8]>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		use crate::size::Size;
+
+		if self.buffer.is_empty() {
+			return None;
+		}
+
+		match option::Option::new(self.buffer) {
+			Ok(option) => {
+				self.buffer = &self.buffer[option.size() ..];
+				Some(Ok(option))
+			}
+
+			Err(error) => {
+				self.buffer = &[];
+				Some(Err(error))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::packet::Packet;
+	use crate::ip;
+	use crate::ip::v6::option::Number;
+
+	#[test]
+	fn values() {
+		let raw = [0x3au8, 0x00, 0x05, 0x02, 0x00, 0x00, 0x01, 0x00, 0xde, 0xad];
+		let hop = ip::v6::hop_by_hop::Packet::new(&raw[..]).unwrap();
+
+		assert_eq!(hop.next_header(), ip::Protocol::Ipv6Icmp);
+		assert_eq!(hop.length(), 0);
+		assert_eq!(hop.header().len(), 8);
+		assert_eq!(hop.payload(), &[0xde, 0xad]);
+
+		let options = hop.options().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(options.len(), 2);
+		assert_eq!(options[0].number(), Number::RouterAlert);
+		assert_eq!(options[0].payload(), &[0x00, 0x00]);
+		assert_eq!(options[1].number(), Number::PadN);
+		assert_eq!(options[1].length(), 0);
+	}
+
+	#[test]
+	fn short() {
+		assert!(ip::v6::hop_by_hop::Packet::new(&[0x3au8, 0x00, 0x00][..]).is_err());
+		assert!(ip::v6::hop_by_hop::Packet::new(&[0x3au8, 0x01, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00][..]).is_err());
+
+		let raw = [0x3au8, 0x00, 0x01, 0x08, 0x00, 0x00, 0x00, 0x00];
+		let hop = ip::v6::hop_by_hop::Packet::new(&raw[..]).unwrap();
+		assert!(hop.options().next().unwrap().is_err());
+	}
+}
@@ -12,8 +12,88 @@
 //
 //  0. You just DO WHAT THE FUCK YOU WANT TO.
 
+// Chaining methods shared by the extension header builders, the header being
+// built is closed before moving on.
+macro_rules! extension {
+	() => (
+		/// Add a Destination Options header.
+		pub fn destination_options(self) -> Result<crate::ip::v6::destination::Builder<B>> {
+			self.close()?.destination_options()
+		}
+
+		/// Add a Routing header of the given type.
+		pub fn routing(self, kind: crate::ip::v6::routing::Kind) -> Result<crate::ip::v6::routing::Builder<B>> {
+			self.close()?.routing(kind)
+		}
+
+		/// Add a Fragment header.
+		pub fn fragment(self) -> Result<crate::ip::v6::fragment::Builder<B>> {
+			self.close()?.fragment()
+		}
+
+		/// Protocol of the next header.
+		pub fn next_header(self, value: crate::ip::Protocol) -> Result<crate::ip::v6::Builder<B>> {
+			self.close()?.next_header(value)
+		}
+
+		/// Payload for the packet.
+		pub fn payload<'a, T: IntoIterator<Item = &'a u8>>(self, value: T) -> Result<crate::ip::v6::Builder<B>> {
+			self.close()?.payload(value)
+		}
+
+		/// Build an ICMP packet.
+		pub fn icmp(self) -> Result<crate::icmp::Builder<B>> {
+			self.close()?.icmp()
+		}
+
+		/// Build an ICMPv6 packet.
+		pub fn icmpv6(self) -> Result<crate::icmpv6::Builder<B>> {
+			self.close()?.icmpv6()
+		}
+
+		/// Build a TCP packet.
+		pub fn tcp(self) -> Result<crate::tcp::Builder<B>> {
+			self.close()?.tcp()
+		}
+
+		/// Build a UDP packet.
+		pub fn udp(self) -> Result<crate::udp::Builder<B>> {
+			self.close()?.udp()
+		}
+
+		/// Build the packet.
+		pub fn build(self) -> Result<B::Inner> {
+			crate::builder::Builder::build(self.close()?)
+		}
+	)
+}
+
+/// IPv6 Hop-by-Hop and Destination options.
+pub mod option;
+pub use self::option::Option;
+
+/// Hop-by-Hop Options header.
+pub mod hop_by_hop;
+
+/// Routing header.
+pub mod routing;
+
+/// Fragment header.
+pub mod fragment;
+pub use self::fragment::fragment;
+
+/// Destination Options header.
+pub mod destination;
+
+mod extension;
+pub use self::extension::Extension;
+
 mod packet;
-pub use self::packet::Packet;
+pub use self::packet::{Packet, ExtensionIter};
 
 mod builder;
 pub use self::builder::Builder;
+
+/// IPv6 fragment reassembly.
+pub mod reassembly;
+pub use self::reassembly::Reassembler;
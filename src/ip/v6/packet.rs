@@ -13,8 +13,15 @@
 //  0. You just DO WHAT THE FUCK YOU WANT TO.
 
 use std::fmt;
+use std::io::Cursor;
+use std::net::Ipv6Addr;
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
+
 use crate::error::*;
 use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::dissect::{dissect, LinkType};
+use crate::ip::Protocol;
+use crate::ip::v6::{Extension, hop_by_hop, routing, fragment, destination};
 
 /// IPv6 packet parser.
 #[derive(Clone)]
@@ -24,24 +31,44 @@ pub struct Packet<B> {
 
 sized!(Packet,
 	header {
-		min:  0,
-		max:  0,
-		size: 0,
+		min:  40,
+		max:  40,
+		size: 40,
 	}
 
 	payload {
 		min:  0,
-		max:  0,
-		size: 0,
+		max:  u32::MAX as usize,
+		size: p => if p.is_jumbo() {
+			p.buffer.as_ref().len().saturating_sub(40)
+		}
+		else {
+			p.length() as usize
+		},
 	});
 
 impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("ip::v6::Packet")
+			.field("version", &self.version())
+			.field("traffic_class", &self.traffic_class())
+			.field("flow_label", &self.flow_label())
+			.field("length", &self.length())
+			.field("next_header", &self.next_header())
+			.field("hop_limit", &self.hop_limit())
+			.field("source", &self.source())
+			.field("destination", &self.destination())
+			.field("payload", &self.payload())
 			.finish()
 	}
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&dissect(LinkType::Raw, self.as_ref()), f)
+	}
+}
+
 impl<B: AsRef<[u8]>> Packet<B> {
 	/// Create an IPv6 packet without checking the buffer.
 	pub fn unchecked(buffer: B) -> Packet<B> {
@@ -67,7 +94,15 @@ impl<B: AsRef<[u8]>> Packet<B> {
 
 	/// Parse an IPv6 packet, checking the buffer contents are correct.
 	pub fn new(buffer: B) -> Result<Packet<B>> {
-		Packet::no_payload(buffer)
+		use crate::size::header::Min;
+
+		let packet = Packet::no_payload(buffer)?;
+
+		if packet.buffer.as_ref().len() < Self::min() + packet.length() as usize {
+			Err(Error::SmallBuffer)?
+		}
+
+		Ok(packet)
 	}
 }
 
@@ -85,13 +120,33 @@ impl<B: AsRef<[u8]>> Packet<B> {
 
 impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
 	fn as_ref(&self) -> &[u8] {
-		&[]
+		use crate::size::Size;
+
+		let size   = self.size();
+		let buffer = self.buffer.as_ref();
+
+		if buffer.len() < size {
+			buffer
+		}
+		else {
+			&buffer[.. size]
+		}
 	}
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
 	fn as_mut(&mut self) -> &mut [u8] {
-		&mut []
+		use crate::size::Size;
+
+		let size   = self.size();
+		let buffer = self.buffer.as_mut();
+
+		if buffer.len() < size {
+			buffer
+		}
+		else {
+			&mut buffer[.. size]
+		}
 	}
 }
 
@@ -109,12 +164,401 @@ impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Packet<&'a mut [u8]>> for
 
 impl<B: AsRef<[u8]>> P for Packet<B> {
 	fn split(&self) -> (&[u8], &[u8]) {
-		(&[], &[])
+		self.as_ref().split_at(40)
 	}
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Packet<B> {
 	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
-		(&mut [], &mut [])
+		self.as_mut().split_at_mut(40)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// IP protocol version, will always be 6.
+	pub fn version(&self) -> u8 {
+		self.buffer.as_ref()[0] >> 4
+	}
+
+	/// Traffic class, the DSCP and ECN values together.
+	pub fn traffic_class(&self) -> u8 {
+		(self.buffer.as_ref()[0] << 4) | (self.buffer.as_ref()[1] >> 4)
+	}
+
+	/// DSCP value.
+	pub fn dscp(&self) -> u8 {
+		self.traffic_class() >> 2
+	}
+
+	/// ECN value.
+	pub fn ecn(&self) -> u8 {
+		self.traffic_class() & 0b11
+	}
+
+	/// Flow label of the packet.
+	pub fn flow_label(&self) -> u32 {
+		(&self.buffer.as_ref()[0 ..]).read_u32::<BigEndian>().unwrap() & 0xf_ffff
+	}
+
+	/// Length of the payload in octets, extension headers included.
+	pub fn length(&self) -> u16 {
+		(&self.buffer.as_ref()[4 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Protocol of the header following the IPv6 header.
+	pub fn next_header(&self) -> Protocol {
+		self.buffer.as_ref()[6].into()
+	}
+
+	/// Hop limit for the packet.
+	pub fn hop_limit(&self) -> u8 {
+		self.buffer.as_ref()[7]
+	}
+
+	/// Source IP address.
+	pub fn source(&self) -> Ipv6Addr {
+		let mut octets = [0u8; 16];
+		octets.copy_from_slice(&self.buffer.as_ref()[8 .. 24]);
+
+		octets.into()
+	}
+
+	/// Destination IP address.
+	pub fn destination(&self) -> Ipv6Addr {
+		let mut octets = [0u8; 16];
+		octets.copy_from_slice(&self.buffer.as_ref()[24 .. 40]);
+
+		octets.into()
+	}
+
+	/// Whether the packet is a jumbogram, in which case the payload length is
+	/// carried by the Jumbo Payload option and the payload is taken to be the
+	/// rest of the buffer.
+	pub fn is_jumbo(&self) -> bool {
+		self.length() == 0 && self.next_header() == Protocol::Hopopt
+	}
+
+	/// Extension headers following the IPv6 header.
+	pub fn extensions(&self) -> ExtensionIter<'_> {
+		ExtensionIter {
+			buffer:   self.payload(),
+			offset:   0,
+			protocol: self.next_header(),
+			done:     false,
+		}
+	}
+
+	/// Protocol of the upper-layer header and its offset from the start of the
+	/// packet, after walking the extension header chain.
+	///
+	/// # Note
+	///
+	/// For non-first fragments the upper-layer header is not present, the
+	/// offset points to the fragment data instead.
+	pub fn upper_layer(&self) -> Result<(Protocol, usize)> {
+		let mut extensions = self.extensions();
+
+		for extension in &mut extensions {
+			extension?;
+		}
+
+		Ok((extensions.protocol(), extensions.offset()))
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
+	/// Traffic class.
+	pub fn set_traffic_class(&mut self, value: u8) -> Result<&mut Self> {
+		let buffer = self.buffer.as_mut();
+
+		buffer[0] = (buffer[0] & 0b1111_0000) | (value >> 4);
+		buffer[1] = (buffer[1] & 0b0000_1111) | (value << 4);
+
+		Ok(self)
+	}
+
+	/// Differentiated Services Code Point.
+	pub fn set_dscp(&mut self, value: u8) -> Result<&mut Self> {
+		if value > 0b11_1111 {
+			Err(Error::InvalidValue)?
+		}
+
+		let ecn = self.ecn();
+		self.set_traffic_class(value << 2 | ecn)
+	}
+
+	/// Explicit Congestion Notification.
+	pub fn set_ecn(&mut self, value: u8) -> Result<&mut Self> {
+		if value > 0b11 {
+			Err(Error::InvalidValue)?
+		}
+
+		let dscp = self.dscp();
+		self.set_traffic_class(dscp << 2 | value)
+	}
+
+	/// Flow label.
+	pub fn set_flow_label(&mut self, value: u32) -> Result<&mut Self> {
+		if value > 0xf_ffff {
+			Err(Error::InvalidValue)?
+		}
+
+		let old = (&self.buffer.as_ref()[0 ..]).read_u32::<BigEndian>()?;
+		Cursor::new(&mut self.buffer.as_mut()[0 ..])
+			.write_u32::<BigEndian>((old & 0xfff0_0000) | value)?;
+
+		Ok(self)
+	}
+
+	/// Payload length.
+	pub fn set_length(&mut self, value: u16) -> Result<&mut Self> {
+		Cursor::new(&mut self.buffer.as_mut()[4 ..])
+			.write_u16::<BigEndian>(value)?;
+
+		Ok(self)
+	}
+
+	/// Protocol of the next header.
+	pub fn set_next_header(&mut self, value: Protocol) -> Result<&mut Self> {
+		self.buffer.as_mut()[6] = value.into();
+
+		Ok(self)
+	}
+
+	/// Hop limit.
+	pub fn set_hop_limit(&mut self, value: u8) -> Result<&mut Self> {
+		self.buffer.as_mut()[7] = value;
+
+		Ok(self)
+	}
+
+	/// Source address.
+	pub fn set_source(&mut self, value: Ipv6Addr) -> Result<&mut Self> {
+		self.buffer.as_mut()[8 .. 24].copy_from_slice(&value.octets());
+
+		Ok(self)
+	}
+
+	/// Destination address.
+	pub fn set_destination(&mut self, value: Ipv6Addr) -> Result<&mut Self> {
+		self.buffer.as_mut()[24 .. 40].copy_from_slice(&value.octets());
+
+		Ok(self)
+	}
+}
+
+/// Iterator over IPv6 extension headers.
+pub struct ExtensionIter<'a> {
+	buffer:   &'a [u8],
+	offset:   usize,
+	protocol: Protocol,
+	done:     bool,
+}
+
+impl<'a> ExtensionIter<'a> {
+	/// Protocol of the header following the extension headers walked so far.
+	pub fn protocol(&self) -> Protocol {
+		self.protocol
+	}
+
+	/// Offset from the start of the IPv6 packet of the header following the
+	/// extension headers walked so far.
+	pub fn offset(&self) -> usize {
+		40 + self.offset
+	}
+}
+
+impl<'a> Iterator for ExtensionIter<'a> {
+	type Item = Result<Extension<&'a [u8]>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+
+		let buffer    = &self.buffer[self.offset ..];
+		let extension = match self.protocol {
+			// The Hop-by-Hop Options header is only allowed right after the IPv6
+			// header.
+			Protocol::Hopopt if self.offset != 0 =>
+				Err(Error::InvalidPacket),
+
+			Protocol::Hopopt =>
+				hop_by_hop::Packet::new(buffer).map(Extension::HopByHop),
+
+			Protocol::Ipv6Route =>
+				routing::Packet::new(buffer).map(Extension::Routing),
+
+			Protocol::Ipv6Frag =>
+				fragment::Packet::new(buffer).map(Extension::Fragment),
+
+			Protocol::Ipv6Opts =>
+				destination::Packet::new(buffer).map(Extension::Destination),
+
+			_ =>
+				return None
+		};
+
+		match extension {
+			Ok(extension) => {
+				self.protocol  = extension.next_header();
+				self.offset   += extension.header().len();
+
+				// The headers following a non-first fragment are not there.
+				if let Extension::Fragment(ref fragment) = extension {
+					if fragment.offset() != 0 {
+						self.done = true;
+					}
+				}
+
+				Some(Ok(extension))
+			}
+
+			Err(error) => {
+				self.done = true;
+				Some(Err(error))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::Ipv6Addr;
+	use crate::packet::Packet;
+	use crate::ip;
+
+	#[test]
+	fn short_packet() {
+		assert!(ip::v6::Packet::no_payload(&[0x60; 10][..]).is_err());
+		assert!(ip::v6::Packet::no_payload(&[0x60; 39][..]).is_err());
+		assert!(ip::v6::Packet::no_payload(&[0x60; 40][..]).is_ok());
+		assert!(ip::v6::Packet::no_payload(&[0x40; 40][..]).is_err());
+
+		let mut raw = [0u8; 40];
+		raw[0] = 0x60;
+		raw[5] = 8;
+		assert!(ip::v6::Packet::new(&raw[..]).is_err());
+		assert!(ip::v6::Packet::new(&[&raw[..], &[0u8; 8][..]].concat()[..]).is_ok());
+	}
+
+	#[test]
+	fn values() {
+		let raw = [0x6eu8, 0x12, 0x34, 0x56, 0x00, 0x08, 0x11, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x05, 0x39, 0x00, 0x35, 0x00, 0x08, 0x00, 0x00];
+		let ip  = ip::v6::Packet::new(&raw[..]).unwrap();
+
+		assert_eq!(ip.version(), 6);
+		assert_eq!(ip.traffic_class(), 0xe1);
+		assert_eq!(ip.dscp(), 0x38);
+		assert_eq!(ip.ecn(), 0x01);
+		assert_eq!(ip.flow_label(), 0x23456);
+		assert_eq!(ip.length(), 8);
+		assert_eq!(ip.next_header(), ip::Protocol::Udp);
+		assert_eq!(ip.hop_limit(), 64);
+		assert_eq!(ip.source(), "2001:db8::1".parse::<Ipv6Addr>().unwrap());
+		assert_eq!(ip.destination(), "2001:db8::2".parse::<Ipv6Addr>().unwrap());
+		assert_eq!(ip.header().len(), 40);
+		assert_eq!(ip.payload(), &[0x05, 0x39, 0x00, 0x35, 0x00, 0x08, 0x00, 0x00]);
+	}
+
+	#[test]
+	fn owned() {
+		let raw: Vec<u8> = vec![0x60, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0xff, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xde, 0xad];
+		let ip           = ip::v6::Packet::new(raw).unwrap();
+
+		assert_eq!(ip.next_header(), ip::Protocol::Ipv6NoNxt);
+		assert_eq!(ip.hop_limit(), 255);
+		assert_eq!(ip.payload().len(), 0);
+		assert_eq!(ip.as_ref().len(), 40);
+	}
+
+	#[test]
+	fn mutable() {
+		let mut raw = [0x60u8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0xff, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02];
+		let mut ip  = ip::v6::Packet::new(&mut raw[..]).unwrap();
+
+		ip.set_traffic_class(0xb8).unwrap();
+		ip.set_flow_label(0xabcde).unwrap();
+		assert_eq!(ip.version(), 6);
+		assert_eq!(ip.traffic_class(), 0xb8);
+		assert_eq!(ip.flow_label(), 0xabcde);
+
+		ip.set_ecn(0b11).unwrap();
+		assert_eq!(ip.dscp(), 0x2e);
+		assert_eq!(ip.ecn(), 0b11);
+		assert!(ip.set_flow_label(0x10_0000).is_err());
+
+		ip.set_next_header(ip::Protocol::Tcp).unwrap();
+		ip.set_hop_limit(1).unwrap();
+		ip.set_source("fe80::1".parse().unwrap()).unwrap();
+		ip.set_destination("ff02::1".parse().unwrap()).unwrap();
+
+		assert_eq!(ip.next_header(), ip::Protocol::Tcp);
+		assert_eq!(ip.hop_limit(), 1);
+		assert_eq!(ip.source(), "fe80::1".parse::<Ipv6Addr>().unwrap());
+		assert_eq!(ip.destination(), "ff02::1".parse::<Ipv6Addr>().unwrap());
+	}
+
+	#[test]
+	fn extensions() {
+		let raw = [
+			// IPv6
+			0x60u8, 0x00, 0x00, 0x00, 0x00, 0x30, 0x00, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+			// Hop-by-Hop Options
+			0x2b, 0x00, 0x05, 0x02, 0x00, 0x00, 0x01, 0x00,
+			// Routing
+			0x2c, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00,
+			// Fragment
+			0x3c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2a,
+			// Destination Options
+			0x06, 0x00, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00,
+			// TCP
+			0x05, 0x39, 0x00, 0x50, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x50, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+		];
+
+		let ip         = ip::v6::Packet::new(&raw[..]).unwrap();
+		let extensions = ip.extensions().collect::<Result<Vec<_>, _>>().unwrap();
+
+		assert_eq!(extensions.len(), 4);
+		assert_eq!(extensions[0].protocol(), ip::Protocol::Hopopt);
+		assert_eq!(extensions[1].protocol(), ip::Protocol::Ipv6Route);
+		assert_eq!(extensions[2].protocol(), ip::Protocol::Ipv6Frag);
+		assert_eq!(extensions[3].protocol(), ip::Protocol::Ipv6Opts);
+		assert_eq!(extensions[3].next_header(), ip::Protocol::Tcp);
+
+		if let ip::v6::Extension::Fragment(ref fragment) = extensions[2] {
+			assert_eq!(fragment.identification(), 42);
+		}
+		else {
+			panic!("not a fragment header");
+		}
+
+		assert_eq!(ip.upper_layer().unwrap(), (ip::Protocol::Tcp, 72));
+	}
+
+	#[test]
+	fn extensions_misplaced() {
+		let raw = [
+			0x60u8, 0x00, 0x00, 0x00, 0x00, 0x10, 0x3c, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+			0x00, 0x00, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00,
+			0x3b, 0x00, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00,
+		];
+
+		let ip = ip::v6::Packet::new(&raw[..]).unwrap();
+		assert!(ip.upper_layer().is_err());
+	}
+
+	#[test]
+	fn extensions_truncated() {
+		let raw = [
+			0x60u8, 0x00, 0x00, 0x00, 0x00, 0x04, 0x2b, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+			0x06, 0x00, 0x04, 0x00,
+		];
+
+		let ip = ip::v6::Packet::new(&raw[..]).unwrap();
+		let mut extensions = ip.extensions();
+
+		assert!(extensions.next().unwrap().is_err());
+		assert!(extensions.next().is_none());
 	}
 }
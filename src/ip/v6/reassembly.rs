@@ -0,0 +1,354 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::Ipv6Addr;
+use std::time::Duration;
+use byteorder::{ByteOrder, BigEndian};
+
+use crate::error::*;
+use crate::ip::reassembly::{Policy, Table};
+use crate::ip::v6::{Packet, Extension};
+
+/// Fragments belonging to the same packet share the key.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct Key {
+	/// Source address.
+	pub source: Ipv6Addr,
+
+	/// Destination address.
+	pub destination: Ipv6Addr,
+
+	/// Identification.
+	pub identification: u32,
+}
+
+/// IPv6 fragment reassembler.
+///
+/// Overlapping fragments are not allowed by RFC 5722, the whole packet is
+/// discarded when one is received, including the fragments still to come.
+/// Exact duplicates are ignored instead. A bounded number of discarded
+/// packets is remembered, the oldest being forgotten first.
+///
+/// Timestamps are only used to expire packets, so any monotonic clock works,
+/// like the capture timestamps of the packets.
+#[derive(Debug)]
+pub struct Reassembler {
+	table:     Table<Key>,
+	discarded: HashMap<Key, Duration>,
+	order:     VecDeque<Key>,
+	remember:  usize,
+	timeout:   Duration,
+}
+
+impl Default for Reassembler {
+	fn default() -> Self {
+		Reassembler::new()
+	}
+}
+
+impl Reassembler {
+	/// Create a reassembler with a 60 seconds timeout, a 4 MiB memory limit
+	/// and remembering up to 4096 discarded packets.
+	pub fn new() -> Reassembler {
+		Reassembler {
+			table:     Table::new(Duration::from_secs(60), 4 * 1024 * 1024),
+			discarded: HashMap::new(),
+			order:     VecDeque::new(),
+			remember:  4096,
+			timeout:   Duration::from_secs(60),
+		}
+	}
+
+	/// Time after which an incomplete packet is dropped.
+	pub fn timeout(mut self, value: Duration) -> Self {
+		self.table.set_timeout(value);
+		self.timeout = value;
+		self
+	}
+
+	/// Maximum number of octets held for incomplete packets, the oldest
+	/// packets are dropped to make room.
+	pub fn limit(mut self, value: usize) -> Self {
+		self.table.set_limit(value);
+		self
+	}
+
+	/// Maximum number of discarded packets whose remaining fragments are
+	/// dropped.
+	pub fn remember(mut self, value: usize) -> Self {
+		self.remember = value;
+		self.forget();
+		self
+	}
+
+	/// Number of incomplete packets.
+	pub fn len(&self) -> usize {
+		self.table.len()
+	}
+
+	/// Whether there are no incomplete packets.
+	pub fn is_empty(&self) -> bool {
+		self.table.len() == 0
+	}
+
+	/// Number of octets held for incomplete packets.
+	pub fn memory(&self) -> usize {
+		self.table.memory()
+	}
+
+	/// Add a packet, returning the reassembled packet once all its fragments
+	/// have been received.
+	///
+	/// Packets without a Fragment header are returned as is, and atomic
+	/// fragments are returned right away without their Fragment header. More
+	/// than 64 fragments drop the whole packet.
+	pub fn push<B: AsRef<[u8]>>(&mut self, timestamp: Duration, packet: &Packet<B>) -> Result<Option<Packet<Vec<u8>>>> {
+		self.expire(timestamp);
+
+		// Find the Fragment header and the header whose Next Header field
+		// points to it.
+		let mut extensions = packet.extensions();
+		let mut next       = 6;
+
+		let (start, fragment) = loop {
+			let start = extensions.offset();
+
+			match extensions.next() {
+				Some(Ok(Extension::Fragment(fragment))) =>
+					break (start, fragment),
+
+				Some(Ok(_)) =>
+					next = start,
+
+				Some(Err(error)) =>
+					Err(error)?,
+
+				None =>
+					return Ok(Some(packet.to_owned())),
+			}
+		};
+
+		let buffer = packet.as_ref();
+		let data   = &buffer[start + 8 ..];
+		let offset = fragment.offset() as usize * 8;
+		let more   = fragment.more();
+
+		let mut header = buffer[.. start].to_vec();
+		header[next] = fragment.next_header().into();
+
+		if fragment.is_atomic() {
+			return Ok(Some(assemble(&header, data)));
+		}
+
+		if (more && !data.len().is_multiple_of(8)) || start - 40 + offset + data.len() > 0xffff {
+			Err(Error::InvalidPacket)?
+		}
+
+		let key = Key {
+			source:         packet.source(),
+			destination:    packet.destination(),
+			identification: fragment.identification(),
+		};
+
+		if self.discarded.contains_key(&key) {
+			return Ok(None);
+		}
+
+		if let Some(fragments) = self.table.get(&key) {
+			if fragments.contains(offset, data) {
+				return Ok(None);
+			}
+
+			if fragments.overlaps(offset, data.len()) {
+				self.table.remove(&key);
+				self.discarded.insert(key, timestamp);
+				self.order.push_back(key);
+				self.forget();
+
+				Err(Error::InvalidPacket)?
+			}
+		}
+
+		let first = if offset == 0 { Some(&header[..]) } else { None };
+		match self.table.insert(timestamp, key, first, offset, data, !more)? {
+			Some(fragments) =>
+				Ok(Some(assemble(fragments.header().unwrap(), &fragments.assemble(Policy::First)))),
+
+			None =>
+				Ok(None),
+		}
+	}
+
+	/// Drop the incomplete packets older than the timeout, returning how many
+	/// were dropped.
+	pub fn expire(&mut self, now: Duration) -> usize {
+		while let Some(&key) = self.order.front() {
+			if now.saturating_sub(self.discarded[&key]) < self.timeout {
+				break;
+			}
+
+			self.order.pop_front();
+			self.discarded.remove(&key);
+		}
+
+		self.table.expire(now)
+	}
+
+	/// Forget the oldest discarded packets over the limit.
+	fn forget(&mut self) {
+		while self.order.len() > self.remember {
+			if let Some(key) = self.order.pop_front() {
+				self.discarded.remove(&key);
+			}
+		}
+	}
+}
+
+/// Join the unfragmentable part and the fragment data, fixing the payload
+/// length.
+fn assemble(header: &[u8], data: &[u8]) -> Packet<Vec<u8>> {
+	let mut buffer = Vec::with_capacity(header.len() + data.len());
+	buffer.extend_from_slice(header);
+	buffer.extend_from_slice(data);
+
+	let length = (buffer.len() - 40) as u16;
+	BigEndian::write_u16(&mut buffer[4 ..], length);
+
+	Packet::unchecked(buffer)
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use crate::builder::Builder;
+	use crate::ip;
+
+	fn build() -> ip::v6::Packet<Vec<u8>> {
+		ip::v6::Packet::new(ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.hop_by_hop().unwrap()
+				.router_alert(0).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.payload((0 .. 200u8).collect::<Vec<_>>().iter()).unwrap()
+				.build().unwrap()).unwrap()
+	}
+
+	#[test]
+	fn reassemble() {
+		let packet    = build();
+		let fragments = ip::v6::fragment(&packet, 100, 42).unwrap();
+		assert_eq!(fragments.len(), 6);
+
+		let mut reassembler = ip::v6::Reassembler::default();
+		let second = Duration::from_secs(1);
+
+		for fragment in fragments.iter().skip(1).rev() {
+			assert!(reassembler.push(second, fragment).unwrap().is_none());
+		}
+
+		assert!(reassembler.push(second, &fragments[2]).unwrap().is_none());
+		assert_eq!(reassembler.len(), 1);
+
+		let datagram = reassembler.push(second, &fragments[0]).unwrap().unwrap();
+		assert_eq!(datagram.as_ref(), packet.as_ref());
+		assert!(reassembler.is_empty());
+		assert_eq!(reassembler.memory(), 0);
+
+		let whole = reassembler.push(second, &packet).unwrap().unwrap();
+		assert_eq!(whole.as_ref(), packet.as_ref());
+	}
+
+	#[test]
+	fn atomic() {
+		let packet = build();
+		let raw    = ip::v6::Builder::default()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.hop_by_hop().unwrap()
+				.router_alert(0).unwrap()
+			.fragment().unwrap()
+				.identification(42).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.payload((0 .. 200u8).collect::<Vec<_>>().iter()).unwrap()
+				.build().unwrap();
+
+		let mut reassembler = ip::v6::Reassembler::default();
+		let atomic = ip::v6::Packet::new(raw).unwrap();
+		let datagram = reassembler.push(Duration::from_secs(0), &atomic).unwrap().unwrap();
+
+		assert_eq!(datagram.as_ref(), packet.as_ref());
+		assert!(reassembler.is_empty());
+	}
+
+	#[test]
+	fn overlap() {
+		let fragments = ip::v6::fragment(&build(), 100, 42).unwrap();
+		let mut reassembler = ip::v6::Reassembler::default()
+			.timeout(Duration::from_secs(10));
+
+		let mut overlapping = fragments[1].clone();
+		ip::v6::fragment::Packet::unchecked(&mut overlapping.as_mut()[48 .. 56])
+			.set_offset(fragments.len() as u16).unwrap();
+
+		assert!(reassembler.push(Duration::from_secs(0), &fragments[1]).unwrap().is_none());
+		assert!(reassembler.push(Duration::from_secs(0), &fragments[2]).unwrap().is_none());
+		assert!(reassembler.push(Duration::from_secs(0), &overlapping).is_err());
+		assert!(reassembler.is_empty());
+
+		for fragment in &fragments {
+			assert!(reassembler.push(Duration::from_secs(1), fragment).unwrap().is_none());
+		}
+
+		assert!(reassembler.is_empty());
+
+		for fragment in &fragments {
+			reassembler.push(Duration::from_secs(20), fragment).unwrap();
+		}
+
+		assert!(reassembler.is_empty());
+	}
+
+	#[test]
+	fn remember() {
+		let mut reassembler = ip::v6::Reassembler::default()
+			.remember(1);
+
+		for id in 42 .. 44 {
+			let fragments = ip::v6::fragment(&build(), 100, id).unwrap();
+
+			let mut overlapping = fragments[1].clone();
+			ip::v6::fragment::Packet::unchecked(&mut overlapping.as_mut()[48 .. 56])
+				.set_offset(fragments.len() as u16).unwrap();
+
+			reassembler.push(Duration::from_secs(0), &fragments[1]).unwrap();
+			reassembler.push(Duration::from_secs(0), &fragments[2]).unwrap();
+			assert!(reassembler.push(Duration::from_secs(0), &overlapping).is_err());
+		}
+
+		let first  = ip::v6::fragment(&build(), 100, 42).unwrap();
+		let second = ip::v6::fragment(&build(), 100, 43).unwrap();
+
+		reassembler.push(Duration::from_secs(0), &second[1]).unwrap();
+		assert!(reassembler.is_empty());
+
+		reassembler.push(Duration::from_secs(0), &first[1]).unwrap();
+		assert_eq!(reassembler.len(), 1);
+	}
+}
@@ -0,0 +1,94 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::net::Ipv6Addr;
+
+use crate::error::*;
+use crate::buffer::{self, Buffer};
+use crate::ip;
+use crate::ip::v6::routing::{Kind, Packet};
+
+/// Routing header builder.
+#[derive(Debug)]
+pub struct Builder<B: Buffer = buffer::Dynamic> {
+	ip:     ip::v6::Builder<B>,
+	offset: usize,
+
+	addresses: usize,
+}
+
+impl<B: Buffer> Builder<B> {
+	pub(in crate::ip::v6) fn new(ip: ip::v6::Builder<B>, offset: usize) -> Self {
+		Builder {
+			ip,
+			offset,
+
+			addresses: 0,
+		}
+	}
+
+	/// Number of route segments remaining.
+	pub fn segments_left(mut self, value: u8) -> Result<Self> {
+		Packet::unchecked(self.ip.header_mut(self.offset)).set_segments_left(value)?;
+		Ok(self)
+	}
+
+	/// Segment Routing Header flags.
+	pub fn flags(mut self, value: u8) -> Result<Self> {
+		Packet::unchecked(self.ip.header_mut(self.offset)).set_flags(value)?;
+		Ok(self)
+	}
+
+	/// Segment Routing Header tag.
+	pub fn tag(mut self, value: u16) -> Result<Self> {
+		Packet::unchecked(self.ip.header_mut(self.offset)).set_tag(value)?;
+		Ok(self)
+	}
+
+	/// Add an address to the header.
+	///
+	/// For the Segment Routing Header this is the segment list, which is in
+	/// reverse order, so the first address is the final destination.
+	pub fn address(mut self, value: Ipv6Addr) -> Result<Self> {
+		if self.addresses == 127 {
+			Err(Error::InvalidValue)?
+		}
+
+		self.addresses += 1;
+		self.ip.append(&value.octets())?;
+
+		Ok(self)
+	}
+
+	fn close(mut self) -> Result<ip::v6::Builder<B>> {
+		let addresses = self.addresses;
+		let header    = self.ip.header_mut(self.offset);
+
+		if Kind::from(header[2]) == Kind::SegmentRouting {
+			if addresses == 0 {
+				Err(Error::InvalidValue)?
+			}
+
+			header[4] = (addresses - 1) as u8;
+		}
+
+		// Addresses are 16 octets each, so the header is always a multiple of
+		// 8 octets.
+		header[1] = (addresses * 2) as u8;
+
+		Ok(self.ip)
+	}
+
+	extension!();
+}
@@ -0,0 +1,65 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+/// Routing header types.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Kind {
+	/// Deprecated source route.
+	SourceRoute,
+
+	/// Deprecated Nimrod route.
+	Nimrod,
+
+	/// Mobile IPv6 home address route.
+	MobileIpv6,
+
+	/// RPL source route.
+	Rpl,
+
+	/// Segment Routing Header.
+	SegmentRouting,
+
+	/// Unknown routing type.
+	Unknown(u8),
+}
+
+impl From<u8> for Kind {
+	fn from(value: u8) -> Kind {
+		use self::Kind::*;
+
+		match value {
+			0 => SourceRoute,
+			1 => Nimrod,
+			2 => MobileIpv6,
+			3 => Rpl,
+			4 => SegmentRouting,
+			v => Unknown(v),
+		}
+	}
+}
+
+impl From<Kind> for u8 {
+	fn from(value: Kind) -> u8 {
+		use self::Kind::*;
+
+		match value {
+			SourceRoute    => 0,
+			Nimrod         => 1,
+			MobileIpv6     => 2,
+			Rpl            => 3,
+			SegmentRouting => 4,
+			Unknown(v)     => v,
+		}
+	}
+}
@@ -0,0 +1,304 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::io::Cursor;
+use std::net::Ipv6Addr;
+use byteorder::{ReadBytesExt, WriteBytesExt, BigEndian};
+
+use crate::error::*;
+use crate::packet::{Packet as P, PacketMut as PM, AsPacket, AsPacketMut};
+use crate::ip::Protocol;
+use crate::ip::v6::routing::Kind;
+
+/// Routing header parser.
+pub struct Packet<B> {
+	buffer: B,
+}
+
+sized!(Packet,
+	header {
+		min:  8,
+		max:  2048,
+		size: p => (p.length() as usize + 1) * 8,
+	}
+
+	payload {
+		min:  0,
+		size: p => p.buffer.as_ref().len() - (p.length() as usize + 1) * 8,
+	});
+
+impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ip::v6::routing::Packet")
+			.field("next_header", &self.next_header())
+			.field("length", &self.length())
+			.field("kind", &self.kind())
+			.field("segments_left", &self.segments_left())
+			.field("addresses", &self.addresses().collect::<Vec<_>>())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Create a Routing header without checking the buffer.
+	pub fn unchecked(buffer: B) -> Packet<B> {
+		Packet { buffer }
+	}
+
+	/// Parse a Routing header, checking the buffer contents are correct.
+	pub fn new(buffer: B) -> Result<Packet<B>> {
+		use crate::size::header::{Min, Size};
+
+		let packet = Packet::unchecked(buffer);
+
+		if packet.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		if packet.buffer.as_ref().len() < packet.size() {
+			Err(Error::SmallBuffer)?
+		}
+
+		if packet.kind() == Kind::SegmentRouting &&
+		   (packet.last_entry() as usize + 1) * 16 > packet.size() - 8
+		{
+			Err(Error::InvalidPacket)?
+		}
+
+		Ok(packet)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Convert the packet to its owned version.
+	///
+	/// # Notes
+	///
+	/// It would be nice if `ToOwned` could be implemented, but `Packet` already
+	/// implements `Clone` and the impl would conflict.
+	pub fn to_owned(&self) -> Packet<Vec<u8>> {
+		Packet::unchecked(self.buffer.as_ref().to_vec())
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
+	fn as_ref(&self) -> &[u8] {
+		use crate::size::Size;
+
+		&self.buffer.as_ref()[.. self.size()]
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> AsMut<[u8]> for Packet<B> {
+	fn as_mut(&mut self) -> &mut [u8] {
+		use crate::size::Size;
+
+		let size = self.size();
+		&mut self.buffer.as_mut()[.. size]
+	}
+}
+
+impl<'a, B: AsRef<[u8]>> AsPacket<'a, Packet<&'a [u8]>> for B {
+	fn as_packet(&self) -> Result<Packet<&[u8]>> {
+		Packet::new(self.as_ref())
+	}
+}
+
+impl<'a, B: AsRef<[u8]> + AsMut<[u8]>> AsPacketMut<'a, Packet<&'a mut [u8]>> for B {
+	fn as_packet_mut(&mut self) -> Result<Packet<&mut [u8]>> {
+		Packet::new(self.as_mut())
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Packet<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		let header = (self.length() as usize + 1) * 8;
+		self.buffer.as_ref().split_at(header)
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> PM for Packet<B> {
+	fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+		let header = (self.length() as usize + 1) * 8;
+		self.buffer.as_mut().split_at_mut(header)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Protocol of the next header.
+	pub fn next_header(&self) -> Protocol {
+		self.buffer.as_ref()[0].into()
+	}
+
+	/// Length of the header in 8 octet units, not including the first 8
+	/// octets.
+	pub fn length(&self) -> u8 {
+		self.buffer.as_ref()[1]
+	}
+
+	/// Routing type.
+	pub fn kind(&self) -> Kind {
+		self.buffer.as_ref()[2].into()
+	}
+
+	/// Number of route segments remaining.
+	pub fn segments_left(&self) -> u8 {
+		self.buffer.as_ref()[3]
+	}
+
+	/// Index of the last element of the segment list, only meaningful for the
+	/// Segment Routing Header.
+	pub fn last_entry(&self) -> u8 {
+		self.buffer.as_ref()[4]
+	}
+
+	/// Segment Routing Header flags.
+	pub fn flags(&self) -> u8 {
+		self.buffer.as_ref()[5]
+	}
+
+	/// Segment Routing Header tag.
+	pub fn tag(&self) -> u16 {
+		(&self.buffer.as_ref()[6 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Type-specific data following the fixed part of the header.
+	pub fn data(&self) -> &[u8] {
+		&self.buffer.as_ref()[4 .. (self.length() as usize + 1) * 8]
+	}
+
+	/// Addresses carried by the header.
+	///
+	/// For the Segment Routing Header this is the segment list, which is in
+	/// reverse order, so the first address is the final destination. Routing
+	/// types with compressed or unknown data carry no addresses.
+	pub fn addresses(&self) -> AddressIter<'_> {
+		let end = match self.kind() {
+			Kind::SourceRoute |
+			Kind::MobileIpv6 =>
+				(self.length() as usize + 1) * 8,
+
+			Kind::SegmentRouting =>
+				8 + (self.last_entry() as usize + 1) * 16,
+
+			_ =>
+				8
+		};
+
+		AddressIter {
+			buffer: &self.buffer.as_ref()[8 .. end],
+		}
+	}
+
+	/// The final destination of the packet, if the header carries it.
+	pub fn final_destination(&self) -> Option<Ipv6Addr> {
+		match self.kind() {
+			Kind::SegmentRouting =>
+				self.addresses().next(),
+
+			Kind::SourceRoute |
+			Kind::MobileIpv6 =>
+				self.addresses().last(),
+
+			_ =>
+				None
+		}
+	}
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
+	/// Protocol of the next header.
+	pub fn set_next_header(&mut self, value: Protocol) -> Result<&mut Self> {
+		self.buffer.as_mut()[0] = value.into();
+
+		Ok(self)
+	}
+
+	/// Number of route segments remaining.
+	pub fn set_segments_left(&mut self, value: u8) -> Result<&mut Self> {
+		self.buffer.as_mut()[3] = value;
+
+		Ok(self)
+	}
+
+	/// Segment Routing Header flags.
+	pub fn set_flags(&mut self, value: u8) -> Result<&mut Self> {
+		self.buffer.as_mut()[5] = value;
+
+		Ok(self)
+	}
+
+	/// Segment Routing Header tag.
+	pub fn set_tag(&mut self, value: u16) -> Result<&mut Self> {
+		Cursor::new(&mut self.buffer.as_mut()[6 ..])
+			.write_u16::<BigEndian>(value)?;
+
+		Ok(self)
+	}
+}
+
+/// Iterator over the addresses in a Routing header.
+pub struct AddressIter<'a> {
+	buffer: &'a [u8],
+}
+
+impl<'a> Iterator for AddressIter<'a> {
+	type Item = Ipv6Addr;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.buffer.len() < 16 {
+			return None;
+		}
+
+		let mut octets = [0u8; 16];
+		octets.copy_from_slice(&self.buffer[.. 16]);
+		self.buffer = &self.buffer[16 ..];
+
+		Some(octets.into())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::Ipv6Addr;
+	use crate::ip;
+	use crate::ip::v6::routing::Kind;
+
+	#[test]
+	fn segment_routing() {
+		let raw = [
+			0x06u8, 0x04, 0x04, 0x01, 0x01, 0x00, 0x00, 0x2a,
+			0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+			0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+		];
+
+		let routing = ip::v6::routing::Packet::new(&raw[..]).unwrap();
+		assert_eq!(routing.next_header(), ip::Protocol::Tcp);
+		assert_eq!(routing.kind(), Kind::SegmentRouting);
+		assert_eq!(routing.segments_left(), 1);
+		assert_eq!(routing.last_entry(), 1);
+		assert_eq!(routing.tag(), 42);
+		assert_eq!(routing.addresses().collect::<Vec<_>>(), vec![
+			"2001:db8::2".parse::<Ipv6Addr>().unwrap(),
+			"2001:db8::3".parse::<Ipv6Addr>().unwrap()]);
+		assert_eq!(routing.final_destination(), Some("2001:db8::2".parse().unwrap()));
+	}
+
+	#[test]
+	fn invalid_segment_routing() {
+		let raw = [0x06u8, 0x02, 0x04, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+		assert!(ip::v6::routing::Packet::new(&raw[..]).is_err());
+	}
+}
@@ -34,14 +34,37 @@ pub use crate::builder::Builder;
 /// Ethernet packet parser and builder.
 pub mod ether;
 
+/// ARP packet parser and builder.
+pub mod arp;
+
 /// IPv4 and IPv6 packet parser and builder.
 pub mod ip;
 
 /// ICMP packet parser and builder.
 pub mod icmp;
 
+/// ICMPv6 packet parser and builder.
+pub mod icmpv6;
+
 /// TCP packet parser and builder.
 pub mod tcp;
 
 /// UDP packet parser and builder.
 pub mod udp;
+
+/// Layered packet dissection.
+pub mod dissect;
+pub use crate::dissect::dissect;
+
+/// Flow keys, hashing and tracking.
+pub mod flow;
+pub use crate::flow::Key as FlowKey;
+
+/// NetFlow v5, NetFlow v9 and IPFIX parsers, and IPFIX exporter.
+pub mod netflow;
+
+/// pcap file reader and writer.
+pub mod pcap;
+
+/// pcapng file reader and writer.
+pub mod pcapng;
@@ -0,0 +1,454 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use byteorder::{ByteOrder, BigEndian};
+
+use crate::error::*;
+use crate::netflow::{Header, Field, Template, Set, Record, Message};
+
+/// Decoder for NetFlow v9 and IPFIX messages.
+///
+/// Templates are cached per exporter, version, domain and template ID, so
+/// data sets can be decoded in later messages; data sets whose template is
+/// not known yet are returned undecoded.
+#[derive(Clone, Debug, Default)]
+pub struct Decoder {
+	templates: HashMap<(SocketAddr, u16, u32, u16), Template>,
+}
+
+impl Decoder {
+	/// Create an empty decoder.
+	pub fn new() -> Decoder {
+		Decoder::default()
+	}
+
+	/// Get a cached template.
+	pub fn template(&self, exporter: SocketAddr, version: u16, domain: u32, id: u16) -> Option<&Template> {
+		self.templates.get(&(exporter, version, domain, id))
+	}
+
+	/// Number of cached templates.
+	pub fn len(&self) -> usize {
+		self.templates.len()
+	}
+
+	/// Whether no template is cached.
+	pub fn is_empty(&self) -> bool {
+		self.templates.is_empty()
+	}
+
+	/// Decode a message, usually the payload of a UDP packet, sent by the
+	/// given exporter.
+	pub fn decode(&mut self, exporter: SocketAddr, buffer: &[u8]) -> Result<Message> {
+		if buffer.len() < 2 {
+			Err(Error::SmallBuffer)?
+		}
+
+		let (header, mut buffer) = match BigEndian::read_u16(buffer) {
+			9 => {
+				if buffer.len() < 20 {
+					Err(Error::SmallBuffer)?
+				}
+
+				(Header {
+					version:     9,
+					count:       BigEndian::read_u16(&buffer[2 ..]),
+					uptime:      Some(BigEndian::read_u32(&buffer[4 ..])),
+					export_time: BigEndian::read_u32(&buffer[8 ..]),
+					sequence:    BigEndian::read_u32(&buffer[12 ..]),
+					domain:      BigEndian::read_u32(&buffer[16 ..]),
+				}, &buffer[20 ..])
+			}
+
+			10 => {
+				if buffer.len() < 16 {
+					Err(Error::SmallBuffer)?
+				}
+
+				let length = BigEndian::read_u16(&buffer[2 ..]);
+
+				if (length as usize) < 16 {
+					Err(Error::InvalidPacket)?
+				}
+
+				if buffer.len() < length as usize {
+					Err(Error::SmallBuffer)?
+				}
+
+				(Header {
+					version:     10,
+					count:       length,
+					uptime:      None,
+					export_time: BigEndian::read_u32(&buffer[4 ..]),
+					sequence:    BigEndian::read_u32(&buffer[8 ..]),
+					domain:      BigEndian::read_u32(&buffer[12 ..]),
+				}, &buffer[16 .. length as usize])
+			}
+
+			_ =>
+				Err(Error::InvalidPacket)?
+		};
+
+		let mut sets = Vec::new();
+
+		// NetFlow v9 exporters may pad the packet after the last set.
+		while buffer.len() >= 4 {
+			let id     = BigEndian::read_u16(buffer);
+			let length = BigEndian::read_u16(&buffer[2 ..]) as usize;
+
+			if length < 4 {
+				Err(Error::InvalidPacket)?
+			}
+
+			if buffer.len() < length {
+				Err(Error::SmallBuffer)?
+			}
+
+			let content = &buffer[4 .. length];
+			buffer = &buffer[length ..];
+
+			sets.push(match (header.version, id) {
+				(9, 0) | (10, 2) =>
+					Set::Template(self.templates(exporter, &header, content, false)?),
+
+				(9, 1) | (10, 3) =>
+					Set::OptionsTemplate(self.templates(exporter, &header, content, true)?),
+
+				(_, id) if id >= 256 =>
+					self.data(exporter, &header, id, content)?,
+
+				(_, id) =>
+					Set::Unknown {
+						id,
+						data: content.to_vec(),
+					},
+			});
+		}
+
+		Ok(Message {
+			header,
+			sets,
+		})
+	}
+
+	/// Parse and cache the templates in a template set.
+	fn templates(&mut self, exporter: SocketAddr, header: &Header, mut buffer: &[u8], options: bool) -> Result<Vec<Template>> {
+		let mut templates = Vec::new();
+
+		// Anything shorter than a template header, or with a reserved ID, is
+		// padding.
+		while buffer.len() >= 4 && BigEndian::read_u16(buffer) >= 256 {
+			let id = BigEndian::read_u16(buffer);
+
+			let (scope, count) = match (header.version, options) {
+				(9, true) => {
+					if buffer.len() < 6 {
+						Err(Error::SmallBuffer)?
+					}
+
+					let scope   = BigEndian::read_u16(&buffer[2 ..]) as usize / 4;
+					let options = BigEndian::read_u16(&buffer[4 ..]) as usize / 4;
+					buffer = &buffer[6 ..];
+
+					(scope, scope + options)
+				}
+
+				(10, true) => {
+					let count = BigEndian::read_u16(&buffer[2 ..]) as usize;
+
+					if count == 0 {
+						buffer = &buffer[4 ..];
+						(0, 0)
+					}
+					else {
+						if buffer.len() < 6 {
+							Err(Error::SmallBuffer)?
+						}
+
+						let scope = BigEndian::read_u16(&buffer[4 ..]) as usize;
+						buffer = &buffer[6 ..];
+
+						if scope == 0 || scope > count {
+							Err(Error::InvalidPacket)?
+						}
+
+						(scope, count)
+					}
+				}
+
+				_ => {
+					let count = BigEndian::read_u16(&buffer[2 ..]) as usize;
+					buffer = &buffer[4 ..];
+
+					(0, count)
+				}
+			};
+
+			let mut fields = Vec::with_capacity(count);
+
+			for _ in 0 .. count {
+				if buffer.len() < 4 {
+					Err(Error::SmallBuffer)?
+				}
+
+				let id     = BigEndian::read_u16(buffer);
+				let length = BigEndian::read_u16(&buffer[2 ..]);
+				buffer = &buffer[4 ..];
+
+				if header.version == 10 && id & 0x8000 != 0 {
+					if buffer.len() < 4 {
+						Err(Error::SmallBuffer)?
+					}
+
+					fields.push(Field {
+						id:         id & 0x7fff,
+						length,
+						enterprise: Some(BigEndian::read_u32(buffer)),
+					});
+
+					buffer = &buffer[4 ..];
+				}
+				else {
+					fields.push(Field::new(id, length));
+				}
+			}
+
+			let template = Template {
+				id,
+				scope,
+				fields,
+			};
+
+			if template.fields.is_empty() {
+				self.templates.remove(&(exporter, header.version, header.domain, id));
+			}
+			else {
+				self.templates.insert((exporter, header.version, header.domain, id), template.clone());
+			}
+
+			templates.push(template);
+		}
+
+		Ok(templates)
+	}
+
+	/// Decode a data set against its cached template.
+	fn data(&self, exporter: SocketAddr, header: &Header, id: u16, mut buffer: &[u8]) -> Result<Set> {
+		let template = if let Some(template) = self.template(exporter, header.version, header.domain, id) {
+			template
+		}
+		else {
+			return Ok(Set::Unknown {
+				id,
+				data: buffer.to_vec(),
+			});
+		};
+
+		let minimum = template.min_length();
+		let mut records = Vec::new();
+
+		// Whatever is left after the last record that fits is padding.
+		while minimum > 0 && buffer.len() >= minimum {
+			let mut values = Vec::with_capacity(template.fields.len());
+
+			for field in &template.fields {
+				let length = if field.is_variable() {
+					if buffer.is_empty() {
+						Err(Error::SmallBuffer)?
+					}
+
+					let length = buffer[0];
+					buffer = &buffer[1 ..];
+
+					if length < 255 {
+						length as usize
+					}
+					else {
+						if buffer.len() < 2 {
+							Err(Error::SmallBuffer)?
+						}
+
+						let length = BigEndian::read_u16(buffer) as usize;
+						buffer = &buffer[2 ..];
+
+						length
+					}
+				}
+				else {
+					field.length as usize
+				};
+
+				if buffer.len() < length {
+					Err(Error::SmallBuffer)?
+				}
+
+				values.push((*field, buffer[.. length].to_vec()));
+				buffer = &buffer[length ..];
+			}
+
+			records.push(Record::new(template, values));
+		}
+
+		Ok(Set::Data(records))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::netflow::{self, field, Set};
+	use crate::Error;
+
+	#[test]
+	fn v9() {
+		let mut decoder = netflow::Decoder::new();
+		let router      = "10.0.0.254:2055".parse().unwrap();
+
+		let data = [
+			0x00, 0x09, 0x00, 0x01,
+			0x00, 0x00, 0x27, 0x10,
+			0x5f, 0x5e, 0x10, 0x00,
+			0x00, 0x00, 0x00, 0x02,
+			0x00, 0x00, 0x00, 0x07,
+
+			0x01, 0x00, 0x00, 0x10,
+			0x0a, 0x00, 0x00, 0x01,
+			0x0a, 0x00, 0x00, 0x02,
+			0x00, 0x00, 0x00, 0x05,
+		];
+
+		let message = decoder.decode(router, &data).unwrap();
+		assert_eq!(message.header.uptime, Some(10000));
+		assert_eq!(message.header.domain, 7);
+		assert!(matches!(message.sets[0], Set::Unknown { id: 256, .. }));
+
+		let templates = [
+			0x00, 0x09, 0x00, 0x02,
+			0x00, 0x00, 0x27, 0x11,
+			0x5f, 0x5e, 0x10, 0x01,
+			0x00, 0x00, 0x00, 0x03,
+			0x00, 0x00, 0x00, 0x07,
+
+			0x00, 0x00, 0x00, 0x14,
+			0x01, 0x00, 0x00, 0x03,
+			0x00, 0x08, 0x00, 0x04,
+			0x00, 0x0c, 0x00, 0x04,
+			0x00, 0x02, 0x00, 0x04,
+
+			0x00, 0x01, 0x00, 0x18,
+			0x01, 0x01, 0x00, 0x04,
+			0x00, 0x08, 0x00, 0x01,
+			0x00, 0x04, 0x00, 0x22,
+			0x00, 0x04, 0x00, 0x23,
+			0x00, 0x01, 0x00, 0x00,
+
+			0x01, 0x01, 0x00, 0x10,
+			0x00, 0x00, 0x00, 0x01,
+			0x00, 0x00, 0x00, 0x64,
+			0x02, 0x00, 0x00, 0x00,
+		];
+
+		let message = decoder.decode(router, &templates).unwrap();
+		assert_eq!(decoder.len(), 2);
+
+		match message.sets[1] {
+			Set::OptionsTemplate(ref templates) => {
+				assert_eq!(templates.len(), 1);
+				assert_eq!(templates[0].scope, 1);
+				assert_eq!(templates[0].fields.len(), 3);
+			}
+
+			ref set =>
+				panic!("unexpected set {:?}", set)
+		}
+
+		let options = message.records().collect::<Vec<_>>();
+		assert_eq!(options.len(), 1);
+		assert_eq!(options[0].scope().len(), 1);
+		assert_eq!(options[0].unsigned(field::SAMPLING_INTERVAL), Some(100));
+		assert_eq!(options[0].unsigned(field::SAMPLING_ALGORITHM), Some(2));
+
+		let message = decoder.decode(router, &data).unwrap();
+		let records = message.records().collect::<Vec<_>>();
+		assert_eq!(records.len(), 1);
+		assert_eq!(records[0].template(), 256);
+		assert_eq!(records[0].address(field::SOURCE_IPV4_ADDRESS), Some("10.0.0.1".parse().unwrap()));
+		assert_eq!(records[0].address(field::DESTINATION_IPV4_ADDRESS), Some("10.0.0.2".parse().unwrap()));
+		assert_eq!(records[0].unsigned(field::PACKET_DELTA_COUNT), Some(5));
+		assert_eq!(records[0].get(field::OCTET_DELTA_COUNT), None);
+
+		let message = decoder.decode("10.0.0.253:2055".parse().unwrap(), &data).unwrap();
+		assert!(matches!(message.sets[0], Set::Unknown { id: 256, .. }));
+
+		assert!(matches!(decoder.decode(router, &data[.. 30]), Err(Error::SmallBuffer)));
+		assert!(matches!(decoder.decode(router, &[0x00, 0x08, 0x00, 0x00]), Err(Error::InvalidPacket)));
+	}
+
+	#[test]
+	fn ipfix() {
+		let mut decoder = netflow::Decoder::new();
+		let router      = "[2001:db8::fe]:4739".parse().unwrap();
+
+		let data = [
+			0x00, 0x0a, 0x00, 0x40,
+			0x5f, 0x5e, 0x10, 0x00,
+			0x00, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x01,
+
+			0x00, 0x02, 0x00, 0x18,
+			0x01, 0x00, 0x00, 0x03,
+			0x00, 0x04, 0x00, 0x01,
+			0x00, 0x52, 0xff, 0xff,
+			0x80, 0x01, 0x00, 0x02,
+			0x00, 0x00, 0x76, 0x8f,
+
+			0x01, 0x00, 0x00, 0x18,
+			0x11, 0x03, 0x61, 0x62,
+			0x63, 0x00, 0x2a, 0x06,
+			0xff, 0x00, 0x04, 0x61,
+			0x62, 0x63, 0x64, 0x00,
+			0x2b, 0x00, 0x00, 0x00,
+		];
+
+		let message = decoder.decode(router, &data).unwrap();
+		let template = decoder.template(router, 10, 1, 256).unwrap();
+		assert!(template.fields[1].is_variable());
+		assert_eq!(template.fields[2].enterprise, Some(30351));
+		assert_eq!(template.min_length(), 4);
+
+		let records = message.records().collect::<Vec<_>>();
+		assert_eq!(records.len(), 2);
+		assert_eq!(records[0].unsigned(field::PROTOCOL_IDENTIFIER), Some(17));
+		assert_eq!(records[0].values()[1].1, b"abc");
+		assert_eq!(records[0].values()[2].1, [0x00, 0x2a]);
+		assert_eq!(records[1].unsigned(field::PROTOCOL_IDENTIFIER), Some(6));
+		assert_eq!(records[1].values()[1].1, b"abcd");
+		assert_eq!(records[1].get(1), None);
+
+		let withdrawal = [
+			0x00, 0x0a, 0x00, 0x18,
+			0x5f, 0x5e, 0x10, 0x00,
+			0x00, 0x00, 0x00, 0x02,
+			0x00, 0x00, 0x00, 0x01,
+
+			0x00, 0x02, 0x00, 0x08,
+			0x01, 0x00, 0x00, 0x00,
+		];
+
+		decoder.decode(router, &withdrawal).unwrap();
+		assert!(decoder.is_empty());
+		assert!(matches!(decoder.decode(router, &data[.. 40]), Err(Error::SmallBuffer)));
+	}
+}
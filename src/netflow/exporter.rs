@@ -0,0 +1,388 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::net::IpAddr;
+use byteorder::{WriteBytesExt, BigEndian};
+
+use crate::error::*;
+use crate::buffer::Buffer;
+use crate::builder::Builder as Build;
+use crate::flow::{Flow, Key, Counters};
+use crate::netflow::{field, Field};
+use crate::udp;
+
+/// ID of the template for IPv4 flows.
+pub const IPV4_TEMPLATE: u16 = 256;
+
+/// ID of the template for IPv6 flows.
+pub const IPV6_TEMPLATE: u16 = 257;
+
+/// IPFIX exporter for flows.
+///
+/// Every flow is exported as one record per direction that saw packets, and
+/// every message carries the templates, as there is no way to know when a
+/// collector over UDP has lost them.
+#[derive(Clone, Debug)]
+pub struct Exporter {
+	domain:   u32,
+	sequence: u32,
+	size:     usize,
+}
+
+impl Exporter {
+	/// Create an exporter for the given observation domain, with messages of
+	/// at most 1400 octets so they fit in an Ethernet frame.
+	pub fn new(domain: u32) -> Exporter {
+		Exporter {
+			domain,
+			sequence: 0,
+			size:     1400,
+		}
+	}
+
+	/// Maximum size of a message, up to 65535 octets.
+	pub fn size(mut self, value: usize) -> Self {
+		self.size = value.min(u16::MAX as usize);
+		self
+	}
+
+	/// Observation domain ID.
+	pub fn domain(&self) -> u32 {
+		self.domain
+	}
+
+	/// Number of data records exported so far.
+	pub fn sequence(&self) -> u32 {
+		self.sequence
+	}
+
+	/// Fields of the template for the given address family.
+	pub fn template(ipv6: bool) -> Vec<Field> {
+		let (source, destination, length) = if ipv6 {
+			(field::SOURCE_IPV6_ADDRESS, field::DESTINATION_IPV6_ADDRESS, 16)
+		}
+		else {
+			(field::SOURCE_IPV4_ADDRESS, field::DESTINATION_IPV4_ADDRESS, 4)
+		};
+
+		vec![
+			Field::new(source, length),
+			Field::new(destination, length),
+			Field::new(field::SOURCE_TRANSPORT_PORT, 2),
+			Field::new(field::DESTINATION_TRANSPORT_PORT, 2),
+			Field::new(field::PROTOCOL_IDENTIFIER, 1),
+			Field::new(field::TCP_CONTROL_BITS, 2),
+			Field::new(field::PACKET_DELTA_COUNT, 8),
+			Field::new(field::OCTET_DELTA_COUNT, 8),
+			Field::new(field::FLOW_START_MILLISECONDS, 8),
+			Field::new(field::FLOW_END_MILLISECONDS, 8),
+		]
+	}
+
+	/// Serialise the flows into IPFIX messages, as many as needed to keep
+	/// each within the maximum size.
+	///
+	/// The flow timestamps are taken as durations since the epoch, which is
+	/// the case for capture timestamps.
+	pub fn messages(&mut self, export_time: u32, flows: &[Flow]) -> Result<Vec<Vec<u8>>> {
+		let mut templates = Vec::new();
+		for &(id, ipv6) in &[(IPV4_TEMPLATE, false), (IPV6_TEMPLATE, true)] {
+			let fields = Exporter::template(ipv6);
+
+			templates.write_u16::<BigEndian>(id)?;
+			templates.write_u16::<BigEndian>(fields.len() as u16)?;
+
+			for field in fields {
+				templates.write_u16::<BigEndian>(field.id)?;
+				templates.write_u16::<BigEndian>(field.length)?;
+			}
+		}
+
+		// Size of a message with data sets of the given lengths.
+		let size = |v4: usize, v6: usize| {
+			let set = |length: usize| if length == 0 { 0 } else { 4 + length };
+			16 + 4 + templates.len() + set(v4) + set(v6)
+		};
+
+		let mut messages = Vec::new();
+		let mut v4       = Vec::new();
+		let mut v6       = Vec::new();
+		let mut count    = 0u32;
+		let mut data     = Vec::new();
+
+		for flow in flows {
+			for &(key, counters) in &[(*flow.key(), flow.forward()), (flow.key().reverse(), flow.reverse())] {
+				if counters.packets == 0 {
+					continue;
+				}
+
+				data.clear();
+				record(&mut data, &key, counters)?;
+
+				let ipv6 = key.source.is_ipv6();
+				let limit = self.size;
+				let fits  = |v4: &[u8], v6: &[u8]| if ipv6 {
+					size(v4.len(), v6.len() + data.len()) <= limit
+				}
+				else {
+					size(v4.len() + data.len(), v6.len()) <= limit
+				};
+
+				if !fits(&v4, &v6) {
+					if count == 0 {
+						Err(Error::InvalidValue)?
+					}
+
+					messages.push(self.message(export_time, &templates, &v4, &v6, count)?);
+					v4.clear();
+					v6.clear();
+					count = 0;
+
+					if !fits(&v4, &v6) {
+						Err(Error::InvalidValue)?
+					}
+				}
+
+				if ipv6 { &mut v6 } else { &mut v4 }.extend_from_slice(&data);
+				count += 1;
+			}
+		}
+
+		if count > 0 {
+			messages.push(self.message(export_time, &templates, &v4, &v6, count)?);
+		}
+
+		Ok(messages)
+	}
+
+	/// Serialise the flows into IPFIX messages, each as the payload of a UDP
+	/// builder from the given function.
+	pub fn build<B, F>(&mut self, mut builder: F, export_time: u32, flows: &[Flow]) -> Result<Vec<B::Inner>>
+		where B: Buffer, F: FnMut() -> Result<udp::Builder<B>>
+	{
+		self.messages(export_time, flows)?.iter()
+			.map(|message| builder()?.payload(message)?.build())
+			.collect()
+	}
+
+	/// Assemble a message from the sets, accounting its records.
+	fn message(&mut self, export_time: u32, templates: &[u8], v4: &[u8], v6: &[u8], count: u32) -> Result<Vec<u8>> {
+		let mut buffer = vec![0u8; 16];
+		set(&mut buffer, 2, templates)?;
+		set(&mut buffer, IPV4_TEMPLATE, v4)?;
+		set(&mut buffer, IPV6_TEMPLATE, v6)?;
+
+		let length = buffer.len() as u16;
+		let mut header = &mut buffer[.. 16];
+		header.write_u16::<BigEndian>(10)?;
+		header.write_u16::<BigEndian>(length)?;
+		header.write_u32::<BigEndian>(export_time)?;
+		header.write_u32::<BigEndian>(self.sequence)?;
+		header.write_u32::<BigEndian>(self.domain)?;
+
+		self.sequence = self.sequence.wrapping_add(count);
+
+		Ok(buffer)
+	}
+}
+
+/// Append a set, unless it's empty.
+fn set(buffer: &mut Vec<u8>, id: u16, content: &[u8]) -> Result<()> {
+	if content.is_empty() {
+		return Ok(());
+	}
+
+	if content.len() + 4 > u16::MAX as usize {
+		Err(Error::InvalidValue)?
+	}
+
+	buffer.write_u16::<BigEndian>(id)?;
+	buffer.write_u16::<BigEndian>(content.len() as u16 + 4)?;
+	buffer.extend_from_slice(content);
+
+	Ok(())
+}
+
+/// Append the record for one direction of a flow.
+fn record(buffer: &mut Vec<u8>, key: &Key, counters: &Counters) -> Result<()> {
+	for address in &[key.source, key.destination] {
+		match *address {
+			IpAddr::V4(address) =>
+				buffer.extend_from_slice(&address.octets()),
+
+			IpAddr::V6(address) =>
+				buffer.extend_from_slice(&address.octets()),
+		}
+	}
+
+	buffer.write_u16::<BigEndian>(key.source_port)?;
+	buffer.write_u16::<BigEndian>(key.destination_port)?;
+	buffer.write_u8(key.protocol.into())?;
+	buffer.write_u16::<BigEndian>(counters.flags.bits())?;
+	buffer.write_u64::<BigEndian>(counters.packets)?;
+	buffer.write_u64::<BigEndian>(counters.bytes)?;
+	buffer.write_u64::<BigEndian>(counters.first.as_millis() as u64)?;
+	buffer.write_u64::<BigEndian>(counters.last.as_millis() as u64)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::SocketAddr;
+	use std::time::Duration;
+	use crate::builder::Builder;
+	use crate::dissect::{dissect, LinkType};
+	use crate::flow;
+	use crate::ip;
+	use crate::netflow::{self, field, Set};
+	use crate::packet::Packet as P;
+	use crate::udp;
+
+	fn udp(source: &str, destination: &str, sport: u16, dport: u16) -> Vec<u8> {
+		ip::v4::Builder::default()
+			.source(source.parse().unwrap()).unwrap()
+			.destination(destination.parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(sport).unwrap()
+				.destination(dport).unwrap()
+				.payload(b"query").unwrap()
+				.build().unwrap()
+	}
+
+	#[test]
+	fn roundtrip() {
+		let mut flows = Vec::new();
+
+		{
+			let mut table = flow::Table::new(|flow, _| flows.push(flow));
+
+			let query = udp("10.0.0.1", "10.0.0.2", 5353, 53);
+			let reply = udp("10.0.0.2", "10.0.0.1", 53, 5353);
+			let v6    = ip::v6::Builder::default()
+				.source("2001:db8::1".parse().unwrap()).unwrap()
+				.destination("2001:db8::2".parse().unwrap()).unwrap()
+				.udp().unwrap()
+					.source(1).unwrap()
+					.destination(2).unwrap()
+					.build().unwrap();
+
+			table.push(Duration::from_millis(1500), &dissect(LinkType::Raw, &query));
+			table.push(Duration::from_millis(2500), &dissect(LinkType::Raw, &reply));
+			table.push(Duration::from_millis(3000), &dissect(LinkType::Raw, &v6));
+			table.flush();
+		}
+
+		let mut exporter = netflow::Exporter::new(42);
+
+		let packets = exporter.build(|| ip::v4::Builder::default()
+			.source("192.168.0.1".parse().unwrap())?
+			.destination("192.168.0.2".parse().unwrap())?
+			.udp()?
+				.source(1024)?
+				.destination(netflow::IPFIX_PORT),
+			1_600_000_000, &flows).unwrap();
+
+		assert_eq!(packets.len(), 1);
+		assert_eq!(exporter.sequence(), 3);
+
+		let ip  = ip::v4::Packet::new(&packets[0][..]).unwrap();
+		let udp = udp::Packet::new(ip.payload()).unwrap();
+
+		let mut decoder = netflow::Decoder::new();
+		let source      = SocketAddr::new(ip.source().into(), udp.source());
+		let message     = decoder.decode(source, udp.payload()).unwrap();
+
+		assert_eq!(message.header.version, 10);
+		assert_eq!(message.header.count as usize, udp.payload().len());
+		assert_eq!(message.header.export_time, 1_600_000_000);
+		assert_eq!(message.header.sequence, 0);
+		assert_eq!(message.header.domain, 42);
+		assert!(matches!(message.sets[0], Set::Template(ref templates) if templates.len() == 2));
+
+		let records = message.records().collect::<Vec<_>>();
+		assert_eq!(records.len(), 3);
+
+		let addresses = records.iter().map(|record| record.address(field::SOURCE_IPV4_ADDRESS)
+			.or_else(|| record.address(field::SOURCE_IPV6_ADDRESS)).unwrap().to_string()).collect::<Vec<_>>();
+		assert!(addresses.contains(&"10.0.0.1".to_owned()));
+		assert!(addresses.contains(&"10.0.0.2".to_owned()));
+		assert!(addresses.contains(&"2001:db8::1".to_owned()));
+
+		let query = records.iter().find(|record| record.unsigned(field::SOURCE_TRANSPORT_PORT) == Some(5353)).unwrap();
+		assert_eq!(query.unsigned(field::DESTINATION_TRANSPORT_PORT), Some(53));
+		assert_eq!(query.unsigned(field::PROTOCOL_IDENTIFIER), Some(17));
+		assert_eq!(query.unsigned(field::PACKET_DELTA_COUNT), Some(1));
+		assert_eq!(query.unsigned(field::OCTET_DELTA_COUNT), Some(33));
+		assert_eq!(query.unsigned(field::FLOW_START_MILLISECONDS), Some(1500));
+		assert_eq!(query.unsigned(field::FLOW_END_MILLISECONDS), Some(1500));
+
+		let reply = records.iter().find(|record| record.unsigned(field::SOURCE_TRANSPORT_PORT) == Some(53)).unwrap();
+		assert_eq!(reply.unsigned(field::FLOW_START_MILLISECONDS), Some(2500));
+		assert_eq!(reply.unsigned(field::FLOW_END_MILLISECONDS), Some(2500));
+
+		let dns     = flows.iter().filter(|flow| flow.packets() == 2).cloned().collect::<Vec<_>>();
+		let message = exporter.messages(1_600_000_001, &dns).unwrap();
+		let message = decoder.decode(source, &message[0]).unwrap();
+		assert_eq!(message.header.sequence, 3);
+		assert_eq!(message.records().count(), 2);
+		assert_eq!(exporter.sequence(), 5);
+	}
+
+	#[test]
+	fn split() {
+		let mut flows = Vec::new();
+
+		{
+			let mut table = flow::Table::new(|flow, _| flows.push(flow));
+
+			for port in 0 .. 40 {
+				let query = udp("10.0.0.1", "10.0.0.2", 1024 + port, 53);
+				table.push(Duration::from_millis(1000), &dissect(LinkType::Raw, &query));
+			}
+
+			table.flush();
+		}
+
+		assert!(netflow::Exporter::new(42).size(150).messages(0, &flows).is_err());
+		assert!(netflow::Exporter::new(42).messages(0, &[]).unwrap().is_empty());
+
+		let mut exporter = netflow::Exporter::new(42).size(500);
+		let mut decoder  = netflow::Decoder::new();
+		let source       = SocketAddr::new("192.168.0.1".parse().unwrap(), 1024);
+		let messages     = exporter.messages(0, &flows).unwrap();
+
+		assert_eq!(messages.len(), 5);
+		assert_eq!(exporter.sequence(), 40);
+
+		let mut sequence = 0;
+		let mut ports    = Vec::new();
+
+		for message in &messages {
+			assert!(message.len() <= 500);
+
+			let message = decoder.decode(source, message).unwrap();
+			assert_eq!(message.header.sequence, sequence);
+			assert!(matches!(message.sets[0], Set::Template(ref templates) if templates.len() == 2));
+
+			for record in message.records() {
+				ports.push(record.unsigned(field::SOURCE_TRANSPORT_PORT).unwrap());
+				sequence += 1;
+			}
+		}
+
+		ports.sort();
+		assert_eq!(ports, (1024 .. 1064).collect::<Vec<_>>());
+	}
+}
@@ -0,0 +1,85 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+/// Number of octets.
+pub const OCTET_DELTA_COUNT: u16 = 1;
+
+/// Number of packets.
+pub const PACKET_DELTA_COUNT: u16 = 2;
+
+/// Transport protocol.
+pub const PROTOCOL_IDENTIFIER: u16 = 4;
+
+/// Type of service octet.
+pub const IP_CLASS_OF_SERVICE: u16 = 5;
+
+/// TCP flags.
+pub const TCP_CONTROL_BITS: u16 = 6;
+
+/// Source port.
+pub const SOURCE_TRANSPORT_PORT: u16 = 7;
+
+/// Source IPv4 address.
+pub const SOURCE_IPV4_ADDRESS: u16 = 8;
+
+/// Source IPv4 prefix length.
+pub const SOURCE_IPV4_PREFIX_LENGTH: u16 = 9;
+
+/// Input interface index.
+pub const INGRESS_INTERFACE: u16 = 10;
+
+/// Destination port.
+pub const DESTINATION_TRANSPORT_PORT: u16 = 11;
+
+/// Destination IPv4 address.
+pub const DESTINATION_IPV4_ADDRESS: u16 = 12;
+
+/// Destination IPv4 prefix length.
+pub const DESTINATION_IPV4_PREFIX_LENGTH: u16 = 13;
+
+/// Output interface index.
+pub const EGRESS_INTERFACE: u16 = 14;
+
+/// Next hop IPv4 address.
+pub const IP_NEXT_HOP_IPV4_ADDRESS: u16 = 15;
+
+/// Source AS number.
+pub const BGP_SOURCE_AS_NUMBER: u16 = 16;
+
+/// Destination AS number.
+pub const BGP_DESTINATION_AS_NUMBER: u16 = 17;
+
+/// Uptime at the last packet.
+pub const FLOW_END_SYS_UP_TIME: u16 = 21;
+
+/// Uptime at the first packet.
+pub const FLOW_START_SYS_UP_TIME: u16 = 22;
+
+/// Source IPv6 address.
+pub const SOURCE_IPV6_ADDRESS: u16 = 27;
+
+/// Destination IPv6 address.
+pub const DESTINATION_IPV6_ADDRESS: u16 = 28;
+
+/// Sampling interval.
+pub const SAMPLING_INTERVAL: u16 = 34;
+
+/// Sampling algorithm.
+pub const SAMPLING_ALGORITHM: u16 = 35;
+
+/// Time of the first packet in milliseconds.
+pub const FLOW_START_MILLISECONDS: u16 = 152;
+
+/// Time of the last packet in milliseconds.
+pub const FLOW_END_MILLISECONDS: u16 = 153;
@@ -0,0 +1,205 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use byteorder::{ByteOrder, BigEndian};
+
+/// Length of variable-length fields in IPFIX templates.
+pub const VARIABLE: u16 = 0xffff;
+
+/// Header of a NetFlow v9 or IPFIX message.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Header {
+	/// 9 for NetFlow v9, 10 for IPFIX.
+	pub version: u16,
+
+	/// Number of records for NetFlow v9, length of the message for IPFIX.
+	pub count: u16,
+
+	/// Milliseconds since the exporter booted, only in NetFlow v9.
+	pub uptime: Option<u32>,
+
+	/// Seconds since the epoch of the export.
+	pub export_time: u32,
+
+	/// Sequence number, counting packets for NetFlow v9 and data records for
+	/// IPFIX.
+	pub sequence: u32,
+
+	/// Source ID for NetFlow v9, observation domain ID for IPFIX.
+	pub domain: u32,
+}
+
+/// Field specifier of a template.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Field {
+	/// Information element identifier.
+	pub id: u16,
+
+	/// Length of the field, `VARIABLE` for variable-length IPFIX fields.
+	pub length: u16,
+
+	/// Private enterprise number, only in IPFIX.
+	pub enterprise: Option<u32>,
+}
+
+impl Field {
+	/// Create an IANA field.
+	pub fn new(id: u16, length: u16) -> Field {
+		Field {
+			id,
+			length,
+			enterprise: None,
+		}
+	}
+
+	/// Whether the field is variable-length.
+	pub fn is_variable(&self) -> bool {
+		self.length == VARIABLE
+	}
+}
+
+/// Template or options template describing data records.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Template {
+	/// Template ID, matching the ID of the data sets using it.
+	pub id: u16,
+
+	/// Number of leading scope fields, 0 for plain templates.
+	pub scope: usize,
+
+	/// Field specifiers.
+	pub fields: Vec<Field>,
+}
+
+impl Template {
+	/// Whether the template is an options template.
+	pub fn is_options(&self) -> bool {
+		self.scope > 0
+	}
+
+	/// Minimum length of a record, variable-length fields taking one octet.
+	pub fn min_length(&self) -> usize {
+		self.fields.iter().map(|field|
+			if field.is_variable() { 1 } else { field.length as usize }).sum()
+	}
+}
+
+/// Set of a NetFlow v9 or IPFIX message.
+#[derive(Clone, Debug)]
+pub enum Set {
+	/// Templates, with no fields for withdrawn templates.
+	Template(Vec<Template>),
+
+	/// Options templates.
+	OptionsTemplate(Vec<Template>),
+
+	/// Data records decoded with a known template.
+	Data(Vec<Record>),
+
+	/// Data set whose template was not seen yet, or set with a reserved ID.
+	Unknown {
+		/// Set ID.
+		id: u16,
+
+		/// Undecoded content of the set.
+		data: Vec<u8>,
+	},
+}
+
+/// Data record decoded against its template.
+#[derive(Clone, Debug)]
+pub struct Record {
+	template: u16,
+	scope:    usize,
+	values:   Vec<(Field, Vec<u8>)>,
+}
+
+impl Record {
+	pub(crate) fn new(template: &Template, values: Vec<(Field, Vec<u8>)>) -> Record {
+		Record {
+			template: template.id,
+			scope:    template.scope,
+			values,
+		}
+	}
+
+	/// ID of the template of the record.
+	pub fn template(&self) -> u16 {
+		self.template
+	}
+
+	/// Scope fields and their values, for options records.
+	pub fn scope(&self) -> &[(Field, Vec<u8>)] {
+		&self.values[.. self.scope]
+	}
+
+	/// Fields and their values, in template order.
+	pub fn values(&self) -> &[(Field, Vec<u8>)] {
+		&self.values
+	}
+
+	/// Value of the first IANA field with the given identifier.
+	pub fn get(&self, id: u16) -> Option<&[u8]> {
+		self.values.iter()
+			.find(|&&(field, _)| field.id == id && field.enterprise.is_none())
+			.map(|(_, value)| &value[..])
+	}
+
+	/// Value of the given field as an unsigned integer, reduced-size encoding
+	/// included.
+	pub fn unsigned(&self, id: u16) -> Option<u64> {
+		match self.get(id)? {
+			value if !value.is_empty() && value.len() <= 8 =>
+				Some(BigEndian::read_uint(value, value.len())),
+
+			_ =>
+				None
+		}
+	}
+
+	/// Value of the given field as an address.
+	pub fn address(&self, id: u16) -> Option<IpAddr> {
+		match self.get(id)? {
+			value if value.len() == 4 =>
+				Some(Ipv4Addr::from(BigEndian::read_u32(value)).into()),
+
+			value if value.len() == 16 =>
+				Some(Ipv6Addr::from(BigEndian::read_u128(value)).into()),
+
+			_ =>
+				None
+		}
+	}
+}
+
+/// Decoded NetFlow v9 or IPFIX message.
+#[derive(Clone, Debug)]
+pub struct Message {
+	/// Message header.
+	pub header: Header,
+
+	/// Sets of the message.
+	pub sets: Vec<Set>,
+}
+
+impl Message {
+	/// Data records of all the sets, in order.
+	pub fn records(&self) -> impl Iterator<Item = &Record> {
+		self.sets.iter().flat_map(|set| match *set {
+			Set::Data(ref records) => &records[..],
+			_                      => &[],
+		})
+	}
+}
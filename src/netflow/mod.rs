@@ -0,0 +1,35 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+/// NetFlow v5 packet parser.
+pub mod v5;
+
+/// Information element identifiers from the IANA IPFIX registry, the ones
+/// below 128 matching the NetFlow v9 field types.
+pub mod field;
+
+mod message;
+pub use self::message::{VARIABLE, Header, Field, Template, Set, Record, Message};
+
+mod decoder;
+pub use self::decoder::Decoder;
+
+mod exporter;
+pub use self::exporter::{Exporter, IPV4_TEMPLATE, IPV6_TEMPLATE};
+
+/// Port NetFlow collectors usually listen on.
+pub const NETFLOW_PORT: u16 = 2055;
+
+/// Port IPFIX collectors listen on.
+pub const IPFIX_PORT: u16 = 4739;
@@ -0,0 +1,455 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::fmt;
+use std::net::Ipv4Addr;
+use byteorder::{ReadBytesExt, BigEndian};
+
+use crate::error::*;
+use crate::packet::Packet as P;
+use crate::ip::Protocol;
+use crate::tcp::Flags;
+
+/// Maximum number of records in a packet.
+pub const MAX_RECORDS: u16 = 30;
+
+/// NetFlow v5 packet parser.
+pub struct Packet<B> {
+	buffer: B,
+}
+
+sized!(Packet,
+	header {
+		min:  24,
+		max:  24,
+		size: 24,
+	}
+
+	payload {
+		min:  0,
+		max:  MAX_RECORDS as usize * 48,
+		size: p => p.count() as usize * 48,
+	});
+
+impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("netflow::v5::Packet")
+			.field("version", &self.version())
+			.field("count", &self.count())
+			.field("uptime", &self.uptime())
+			.field("seconds", &self.seconds())
+			.field("nanoseconds", &self.nanoseconds())
+			.field("sequence", &self.sequence())
+			.field("engine_type", &self.engine_type())
+			.field("engine_id", &self.engine_id())
+			.field("sampling", &self.sampling())
+			.field("records", &self.records().collect::<Vec<_>>())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Create a NetFlow v5 packet without checking.
+	pub fn unchecked(buffer: B) -> Packet<B> {
+		Packet { buffer }
+	}
+
+	/// Parse a NetFlow v5 packet without checking the payload.
+	pub fn no_payload(buffer: B) -> Result<Packet<B>> {
+		use crate::size::header::Min;
+
+		let packet = Packet::unchecked(buffer);
+
+		if packet.buffer.as_ref().len() < Self::min() {
+			Err(Error::SmallBuffer)?
+		}
+
+		if packet.version() != 5 {
+			Err(Error::InvalidPacket)?
+		}
+
+		Ok(packet)
+	}
+
+	/// Parse a NetFlow v5 packet, checking the buffer contents are correct.
+	pub fn new(buffer: B) -> Result<Packet<B>> {
+		use crate::size::Size;
+
+		let packet = Packet::no_payload(buffer)?;
+
+		if packet.count() > MAX_RECORDS {
+			Err(Error::InvalidPacket)?
+		}
+
+		if packet.buffer.as_ref().len() < packet.size() {
+			Err(Error::SmallBuffer)?
+		}
+
+		Ok(packet)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Convert the packet to its owned version.
+	pub fn to_owned(&self) -> Packet<Vec<u8>> {
+		Packet {
+			buffer: self.buffer.as_ref().to_vec(),
+		}
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Packet<B> {
+	fn as_ref(&self) -> &[u8] {
+		use crate::size::Size;
+
+		&self.buffer.as_ref()[.. self.size()]
+	}
+}
+
+impl<B: AsRef<[u8]>> P for Packet<B> {
+	fn split(&self) -> (&[u8], &[u8]) {
+		use crate::size::payload::Size;
+
+		let header  = 24;
+		let payload = self.size();
+
+		let buffer = self.buffer.as_ref();
+		let buffer = if buffer.len() < header + payload {
+			buffer
+		}
+		else {
+			&buffer[.. header + payload]
+		};
+
+		buffer.split_at(header)
+	}
+}
+
+impl<B: AsRef<[u8]>> Packet<B> {
+	/// Version of the packet, always 5.
+	pub fn version(&self) -> u16 {
+		(&self.buffer.as_ref()[0 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Number of records.
+	pub fn count(&self) -> u16 {
+		(&self.buffer.as_ref()[2 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Milliseconds since the exporter booted.
+	pub fn uptime(&self) -> u32 {
+		(&self.buffer.as_ref()[4 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Seconds since the epoch of the export.
+	pub fn seconds(&self) -> u32 {
+		(&self.buffer.as_ref()[8 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Residual nanoseconds of the export.
+	pub fn nanoseconds(&self) -> u32 {
+		(&self.buffer.as_ref()[12 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Number of flows exported before this packet.
+	pub fn sequence(&self) -> u32 {
+		(&self.buffer.as_ref()[16 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Type of the flow switching engine.
+	pub fn engine_type(&self) -> u8 {
+		self.buffer.as_ref()[20]
+	}
+
+	/// Slot number of the flow switching engine.
+	pub fn engine_id(&self) -> u8 {
+		self.buffer.as_ref()[21]
+	}
+
+	/// Sampling mode, in the first two bits, and interval.
+	pub fn sampling(&self) -> u16 {
+		(&self.buffer.as_ref()[22 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Sampling interval, 0 when not sampling.
+	pub fn sampling_interval(&self) -> u16 {
+		self.sampling() & 0x3fff
+	}
+
+	/// Records in the packet.
+	pub fn records(&self) -> RecordIter<'_> {
+		RecordIter {
+			buffer: self.payload(),
+		}
+	}
+}
+
+/// Iterator over NetFlow v5 records.
+pub struct RecordIter<'a> {
+	buffer: &'a [u8],
+}
+
+impl<'a> Iterator for RecordIter<'a> {
+	type Item = Record<&'a [u8]>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.buffer.len() < 48 {
+			return None;
+		}
+
+		let (record, rest) = self.buffer.split_at(48);
+		self.buffer = rest;
+
+		Some(Record::unchecked(record))
+	}
+}
+
+/// NetFlow v5 record parser.
+pub struct Record<B> {
+	buffer: B,
+}
+
+impl<B: AsRef<[u8]>> fmt::Debug for Record<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("netflow::v5::Record")
+			.field("source", &self.source())
+			.field("destination", &self.destination())
+			.field("next_hop", &self.next_hop())
+			.field("input", &self.input())
+			.field("output", &self.output())
+			.field("packets", &self.packets())
+			.field("bytes", &self.bytes())
+			.field("first", &self.first())
+			.field("last", &self.last())
+			.field("source_port", &self.source_port())
+			.field("destination_port", &self.destination_port())
+			.field("flags", &self.flags())
+			.field("protocol", &self.protocol())
+			.field("tos", &self.tos())
+			.field("source_as", &self.source_as())
+			.field("destination_as", &self.destination_as())
+			.field("source_mask", &self.source_mask())
+			.field("destination_mask", &self.destination_mask())
+			.finish()
+	}
+}
+
+impl<B: AsRef<[u8]>> fmt::Display for Record<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:?} {}:{} > {}:{}, {} packets, {} bytes",
+			self.protocol(), self.source(), self.source_port(),
+			self.destination(), self.destination_port(),
+			self.packets(), self.bytes())
+	}
+}
+
+impl<B: AsRef<[u8]>> Record<B> {
+	/// Create a NetFlow v5 record without checking.
+	pub fn unchecked(buffer: B) -> Record<B> {
+		Record { buffer }
+	}
+
+	/// Parse a NetFlow v5 record.
+	pub fn new(buffer: B) -> Result<Record<B>> {
+		if buffer.as_ref().len() < 48 {
+			Err(Error::SmallBuffer)?
+		}
+
+		Ok(Record::unchecked(buffer))
+	}
+
+	/// Convert the record to its owned version.
+	pub fn to_owned(&self) -> Record<Vec<u8>> {
+		Record {
+			buffer: self.buffer.as_ref()[.. 48].to_vec(),
+		}
+	}
+}
+
+impl<B: AsRef<[u8]>> AsRef<[u8]> for Record<B> {
+	fn as_ref(&self) -> &[u8] {
+		&self.buffer.as_ref()[.. 48]
+	}
+}
+
+impl<B: AsRef<[u8]>> Record<B> {
+	/// Source address.
+	pub fn source(&self) -> Ipv4Addr {
+		Ipv4Addr::from((&self.buffer.as_ref()[0 ..]).read_u32::<BigEndian>().unwrap())
+	}
+
+	/// Destination address.
+	pub fn destination(&self) -> Ipv4Addr {
+		Ipv4Addr::from((&self.buffer.as_ref()[4 ..]).read_u32::<BigEndian>().unwrap())
+	}
+
+	/// Address of the next hop router.
+	pub fn next_hop(&self) -> Ipv4Addr {
+		Ipv4Addr::from((&self.buffer.as_ref()[8 ..]).read_u32::<BigEndian>().unwrap())
+	}
+
+	/// SNMP index of the input interface.
+	pub fn input(&self) -> u16 {
+		(&self.buffer.as_ref()[12 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// SNMP index of the output interface.
+	pub fn output(&self) -> u16 {
+		(&self.buffer.as_ref()[14 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Number of packets in the flow.
+	pub fn packets(&self) -> u32 {
+		(&self.buffer.as_ref()[16 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Number of octets in the flow, counting the IP headers.
+	pub fn bytes(&self) -> u32 {
+		(&self.buffer.as_ref()[20 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Uptime at the first packet of the flow.
+	pub fn first(&self) -> u32 {
+		(&self.buffer.as_ref()[24 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Uptime at the last packet of the flow.
+	pub fn last(&self) -> u32 {
+		(&self.buffer.as_ref()[28 ..]).read_u32::<BigEndian>().unwrap()
+	}
+
+	/// Source port.
+	pub fn source_port(&self) -> u16 {
+		(&self.buffer.as_ref()[32 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Destination port.
+	pub fn destination_port(&self) -> u16 {
+		(&self.buffer.as_ref()[34 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Union of the TCP flags.
+	pub fn flags(&self) -> Flags {
+		Flags::from_bits_truncate(self.buffer.as_ref()[37] as u16)
+	}
+
+	/// Protocol of the flow.
+	pub fn protocol(&self) -> Protocol {
+		self.buffer.as_ref()[38].into()
+	}
+
+	/// Type of service.
+	pub fn tos(&self) -> u8 {
+		self.buffer.as_ref()[39]
+	}
+
+	/// Autonomous system of the source.
+	pub fn source_as(&self) -> u16 {
+		(&self.buffer.as_ref()[40 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Autonomous system of the destination.
+	pub fn destination_as(&self) -> u16 {
+		(&self.buffer.as_ref()[42 ..]).read_u16::<BigEndian>().unwrap()
+	}
+
+	/// Prefix length of the source.
+	pub fn source_mask(&self) -> u8 {
+		self.buffer.as_ref()[44]
+	}
+
+	/// Prefix length of the destination.
+	pub fn destination_mask(&self) -> u8 {
+		self.buffer.as_ref()[45]
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::builder::Builder;
+	use crate::ip::{self, Protocol};
+	use crate::packet::Packet as P;
+	use crate::netflow::v5;
+	use crate::tcp::Flags;
+	use crate::udp;
+	use crate::Error;
+
+	#[test]
+	fn records() {
+		let mut payload = vec![
+			0x00, 0x05, 0x00, 0x01,
+			0x00, 0x00, 0x27, 0x10,
+			0x5f, 0x5e, 0x10, 0x00,
+			0x00, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x2a,
+			0x00, 0x01, 0x40, 0x64,
+
+			0x0a, 0x00, 0x00, 0x01,
+			0x0a, 0x00, 0x00, 0x02,
+			0x0a, 0x00, 0x00, 0xfe,
+			0x00, 0x01, 0x00, 0x02,
+			0x00, 0x00, 0x00, 0x03,
+			0x00, 0x00, 0x00, 0xb4,
+			0x00, 0x00, 0x1f, 0x40,
+			0x00, 0x00, 0x23, 0x28,
+			0x05, 0x39, 0x00, 0x50,
+			0x00, 0x1b, 0x06, 0x00,
+			0xfd, 0xe8, 0xfd, 0xe9,
+			0x18, 0x10, 0x00, 0x00,
+		];
+
+		let packet = ip::v4::Builder::default()
+			.source("192.168.0.1".parse().unwrap()).unwrap()
+			.destination("192.168.0.2".parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(1024).unwrap()
+				.destination(2055).unwrap()
+				.payload(&payload).unwrap()
+				.build().unwrap();
+
+		let ip      = ip::v4::Packet::new(&packet[..]).unwrap();
+		let udp     = udp::Packet::new(ip.payload()).unwrap();
+		let netflow = v5::Packet::new(udp.payload()).unwrap();
+
+		assert_eq!(netflow.count(), 1);
+		assert_eq!(netflow.uptime(), 10000);
+		assert_eq!(netflow.seconds(), 0x5f5e1000);
+		assert_eq!(netflow.sequence(), 42);
+		assert_eq!((netflow.engine_type(), netflow.engine_id()), (0, 1));
+		assert_eq!(netflow.sampling_interval(), 100);
+
+		let records = netflow.records().collect::<Vec<_>>();
+		assert_eq!(records.len(), 1);
+
+		let record = &records[0];
+		assert_eq!(record.source(), "10.0.0.1".parse::<std::net::Ipv4Addr>().unwrap());
+		assert_eq!(record.destination(), "10.0.0.2".parse::<std::net::Ipv4Addr>().unwrap());
+		assert_eq!(record.next_hop(), "10.0.0.254".parse::<std::net::Ipv4Addr>().unwrap());
+		assert_eq!((record.input(), record.output()), (1, 2));
+		assert_eq!((record.packets(), record.bytes()), (3, 180));
+		assert_eq!((record.first(), record.last()), (8000, 9000));
+		assert_eq!((record.source_port(), record.destination_port()), (1337, 80));
+		assert_eq!(record.flags(), Flags::FIN | Flags::SYN | Flags::PSH | Flags::ACK);
+		assert_eq!(record.protocol(), Protocol::Tcp);
+		assert_eq!((record.source_as(), record.destination_as()), (65000, 65001));
+		assert_eq!((record.source_mask(), record.destination_mask()), (24, 16));
+		assert_eq!(record.to_string(), "Tcp 10.0.0.1:1337 > 10.0.0.2:80, 3 packets, 180 bytes");
+
+		payload.truncate(60);
+		assert!(matches!(v5::Packet::new(&payload[..]), Err(Error::SmallBuffer)));
+
+		payload[1] = 9;
+		assert!(matches!(v5::Packet::new(&payload[..]), Err(Error::InvalidPacket)));
+	}
+}
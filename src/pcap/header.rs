@@ -0,0 +1,185 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::io::Write;
+use byteorder::{ByteOrder, BigEndian, LittleEndian, WriteBytesExt};
+
+use crate::error::*;
+use crate::pcap::LinkType;
+
+/// Byte order of a capture file.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Endianness {
+	/// Little-endian.
+	Little,
+
+	/// Big-endian.
+	Big,
+}
+
+/// Timestamp precision of a capture file.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Precision {
+	/// Microsecond timestamps.
+	Micro,
+
+	/// Nanosecond timestamps.
+	Nano,
+}
+
+/// Magic number for microsecond precision files.
+pub const MAGIC: u32 = 0xa1b2_c3d4;
+
+/// Magic number for nanosecond precision files.
+pub const MAGIC_NANO: u32 = 0xa1b2_3c4d;
+
+/// Global header of a pcap file.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Header {
+	/// Byte order of the file.
+	pub endianness: Endianness,
+
+	/// Precision of the record timestamps.
+	pub precision: Precision,
+
+	/// Major and minor version of the format.
+	pub version: (u16, u16),
+
+	/// Offset of the timestamps from UTC in seconds.
+	pub zone: i32,
+
+	/// Accuracy of the timestamps.
+	pub sigfigs: u32,
+
+	/// Maximum length of captured data per record.
+	pub snaplen: u32,
+
+	/// Link-layer header type of the records.
+	pub link_type: LinkType,
+}
+
+impl Default for Header {
+	fn default() -> Self {
+		Header {
+			endianness: Endianness::Little,
+			precision:  Precision::Micro,
+			version:    (2, 4),
+			zone:       0,
+			sigfigs:    0,
+			snaplen:    65535,
+			link_type:  LinkType::Ethernet,
+		}
+	}
+}
+
+impl Header {
+	/// Parse a global header from the start of the buffer.
+	pub fn parse(buffer: &[u8]) -> Result<Header> {
+		if buffer.len() < 24 {
+			Err(Error::SmallBuffer)?
+		}
+
+		let (endianness, precision) = match (BigEndian::read_u32(buffer), LittleEndian::read_u32(buffer)) {
+			(MAGIC, _)      => (Endianness::Big, Precision::Micro),
+			(MAGIC_NANO, _) => (Endianness::Big, Precision::Nano),
+			(_, MAGIC)      => (Endianness::Little, Precision::Micro),
+			(_, MAGIC_NANO) => (Endianness::Little, Precision::Nano),
+			_               => Err(Error::InvalidPacket)?,
+		};
+
+		Ok(Header {
+			endianness,
+			precision,
+			version:    (endianness.read_u16(&buffer[4 ..]), endianness.read_u16(&buffer[6 ..])),
+			zone:       endianness.read_u32(&buffer[8 ..]) as i32,
+			sigfigs:    endianness.read_u32(&buffer[12 ..]),
+			snaplen:    endianness.read_u32(&buffer[16 ..]),
+			link_type:  endianness.read_u32(&buffer[20 ..]).into(),
+		})
+	}
+
+	/// Write the global header.
+	pub fn write<W: Write>(&self, mut output: W) -> Result<()> {
+		let magic = match self.precision {
+			Precision::Micro => MAGIC,
+			Precision::Nano  => MAGIC_NANO,
+		};
+
+		self.endianness.write_u32(&mut output, magic)?;
+		self.endianness.write_u16(&mut output, self.version.0)?;
+		self.endianness.write_u16(&mut output, self.version.1)?;
+		self.endianness.write_u32(&mut output, self.zone as u32)?;
+		self.endianness.write_u32(&mut output, self.sigfigs)?;
+		self.endianness.write_u32(&mut output, self.snaplen)?;
+		self.endianness.write_u32(&mut output, self.link_type.into())?;
+
+		Ok(())
+	}
+}
+
+impl Endianness {
+	/// Read a `u16` in this byte order.
+	pub fn read_u16(self, buffer: &[u8]) -> u16 {
+		match self {
+			Endianness::Little => LittleEndian::read_u16(buffer),
+			Endianness::Big    => BigEndian::read_u16(buffer),
+		}
+	}
+
+	/// Read a `u32` in this byte order.
+	pub fn read_u32(self, buffer: &[u8]) -> u32 {
+		match self {
+			Endianness::Little => LittleEndian::read_u32(buffer),
+			Endianness::Big    => BigEndian::read_u32(buffer),
+		}
+	}
+
+	/// Read a `u64` in this byte order.
+	pub fn read_u64(self, buffer: &[u8]) -> u64 {
+		match self {
+			Endianness::Little => LittleEndian::read_u64(buffer),
+			Endianness::Big    => BigEndian::read_u64(buffer),
+		}
+	}
+
+	/// Write a `u16` in this byte order.
+	pub fn write_u16<W: Write>(self, mut output: W, value: u16) -> Result<()> {
+		match self {
+			Endianness::Little => output.write_u16::<LittleEndian>(value)?,
+			Endianness::Big    => output.write_u16::<BigEndian>(value)?,
+		}
+
+		Ok(())
+	}
+
+	/// Write a `u32` in this byte order.
+	pub fn write_u32<W: Write>(self, mut output: W, value: u32) -> Result<()> {
+		match self {
+			Endianness::Little => output.write_u32::<LittleEndian>(value)?,
+			Endianness::Big    => output.write_u32::<BigEndian>(value)?,
+		}
+
+		Ok(())
+	}
+
+	/// Write a `u64` in this byte order.
+	pub fn write_u64<W: Write>(self, mut output: W, value: u64) -> Result<()> {
+		match self {
+			Endianness::Little => output.write_u64::<LittleEndian>(value)?,
+			Endianness::Big    => output.write_u64::<BigEndian>(value)?,
+		}
+
+		Ok(())
+	}
+}
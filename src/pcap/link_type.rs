@@ -0,0 +1,144 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use crate::error::*;
+use crate::ether;
+use crate::ip;
+
+/// Link-layer header types.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum LinkType {
+	/// BSD loopback.
+	Null,
+
+	/// Ethernet.
+	Ethernet,
+
+	/// IEEE 802.5 Token Ring.
+	Ieee802,
+
+	/// Point-to-Point Protocol.
+	Ppp,
+
+	/// Raw IP.
+	Raw,
+
+	/// IEEE 802.11 wireless.
+	Ieee80211,
+
+	/// OpenBSD loopback.
+	Loop,
+
+	/// Linux cooked capture.
+	LinuxSll,
+
+	/// IEEE 802.11 with Radiotap header.
+	Ieee80211Radiotap,
+
+	/// Raw IPv4.
+	Ipv4,
+
+	/// Raw IPv6.
+	Ipv6,
+
+	/// Linux cooked capture v2.
+	LinuxSll2,
+
+	/// Unknown link type.
+	Unknown(u32),
+}
+
+impl From<u32> for LinkType {
+	fn from(value: u32) -> LinkType {
+		use self::LinkType::*;
+
+		match value {
+			0   => Null,
+			1   => Ethernet,
+			6   => Ieee802,
+			9   => Ppp,
+			101 => Raw,
+			105 => Ieee80211,
+			108 => Loop,
+			113 => LinuxSll,
+			127 => Ieee80211Radiotap,
+			228 => Ipv4,
+			229 => Ipv6,
+			276 => LinuxSll2,
+			n   => Unknown(n),
+		}
+	}
+}
+
+impl From<LinkType> for u32 {
+	fn from(value: LinkType) -> u32 {
+		use self::LinkType::*;
+
+		match value {
+			Null              => 0,
+			Ethernet          => 1,
+			Ieee802           => 6,
+			Ppp               => 9,
+			Raw               => 101,
+			Ieee80211         => 105,
+			Loop              => 108,
+			LinuxSll          => 113,
+			Ieee80211Radiotap => 127,
+			Ipv4              => 228,
+			Ipv6              => 229,
+			LinuxSll2         => 276,
+			Unknown(n)        => n,
+		}
+	}
+}
+
+impl LinkType {
+	/// Parse the data as an Ethernet frame.
+	pub(crate) fn ether(self, data: &[u8]) -> Result<ether::Packet<&[u8]>> {
+		if self != LinkType::Ethernet {
+			Err(Error::InvalidPacket)?
+		}
+
+		ether::Packet::new(data)
+	}
+
+	/// Parse the data as an IP packet, skipping the Ethernet header if any.
+	pub(crate) fn ip(self, data: &[u8]) -> Result<ip::Packet<&[u8]>> {
+		let data = match self {
+			LinkType::Ethernet => {
+				let packet = ether::Packet::new(data)?;
+
+				match packet.inner()? {
+					(ether::Protocol::Ipv4, payload) | (ether::Protocol::Ipv6, payload) =>
+						&data[data.len() - payload.len() ..],
+
+					_ =>
+						Err(Error::InvalidPacket)?
+				}
+			}
+
+			LinkType::Raw | LinkType::Ipv4 | LinkType::Ipv6 =>
+				data,
+
+			_ =>
+				Err(Error::InvalidPacket)?
+		};
+
+		if data.is_empty() {
+			Err(Error::SmallBuffer)?
+		}
+
+		ip::Packet::new(data)
+	}
+}
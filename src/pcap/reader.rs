@@ -0,0 +1,210 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::time::Duration;
+
+use crate::error::*;
+use crate::dissect;
+use crate::ether;
+use crate::ip;
+use crate::pcap::{Header, Precision, LinkType};
+
+/// Zero-copy pcap file reader, iterating over the records.
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+	header: Header,
+	buffer: &'a [u8],
+	offset: usize,
+}
+
+/// A record within a pcap file.
+#[derive(Copy, Clone, Debug)]
+pub struct Record<'a> {
+	link_type: LinkType,
+	timestamp: Duration,
+	captured:  u32,
+	length:    u32,
+	data:      &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+	/// Parse the global header and prepare to read the records.
+	pub fn new(buffer: &'a [u8]) -> Result<Reader<'a>> {
+		Ok(Reader {
+			header: Header::parse(buffer)?,
+			buffer,
+			offset: 24,
+		})
+	}
+
+	/// Global header of the file.
+	pub fn header(&self) -> &Header {
+		&self.header
+	}
+}
+
+impl<'a> Iterator for Reader<'a> {
+	type Item = Result<Record<'a>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.offset >= self.buffer.len() {
+			return None;
+		}
+
+		let buffer = &self.buffer[self.offset ..];
+
+		if buffer.len() < 16 {
+			self.offset = self.buffer.len();
+			return Some(Err(Error::SmallBuffer));
+		}
+
+		let endianness = self.header.endianness;
+		let seconds    = endianness.read_u32(&buffer[0 ..]);
+		let fraction   = endianness.read_u32(&buffer[4 ..]);
+		let captured   = endianness.read_u32(&buffer[8 ..]);
+		let length     = endianness.read_u32(&buffer[12 ..]);
+
+		let nanos = match self.header.precision {
+			Precision::Micro => fraction.checked_mul(1_000),
+			Precision::Nano  => Some(fraction),
+		};
+
+		let nanos = match nanos {
+			Some(nanos) if nanos < 1_000_000_000 =>
+				nanos,
+
+			_ => {
+				self.offset = self.buffer.len();
+				return Some(Err(Error::InvalidPacket));
+			}
+		};
+
+		if buffer.len() < 16 + captured as usize {
+			self.offset = self.buffer.len();
+			return Some(Err(Error::SmallBuffer));
+		}
+
+		self.offset += 16 + captured as usize;
+
+		Some(Ok(Record {
+			link_type: self.header.link_type,
+			timestamp: Duration::new(seconds as u64, nanos),
+			captured,
+			length,
+			data:      &buffer[16 .. 16 + captured as usize],
+		}))
+	}
+}
+
+impl<'a> Record<'a> {
+	/// Link-layer header type of the data.
+	pub fn link_type(&self) -> LinkType {
+		self.link_type
+	}
+
+	/// Time since the UNIX epoch the packet was captured at.
+	pub fn timestamp(&self) -> Duration {
+		self.timestamp
+	}
+
+	/// Number of bytes of the packet present in the file.
+	pub fn captured_length(&self) -> u32 {
+		self.captured
+	}
+
+	/// Length of the packet on the wire.
+	pub fn original_length(&self) -> u32 {
+		self.length
+	}
+
+	/// Check if the packet was truncated by the snapshot length.
+	pub fn is_truncated(&self) -> bool {
+		self.captured < self.length
+	}
+
+	/// The captured data.
+	pub fn data(&self) -> &'a [u8] {
+		self.data
+	}
+
+	/// Parse the data as an Ethernet frame.
+	pub fn ether(&self) -> Result<ether::Packet<&'a [u8]>> {
+		self.link_type.ether(self.data)
+	}
+
+	/// Parse the data as an IP packet, skipping the Ethernet header if any.
+	pub fn ip(&self) -> Result<ip::Packet<&'a [u8]>> {
+		self.link_type.ip(self.data)
+	}
+
+	/// Dissect the data into its layers.
+	pub fn dissect(&self) -> dissect::Stack<'a> {
+		dissect::dissect(self.link_type, self.data)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use crate::pcap;
+
+	#[test]
+	fn big_endian_nano() {
+		let raw = [
+			// header
+			0xa1, 0xb2, 0x3c, 0x4d, 0x00, 0x02, 0x00, 0x04,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x65,
+
+			// record
+			0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x01, 0x00,
+			0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x14,
+			0x45, 0x00, 0x00, 0x14,
+		];
+
+		let mut reader = pcap::Reader::new(&raw[..]).unwrap();
+		assert_eq!(reader.header().endianness, pcap::Endianness::Big);
+		assert_eq!(reader.header().precision, pcap::Precision::Nano);
+		assert_eq!(reader.header().snaplen, 4);
+		assert_eq!(reader.header().link_type, pcap::LinkType::Raw);
+
+		let record = reader.next().unwrap().unwrap();
+		assert_eq!(record.timestamp(), Duration::new(10, 256));
+		assert_eq!(record.captured_length(), 4);
+		assert_eq!(record.original_length(), 20);
+		assert!(record.is_truncated());
+		assert_eq!(record.data(), &[0x45, 0x00, 0x00, 0x14]);
+
+		assert!(reader.next().is_none());
+	}
+
+	#[test]
+	fn truncated() {
+		let raw = [
+			0xd4, 0xc3, 0xb2, 0xa1, 0x02, 0x00, 0x04, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0xff, 0xff, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x10, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
+			0x00, 0x00,
+		];
+
+		let mut reader = pcap::Reader::new(&raw[..]).unwrap();
+		assert!(reader.next().unwrap().is_err());
+		assert!(reader.next().is_none());
+
+		assert!(pcap::Reader::new(&raw[1 ..]).is_err());
+	}
+}
@@ -0,0 +1,174 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::io::Write;
+use std::time::Duration;
+use std::cmp;
+
+use crate::error::*;
+use crate::pcap::{Header, Precision, Record};
+
+/// pcap file writer.
+#[derive(Debug)]
+pub struct Writer<W: Write> {
+	output: W,
+	header: Header,
+}
+
+impl<W: Write> Writer<W> {
+	/// Create a new writer, writing the global header.
+	pub fn new(mut output: W, header: Header) -> Result<Writer<W>> {
+		header.write(&mut output)?;
+
+		Ok(Writer {
+			output,
+			header,
+		})
+	}
+
+	/// Global header of the file.
+	pub fn header(&self) -> &Header {
+		&self.header
+	}
+
+	/// Write a packet, truncating it to the snapshot length.
+	pub fn write(&mut self, timestamp: Duration, data: &[u8]) -> Result<()> {
+		if data.len() > u32::MAX as usize {
+			Err(Error::InvalidValue)?
+		}
+
+		self.write_truncated(timestamp, data, data.len() as u32)
+	}
+
+	/// Write a record from another file.
+	pub fn write_record(&mut self, record: &Record) -> Result<()> {
+		self.write_truncated(record.timestamp(), record.data(), record.original_length())
+	}
+
+	/// Write already truncated data, along with the original length of the
+	/// packet.
+	pub fn write_truncated(&mut self, timestamp: Duration, data: &[u8], length: u32) -> Result<()> {
+		if timestamp.as_secs() > u32::MAX as u64 {
+			Err(Error::InvalidValue)?
+		}
+
+		let captured = cmp::min(data.len(), self.header.snaplen as usize);
+		let fraction = match self.header.precision {
+			Precision::Micro => timestamp.subsec_micros(),
+			Precision::Nano  => timestamp.subsec_nanos(),
+		};
+
+		let endianness = self.header.endianness;
+		endianness.write_u32(&mut self.output, timestamp.as_secs() as u32)?;
+		endianness.write_u32(&mut self.output, fraction)?;
+		endianness.write_u32(&mut self.output, captured as u32)?;
+		endianness.write_u32(&mut self.output, cmp::max(length, captured as u32))?;
+		self.output.write_all(&data[.. captured])?;
+
+		Ok(())
+	}
+
+	/// Flush the underlying output.
+	pub fn flush(&mut self) -> Result<()> {
+		self.output.flush()?;
+
+		Ok(())
+	}
+
+	/// Get the underlying output back.
+	pub fn into_inner(self) -> W {
+		self.output
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use crate::builder::Builder;
+	use crate::ether;
+	use crate::ip;
+	use crate::pcap;
+
+	#[test]
+	fn round_trip() {
+		let frame = ether::Builder::default()
+			.ip().unwrap().v4().unwrap()
+				.source("66.102.1.108".parse().unwrap()).unwrap()
+				.destination("192.168.0.79".parse().unwrap()).unwrap()
+				.protocol(ip::Protocol::Tcp).unwrap()
+				.build().unwrap();
+
+		for &endianness in &[pcap::Endianness::Little, pcap::Endianness::Big] {
+			for &precision in &[pcap::Precision::Micro, pcap::Precision::Nano] {
+				let mut writer = pcap::Writer::new(Vec::new(), pcap::Header {
+					endianness,
+					precision,
+					snaplen:    24,
+					.. Default::default()
+				}).unwrap();
+
+				writer.write(Duration::new(1_500_000_000, 123_456_789), &frame).unwrap();
+				writer.write(Duration::new(1_500_000_001, 0), &frame[.. 20]).unwrap();
+
+				let output = writer.into_inner();
+				let mut reader = pcap::Reader::new(&output).unwrap();
+				assert_eq!(reader.header().endianness, endianness);
+				assert_eq!(reader.header().precision, precision);
+				assert_eq!(reader.header().link_type, pcap::LinkType::Ethernet);
+
+				let record = reader.next().unwrap().unwrap();
+				assert_eq!(record.timestamp().as_secs(), 1_500_000_000);
+				assert_eq!(record.timestamp().subsec_nanos(), match precision {
+					pcap::Precision::Micro => 123_456_000,
+					pcap::Precision::Nano  => 123_456_789,
+				});
+				assert_eq!(record.captured_length(), 24);
+				assert_eq!(record.original_length(), 34);
+				assert_eq!(record.data(), &frame[.. 24]);
+
+				let record = reader.next().unwrap().unwrap();
+				assert_eq!(record.original_length(), 20);
+				assert!(!record.is_truncated());
+
+				assert!(reader.next().is_none());
+			}
+		}
+	}
+
+	#[test]
+	fn layers() {
+		let frame = ether::Builder::default()
+			.ip().unwrap().v4().unwrap()
+				.source("66.102.1.108".parse().unwrap()).unwrap()
+				.destination("192.168.0.79".parse().unwrap()).unwrap()
+				.protocol(ip::Protocol::Tcp).unwrap()
+				.build().unwrap();
+
+		let mut writer = pcap::Writer::new(Vec::new(), Default::default()).unwrap();
+		writer.write(Duration::new(0, 0), &frame).unwrap();
+
+		let output = writer.into_inner();
+		let record = pcap::Reader::new(&output).unwrap().next().unwrap().unwrap();
+
+		assert_eq!(record.ether().unwrap().protocol(), ether::Protocol::Ipv4);
+		assert!(record.dissect().ip().is_some());
+
+		if let ip::Packet::V4(packet) = record.ip().unwrap() {
+			assert_eq!(packet.destination(), "192.168.0.79".parse::<std::net::Ipv4Addr>().unwrap());
+		}
+		else {
+			unreachable!();
+		}
+	}
+}
@@ -0,0 +1,66 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use crate::pcapng::{Section, Interface, Record, NameResolution, Custom};
+
+/// Section Header Block type.
+pub(in crate::pcapng) const SECTION: u32 = 0x0a0d_0d0a;
+
+/// Interface Description Block type.
+pub(in crate::pcapng) const INTERFACE: u32 = 0x0000_0001;
+
+/// Simple Packet Block type.
+pub(in crate::pcapng) const SIMPLE: u32 = 0x0000_0003;
+
+/// Name Resolution Block type.
+pub(in crate::pcapng) const NAME_RESOLUTION: u32 = 0x0000_0004;
+
+/// Enhanced Packet Block type.
+pub(in crate::pcapng) const ENHANCED: u32 = 0x0000_0006;
+
+/// Custom Block type, which can be copied.
+pub(in crate::pcapng) const CUSTOM: u32 = 0x0000_0bad;
+
+/// Custom Block type, which should not be copied.
+pub(in crate::pcapng) const CUSTOM_NO_COPY: u32 = 0x4000_0bad;
+
+/// Byte-order magic of the Section Header Block.
+pub(in crate::pcapng) const MAGIC: u32 = 0x1a2b_3c4d;
+
+/// Round a length up to the 32-bit boundary.
+pub(in crate::pcapng) fn aligned(length: usize) -> usize {
+	length.div_ceil(4) * 4
+}
+
+/// A pcapng block.
+#[derive(Clone, Debug)]
+pub enum Block<'a> {
+	/// Section Header Block.
+	Section(Section<'a>),
+
+	/// Interface Description Block.
+	Interface(Interface<'a>),
+
+	/// Enhanced or Simple Packet Block.
+	Packet(Record<'a>),
+
+	/// Name Resolution Block.
+	NameResolution(NameResolution<'a>),
+
+	/// Custom Block.
+	Custom(Custom<'a>),
+
+	/// Any other block, with its type and body.
+	Unknown(u32, &'a [u8]),
+}
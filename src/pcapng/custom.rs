@@ -0,0 +1,38 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+/// Custom Block.
+#[derive(Clone, Debug)]
+pub struct Custom<'a> {
+	pub(in crate::pcapng) copiable:   bool,
+	pub(in crate::pcapng) enterprise: u32,
+	pub(in crate::pcapng) data:       &'a [u8],
+}
+
+impl<'a> Custom<'a> {
+	/// Check if the block may be copied to other files.
+	pub fn is_copiable(&self) -> bool {
+		self.copiable
+	}
+
+	/// IANA Private Enterprise Number of the block owner.
+	pub fn enterprise(&self) -> u32 {
+		self.enterprise
+	}
+
+	/// Custom data, including any options.
+	pub fn data(&self) -> &'a [u8] {
+		self.data
+	}
+}
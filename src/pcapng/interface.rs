@@ -0,0 +1,157 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::time::Duration;
+
+use crate::error::*;
+use crate::pcap::{Endianness, LinkType};
+use crate::pcapng::option::{self, OptionIter};
+
+/// Interface Description Block.
+#[derive(Clone, Debug)]
+pub struct Interface<'a> {
+	endianness: Endianness,
+	buffer:     &'a [u8],
+}
+
+impl<'a> Interface<'a> {
+	pub(in crate::pcapng) fn new(endianness: Endianness, buffer: &'a [u8]) -> Interface<'a> {
+		Interface {
+			endianness,
+			buffer,
+		}
+	}
+
+	/// Link-layer header type of the interface.
+	pub fn link_type(&self) -> LinkType {
+		(self.endianness.read_u16(&self.buffer[0 ..]) as u32).into()
+	}
+
+	/// Maximum length of captured data per packet, 0 if unlimited.
+	pub fn snaplen(&self) -> u32 {
+		self.endianness.read_u32(&self.buffer[4 ..])
+	}
+
+	/// Interface options.
+	pub fn options(&self) -> OptionIter<'a> {
+		OptionIter::new(self.endianness, &self.buffer[8 ..])
+	}
+
+	/// Interface comments.
+	pub fn comments(&self) -> Vec<&'a str> {
+		self.options().comments()
+	}
+
+	/// Name of the interface.
+	pub fn name(&self) -> Option<&'a str> {
+		self.string(option::IF_NAME)
+	}
+
+	/// Description of the interface.
+	pub fn description(&self) -> Option<&'a str> {
+		self.string(option::IF_DESCRIPTION)
+	}
+
+	/// Resolution of the timestamps, as in `if_tsresol`.
+	///
+	/// When the most significant bit is set the remaining bits are a negative
+	/// power of two, otherwise a negative power of ten; the default is 6 for
+	/// microseconds.
+	pub fn resolution(&self) -> u8 {
+		match self.options().find(option::IF_TSRESOL) {
+			Some(value) if value.len() == 1 =>
+				value[0],
+
+			_ =>
+				6
+		}
+	}
+
+	/// Offset in seconds to add to the timestamps.
+	pub fn offset(&self) -> i64 {
+		match self.options().find(option::IF_TSOFFSET) {
+			Some(value) if value.len() == 8 =>
+				self.endianness.read_u64(value) as i64,
+
+			_ =>
+				0
+		}
+	}
+
+	fn string(&self, code: u16) -> Option<&'a str> {
+		self.options().filter_map(|o| o.ok()).find(|o| o.code() == code).and_then(|o| o.as_str().ok())
+	}
+}
+
+/// Number of timestamp units per second for the given resolution.
+pub(in crate::pcapng) fn units(resolution: u8) -> Result<u64> {
+	let units = if resolution & 0x80 != 0 {
+		1u64.checked_shl((resolution & 0x7f) as u32)
+	}
+	else {
+		10u64.checked_pow(resolution as u32)
+	};
+
+	units.ok_or(Error::InvalidValue)
+}
+
+/// Convert a raw timestamp to the time since the UNIX epoch.
+pub(in crate::pcapng) fn decode(value: u64, units: u64, offset: i64) -> Result<Duration> {
+	let seconds = (value / units) as i128 + offset as i128;
+	let nanos   = (value % units) as u128 * 1_000_000_000 / units as u128;
+
+	if seconds < 0 || seconds > u64::MAX as i128 {
+		Err(Error::InvalidValue)?
+	}
+
+	Ok(Duration::new(seconds as u64, nanos as u32))
+}
+
+/// Convert the time since the UNIX epoch to a raw timestamp.
+pub(in crate::pcapng) fn encode(timestamp: Duration, units: u64, offset: i64) -> Result<u64> {
+	let seconds = timestamp.as_secs() as i128 - offset as i128;
+
+	if seconds < 0 {
+		Err(Error::InvalidValue)?
+	}
+
+	let value = seconds as u128 * units as u128
+		+ timestamp.subsec_nanos() as u128 * units as u128 / 1_000_000_000;
+
+	if value > u64::MAX as u128 {
+		Err(Error::InvalidValue)?
+	}
+
+	Ok(value as u64)
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use crate::pcapng::interface::{units, decode, encode};
+
+	#[test]
+	fn resolution() {
+		assert_eq!(units(6).unwrap(), 1_000_000);
+		assert_eq!(units(9).unwrap(), 1_000_000_000);
+		assert_eq!(units(0x80 | 10).unwrap(), 1024);
+		assert!(units(20).is_err());
+
+		let timestamp = Duration::new(1_500_000_000, 500_000_000);
+		assert_eq!(encode(timestamp, 1024, 0).unwrap(), 1_500_000_000 * 1024 + 512);
+		assert_eq!(decode(1_500_000_000 * 1024 + 512, 1024, 0).unwrap(), timestamp);
+		assert_eq!(decode(encode(timestamp, 1000, 100).unwrap(), 1000, 100).unwrap(), timestamp);
+		assert!(encode(Duration::new(10, 0), 1000, 100).is_err());
+	}
+}
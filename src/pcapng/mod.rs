@@ -0,0 +1,42 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+pub use crate::pcap::{LinkType, Endianness};
+
+pub mod option;
+pub use self::option::Option;
+
+mod block;
+pub use self::block::Block;
+
+mod section;
+pub use self::section::Section;
+
+mod interface;
+pub use self::interface::Interface;
+
+mod record;
+pub use self::record::Record;
+
+mod names;
+pub use self::names::{NameResolution, Name, NameIter};
+
+mod custom;
+pub use self::custom::Custom;
+
+mod reader;
+pub use self::reader::Reader;
+
+mod writer;
+pub use self::writer::Writer;
@@ -0,0 +1,169 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str;
+
+use crate::error::*;
+use crate::pcap::Endianness;
+use crate::pcapng::block::aligned;
+use crate::pcapng::option::{self, OptionIter};
+
+/// End of the name resolution records.
+pub(in crate::pcapng) const END: u16 = 0;
+
+/// IPv4 name resolution record.
+pub(in crate::pcapng) const IPV4: u16 = 1;
+
+/// IPv6 name resolution record.
+pub(in crate::pcapng) const IPV6: u16 = 2;
+
+/// Name Resolution Block.
+#[derive(Clone, Debug)]
+pub struct NameResolution<'a> {
+	endianness: Endianness,
+	records:    &'a [u8],
+	options:    &'a [u8],
+}
+
+/// A name resolution record.
+#[derive(Clone, Debug)]
+pub struct Name<'a> {
+	address: IpAddr,
+	names:   &'a [u8],
+}
+
+/// Iterator over name resolution records.
+#[derive(Clone, Debug)]
+pub struct NameIter<'a> {
+	endianness: Endianness,
+	buffer:     &'a [u8],
+}
+
+impl<'a> NameResolution<'a> {
+	/// Split the block body into records and options.
+	pub(in crate::pcapng) fn new(endianness: Endianness, buffer: &'a [u8]) -> Result<NameResolution<'a>> {
+		let mut offset = 0;
+
+		while offset < buffer.len() {
+			if buffer.len() < offset + 4 {
+				Err(Error::SmallBuffer)?
+			}
+
+			let kind   = endianness.read_u16(&buffer[offset ..]);
+			let length = endianness.read_u16(&buffer[offset + 2 ..]) as usize;
+
+			if kind == END {
+				return Ok(NameResolution {
+					endianness,
+					records:    &buffer[.. offset],
+					options:    &buffer[offset + 4 ..],
+				});
+			}
+
+			if buffer.len() < offset + 4 + length {
+				Err(Error::SmallBuffer)?
+			}
+
+			if (kind == IPV4 && length < 4) || (kind == IPV6 && length < 16) {
+				Err(Error::InvalidPacket)?
+			}
+
+			offset += 4 + aligned(length);
+		}
+
+		Ok(NameResolution {
+			endianness,
+			records:    &buffer[.. offset.min(buffer.len())],
+			options:    &[],
+		})
+	}
+
+	/// Address to name records.
+	pub fn records(&self) -> NameIter<'a> {
+		NameIter {
+			endianness: self.endianness,
+			buffer:     self.records,
+		}
+	}
+
+	/// Block options.
+	pub fn options(&self) -> OptionIter<'a> {
+		OptionIter::new(self.endianness, self.options)
+	}
+
+	/// Block comments.
+	pub fn comments(&self) -> Vec<&'a str> {
+		self.options().comments()
+	}
+
+	/// Name of the DNS server used.
+	pub fn dns_name(&self) -> Option<&'a str> {
+		self.options().filter_map(|o| o.ok()).find(|o| o.code() == option::NS_DNSNAME).and_then(|o| o.as_str().ok())
+	}
+}
+
+impl<'a> Name<'a> {
+	/// The resolved address.
+	pub fn address(&self) -> IpAddr {
+		self.address
+	}
+
+	/// The names for the address.
+	pub fn names(&self) -> Vec<&'a str> {
+		self.names.split(|&b| b == 0)
+			.filter(|n| !n.is_empty())
+			.filter_map(|n| str::from_utf8(n).ok())
+			.collect()
+	}
+}
+
+impl<'a> Iterator for NameIter<'a> {
+	type Item = Name<'a>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while self.buffer.len() >= 4 {
+			let kind   = self.endianness.read_u16(&self.buffer[0 ..]);
+			let length = self.endianness.read_u16(&self.buffer[2 ..]) as usize;
+			let value  = &self.buffer[4 .. 4 + length];
+
+			self.buffer = &self.buffer[(4 + aligned(length)).min(self.buffer.len()) ..];
+
+			match kind {
+				IPV4 => {
+					let address = Ipv4Addr::new(value[0], value[1], value[2], value[3]);
+
+					return Some(Name {
+						address: address.into(),
+						names:   &value[4 ..],
+					});
+				}
+
+				IPV6 => {
+					let mut octets = [0u8; 16];
+					octets.copy_from_slice(&value[.. 16]);
+
+					return Some(Name {
+						address: Ipv6Addr::from(octets).into(),
+						names:   &value[16 ..],
+					});
+				}
+
+				_ => ()
+			}
+		}
+
+		None
+	}
+}
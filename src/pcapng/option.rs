@@ -0,0 +1,218 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::io::Write;
+use std::str;
+
+use crate::error::*;
+use crate::pcap::Endianness;
+use crate::pcapng::block::aligned;
+
+/// End of the options.
+pub const END: u16 = 0;
+
+/// Comment, valid in every block.
+pub const COMMENT: u16 = 1;
+
+/// Hardware used to create the section.
+pub const SHB_HARDWARE: u16 = 2;
+
+/// Operating system used to create the section.
+pub const SHB_OS: u16 = 3;
+
+/// Application used to create the section.
+pub const SHB_USERAPPL: u16 = 4;
+
+/// Name of the interface.
+pub const IF_NAME: u16 = 2;
+
+/// Description of the interface.
+pub const IF_DESCRIPTION: u16 = 3;
+
+/// Speed of the interface in bits per second.
+pub const IF_SPEED: u16 = 8;
+
+/// Resolution of the interface timestamps.
+pub const IF_TSRESOL: u16 = 9;
+
+/// Offset in seconds to add to the interface timestamps.
+pub const IF_TSOFFSET: u16 = 14;
+
+/// Link-layer flags of a packet.
+pub const EPB_FLAGS: u16 = 2;
+
+/// Number of packets dropped since the previous packet.
+pub const EPB_DROPCOUNT: u16 = 4;
+
+/// Name of the DNS server used for name resolution.
+pub const NS_DNSNAME: u16 = 2;
+
+/// A block option.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Option<'a> {
+	code:  u16,
+	value: &'a [u8],
+}
+
+impl<'a> Option<'a> {
+	/// Create an option from its code and raw value.
+	///
+	/// # Note
+	///
+	/// Numeric values must be in the byte order of the section.
+	pub fn new(code: u16, value: &'a [u8]) -> Option<'a> {
+		Option {
+			code,
+			value,
+		}
+	}
+
+	/// Create a comment option.
+	pub fn comment(value: &'a str) -> Option<'a> {
+		Option::new(COMMENT, value.as_bytes())
+	}
+
+	/// Option code.
+	pub fn code(&self) -> u16 {
+		self.code
+	}
+
+	/// Raw option value.
+	pub fn value(&self) -> &'a [u8] {
+		self.value
+	}
+
+	/// Option value as UTF-8 string, without any trailing NUL.
+	pub fn as_str(&self) -> Result<&'a str> {
+		let mut value = self.value;
+
+		while let Some((&0, rest)) = value.split_last() {
+			value = rest;
+		}
+
+		str::from_utf8(value).map_err(|_| Error::InvalidValue)
+	}
+}
+
+/// Iterator over block options.
+#[derive(Clone, Debug)]
+pub struct OptionIter<'a> {
+	endianness: Endianness,
+	buffer:     &'a [u8],
+}
+
+impl<'a> OptionIter<'a> {
+	pub(in crate::pcapng) fn new(endianness: Endianness, buffer: &'a [u8]) -> OptionIter<'a> {
+		OptionIter {
+			endianness,
+			buffer,
+		}
+	}
+
+	/// Find the value of the first option with the given code.
+	pub(in crate::pcapng) fn find(self, code: u16) -> ::std::option::Option<&'a [u8]> {
+		self.filter_map(|o| o.ok()).find(|o| o.code() == code).map(|o| o.value())
+	}
+
+	/// Collect all the comments.
+	pub(in crate::pcapng) fn comments(self) -> Vec<&'a str> {
+		self.filter_map(|o| o.ok())
+			.filter(|o| o.code() == COMMENT)
+			.filter_map(|o| o.as_str().ok())
+			.collect()
+	}
+}
+
+impl<'a> Iterator for OptionIter<'a> {
+	type Item = Result<Option<'a>>;
+
+	fn next(&mut self) -> ::std::option::Option<Self::Item> {
+		if self.buffer.is_empty() {
+			return None;
+		}
+
+		if self.buffer.len() < 4 {
+			self.buffer = &[];
+			return Some(Err(Error::SmallBuffer));
+		}
+
+		let code   = self.endianness.read_u16(&self.buffer[0 ..]);
+		let length = self.endianness.read_u16(&self.buffer[2 ..]) as usize;
+
+		if code == END {
+			self.buffer = &[];
+			return None;
+		}
+
+		if self.buffer.len() < 4 + length {
+			self.buffer = &[];
+			return Some(Err(Error::SmallBuffer));
+		}
+
+		let value   = &self.buffer[4 .. 4 + length];
+		self.buffer = &self.buffer[(4 + aligned(length)).min(self.buffer.len()) ..];
+
+		Some(Ok(Option::new(code, value)))
+	}
+}
+
+/// Write the options followed by the end marker, if there are any.
+pub(in crate::pcapng) fn write<W: Write>(mut output: W, endianness: Endianness, options: &[Option]) -> Result<()> {
+	if options.is_empty() {
+		return Ok(());
+	}
+
+	for option in options {
+		if option.value.len() > u16::MAX as usize {
+			Err(Error::InvalidValue)?
+		}
+
+		endianness.write_u16(&mut output, option.code)?;
+		endianness.write_u16(&mut output, option.value.len() as u16)?;
+		output.write_all(option.value)?;
+		output.write_all(&[0; 3][.. aligned(option.value.len()) - option.value.len()])?;
+	}
+
+	endianness.write_u16(&mut output, END)?;
+	endianness.write_u16(&mut output, 0)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use crate::pcap::Endianness;
+	use crate::pcapng::option::{self, OptionIter};
+	use crate::pcapng::Option;
+
+	#[test]
+	fn round_trip() {
+		let mut buffer = Vec::new();
+		option::write(&mut buffer, Endianness::Big, &[
+			Option::comment("hello"),
+			Option::new(option::IF_TSRESOL, &[9]),
+		]).unwrap();
+
+		assert_eq!(buffer.len(), 4 + 8 + 4 + 4 + 4);
+
+		let options = OptionIter::new(Endianness::Big, &buffer).collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(options.len(), 2);
+		assert_eq!(options[0].as_str().unwrap(), "hello");
+		assert_eq!(options[1].code(), option::IF_TSRESOL);
+		assert_eq!(options[1].value(), &[9]);
+
+		assert_eq!(OptionIter::new(Endianness::Big, &buffer).comments(), vec!["hello"]);
+		assert!(OptionIter::new(Endianness::Little, &buffer[.. 6]).next().unwrap().is_err());
+	}
+}
@@ -0,0 +1,308 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use byteorder::{ByteOrder, BigEndian, LittleEndian};
+
+use crate::error::*;
+use crate::pcap::{Endianness, LinkType};
+use crate::pcapng::block::{self, Block};
+use crate::pcapng::interface::{units, decode};
+use crate::pcapng::{Section, Interface, Record, NameResolution, Custom};
+
+/// Zero-copy pcapng file reader, iterating over the blocks.
+///
+/// Interfaces are tracked per section, so packets carry the link-layer type
+/// and the timestamps are decoded with the resolution of their interface.
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+	buffer:     &'a [u8],
+	offset:     usize,
+	endianness: Endianness,
+	interfaces: Vec<State>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct State {
+	link_type: LinkType,
+	snaplen:   u32,
+	units:     u64,
+	offset:    i64,
+}
+
+impl<'a> Reader<'a> {
+	/// Check the file starts with a section and prepare to read the blocks.
+	pub fn new(buffer: &'a [u8]) -> Result<Reader<'a>> {
+		if buffer.len() < 12 {
+			Err(Error::SmallBuffer)?
+		}
+
+		if BigEndian::read_u32(buffer) != block::SECTION {
+			Err(Error::InvalidPacket)?
+		}
+
+		Ok(Reader {
+			buffer,
+			offset:     0,
+			endianness: Endianness::Little,
+			interfaces: Vec::new(),
+		})
+	}
+
+	/// Byte order of the current section.
+	pub fn endianness(&self) -> Endianness {
+		self.endianness
+	}
+
+	/// Number of interfaces described in the current section.
+	pub fn interfaces(&self) -> usize {
+		self.interfaces.len()
+	}
+
+	fn block(&mut self) -> Result<Block<'a>> {
+		let buffer = &self.buffer[self.offset ..];
+
+		if buffer.len() < 12 {
+			Err(Error::SmallBuffer)?
+		}
+
+		let kind = self.endianness.read_u32(buffer);
+
+		if kind == block::SECTION {
+			self.endianness = match (BigEndian::read_u32(&buffer[8 ..]), LittleEndian::read_u32(&buffer[8 ..])) {
+				(block::MAGIC, _) => Endianness::Big,
+				(_, block::MAGIC) => Endianness::Little,
+				_                 => Err(Error::InvalidPacket)?,
+			};
+
+			self.interfaces.clear();
+		}
+
+		let length = self.endianness.read_u32(&buffer[4 ..]) as usize;
+
+		if length < 12 || !length.is_multiple_of(4) {
+			Err(Error::InvalidPacket)?
+		}
+
+		if buffer.len() < length {
+			Err(Error::SmallBuffer)?
+		}
+
+		if self.endianness.read_u32(&buffer[length - 4 ..]) as usize != length {
+			Err(Error::InvalidPacket)?
+		}
+
+		let body = &buffer[8 .. length - 4];
+		self.offset += length;
+
+		Ok(match kind {
+			block::SECTION => {
+				if body.len() < 16 {
+					Err(Error::SmallBuffer)?
+				}
+
+				Block::Section(Section::new(self.endianness, body))
+			}
+
+			block::INTERFACE => {
+				if body.len() < 8 {
+					Err(Error::SmallBuffer)?
+				}
+
+				let interface = Interface::new(self.endianness, body);
+
+				self.interfaces.push(State {
+					link_type: interface.link_type(),
+					snaplen:   interface.snaplen(),
+					units:     units(interface.resolution())?,
+					offset:    interface.offset(),
+				});
+
+				Block::Interface(interface)
+			}
+
+			block::ENHANCED => {
+				if body.len() < 20 {
+					Err(Error::SmallBuffer)?
+				}
+
+				let id       = self.endianness.read_u32(&body[0 ..]);
+				let high     = self.endianness.read_u32(&body[4 ..]) as u64;
+				let low      = self.endianness.read_u32(&body[8 ..]) as u64;
+				let captured = self.endianness.read_u32(&body[12 ..]);
+				let original = self.endianness.read_u32(&body[16 ..]);
+				let state    = *self.interfaces.get(id as usize).ok_or(Error::InvalidPacket)?;
+
+				if body.len() < 20 + captured as usize {
+					Err(Error::SmallBuffer)?
+				}
+
+				Block::Packet(Record {
+					endianness: self.endianness,
+					interface:  id,
+					link_type:  state.link_type,
+					timestamp:  Some(decode(high << 32 | low, state.units, state.offset)?),
+					captured,
+					length:     original,
+					data:       &body[20 .. 20 + captured as usize],
+					options:    &body[(20 + block::aligned(captured as usize)).min(body.len()) ..],
+				})
+			}
+
+			block::SIMPLE => {
+				if body.len() < 4 {
+					Err(Error::SmallBuffer)?
+				}
+
+				let original = self.endianness.read_u32(&body[0 ..]);
+				let state    = *self.interfaces.first().ok_or(Error::InvalidPacket)?;
+				let mut captured = (original as usize).min(body.len() - 4);
+
+				if state.snaplen != 0 {
+					captured = captured.min(state.snaplen as usize);
+				}
+
+				Block::Packet(Record {
+					endianness: self.endianness,
+					interface:  0,
+					link_type:  state.link_type,
+					timestamp:  None,
+					captured:   captured as u32,
+					length:     original,
+					data:       &body[4 .. 4 + captured],
+					options:    &[],
+				})
+			}
+
+			block::NAME_RESOLUTION =>
+				Block::NameResolution(NameResolution::new(self.endianness, body)?),
+
+			block::CUSTOM | block::CUSTOM_NO_COPY => {
+				if body.len() < 4 {
+					Err(Error::SmallBuffer)?
+				}
+
+				Block::Custom(Custom {
+					copiable:   kind == block::CUSTOM,
+					enterprise: self.endianness.read_u32(body),
+					data:       &body[4 ..],
+				})
+			}
+
+			kind =>
+				Block::Unknown(kind, body)
+		})
+	}
+}
+
+impl<'a> Iterator for Reader<'a> {
+	type Item = Result<Block<'a>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.offset >= self.buffer.len() {
+			return None;
+		}
+
+		let block = self.block();
+
+		if block.is_err() {
+			self.offset = self.buffer.len();
+		}
+
+		Some(block)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use crate::pcapng::{self, Block};
+
+	#[test]
+	fn little_endian() {
+		let raw = [
+			// section header
+			0x0a, 0x0d, 0x0d, 0x0a, 0x1c, 0x00, 0x00, 0x00,
+			0x4d, 0x3c, 0x2b, 0x1a, 0x01, 0x00, 0x00, 0x00,
+			0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+			0x1c, 0x00, 0x00, 0x00,
+
+			// interface description
+			0x01, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00,
+			0x65, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x14, 0x00, 0x00, 0x00,
+
+			// enhanced packet
+			0x06, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x40, 0x42, 0x0f, 0x00, 0x04, 0x00, 0x00, 0x00,
+			0x14, 0x00, 0x00, 0x00, 0x45, 0x00, 0x00, 0x14,
+			0x24, 0x00, 0x00, 0x00,
+		];
+
+		let mut reader = pcapng::Reader::new(&raw[..]).unwrap();
+
+		if let Block::Section(section) = reader.next().unwrap().unwrap() {
+			assert_eq!(section.endianness(), pcapng::Endianness::Little);
+			assert_eq!(section.version(), (1, 0));
+			assert_eq!(section.length(), None);
+		}
+		else {
+			unreachable!();
+		}
+
+		if let Block::Interface(interface) = reader.next().unwrap().unwrap() {
+			assert_eq!(interface.link_type(), pcapng::LinkType::Raw);
+			assert_eq!(interface.snaplen(), 0);
+			assert_eq!(interface.resolution(), 6);
+		}
+		else {
+			unreachable!();
+		}
+
+		if let Block::Packet(record) = reader.next().unwrap().unwrap() {
+			assert_eq!(record.interface(), 0);
+			assert_eq!(record.timestamp(), Some(Duration::new(1, 0)));
+			assert_eq!(record.captured_length(), 4);
+			assert_eq!(record.original_length(), 20);
+			assert_eq!(record.data(), &[0x45, 0x00, 0x00, 0x14]);
+		}
+		else {
+			unreachable!();
+		}
+
+		assert!(reader.next().is_none());
+		assert!(pcapng::Reader::new(&raw[28 ..]).is_err());
+	}
+
+	#[test]
+	fn unknown_interface() {
+		let raw = [
+			0x0a, 0x0d, 0x0d, 0x0a, 0x00, 0x00, 0x00, 0x1c,
+			0x1a, 0x2b, 0x3c, 0x4d, 0x00, 0x01, 0x00, 0x00,
+			0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+			0x00, 0x00, 0x00, 0x1c,
+
+			0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x20,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+		];
+
+		let mut reader = pcapng::Reader::new(&raw[..]).unwrap();
+		assert!(reader.next().unwrap().is_ok());
+		assert_eq!(reader.endianness(), pcapng::Endianness::Big);
+		assert!(reader.next().unwrap().is_err());
+		assert!(reader.next().is_none());
+	}
+}
@@ -0,0 +1,114 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::time::Duration;
+
+use crate::error::*;
+use crate::dissect;
+use crate::ether;
+use crate::ip;
+use crate::pcap::{Endianness, LinkType};
+use crate::pcapng::option::{self, OptionIter};
+
+/// A packet from an Enhanced or Simple Packet Block.
+#[derive(Clone, Debug)]
+pub struct Record<'a> {
+	pub(in crate::pcapng) endianness: Endianness,
+	pub(in crate::pcapng) interface:  u32,
+	pub(in crate::pcapng) link_type:  LinkType,
+	pub(in crate::pcapng) timestamp:  Option<Duration>,
+	pub(in crate::pcapng) captured:   u32,
+	pub(in crate::pcapng) length:     u32,
+	pub(in crate::pcapng) data:       &'a [u8],
+	pub(in crate::pcapng) options:    &'a [u8],
+}
+
+impl<'a> Record<'a> {
+	/// Identifier of the interface the packet was captured on.
+	pub fn interface(&self) -> u32 {
+		self.interface
+	}
+
+	/// Link-layer header type of the data.
+	pub fn link_type(&self) -> LinkType {
+		self.link_type
+	}
+
+	/// Time since the UNIX epoch the packet was captured at, missing for
+	/// Simple Packet Blocks.
+	pub fn timestamp(&self) -> Option<Duration> {
+		self.timestamp
+	}
+
+	/// Check if the packet came from a Simple Packet Block.
+	pub fn is_simple(&self) -> bool {
+		self.timestamp.is_none()
+	}
+
+	/// Number of bytes of the packet present in the file.
+	pub fn captured_length(&self) -> u32 {
+		self.captured
+	}
+
+	/// Length of the packet on the wire.
+	pub fn original_length(&self) -> u32 {
+		self.length
+	}
+
+	/// Check if the packet was truncated by the snapshot length.
+	pub fn is_truncated(&self) -> bool {
+		self.captured < self.length
+	}
+
+	/// The captured data.
+	pub fn data(&self) -> &'a [u8] {
+		self.data
+	}
+
+	/// Packet options.
+	pub fn options(&self) -> OptionIter<'a> {
+		OptionIter::new(self.endianness, self.options)
+	}
+
+	/// Packet comments.
+	pub fn comments(&self) -> Vec<&'a str> {
+		self.options().comments()
+	}
+
+	/// Link-layer flags of the packet.
+	pub fn flags(&self) -> Option<u32> {
+		match self.options().find(option::EPB_FLAGS) {
+			Some(value) if value.len() == 4 =>
+				Some(self.endianness.read_u32(value)),
+
+			_ =>
+				None
+		}
+	}
+
+	/// Parse the data as an Ethernet frame.
+	pub fn ether(&self) -> Result<ether::Packet<&'a [u8]>> {
+		self.link_type.ether(self.data)
+	}
+
+	/// Parse the data as an IP packet, skipping the Ethernet header if any.
+	pub fn ip(&self) -> Result<ip::Packet<&'a [u8]>> {
+		self.link_type.ip(self.data)
+	}
+
+	/// Dissect the data into its layers.
+	pub fn dissect(&self) -> dissect::Stack<'a> {
+		dissect::dissect(self.link_type, self.data)
+	}
+}
@@ -0,0 +1,79 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use crate::pcap::Endianness;
+use crate::pcapng::option::{self, OptionIter};
+
+/// Section Header Block.
+#[derive(Clone, Debug)]
+pub struct Section<'a> {
+	endianness: Endianness,
+	buffer:     &'a [u8],
+}
+
+impl<'a> Section<'a> {
+	pub(in crate::pcapng) fn new(endianness: Endianness, buffer: &'a [u8]) -> Section<'a> {
+		Section {
+			endianness,
+			buffer,
+		}
+	}
+
+	/// Byte order of the section.
+	pub fn endianness(&self) -> Endianness {
+		self.endianness
+	}
+
+	/// Major and minor version of the format.
+	pub fn version(&self) -> (u16, u16) {
+		(self.endianness.read_u16(&self.buffer[4 ..]), self.endianness.read_u16(&self.buffer[6 ..]))
+	}
+
+	/// Length of the section, if known.
+	pub fn length(&self) -> Option<u64> {
+		match self.endianness.read_u64(&self.buffer[8 ..]) {
+			u64::MAX => None,
+			length   => Some(length),
+		}
+	}
+
+	/// Section options.
+	pub fn options(&self) -> OptionIter<'a> {
+		OptionIter::new(self.endianness, &self.buffer[16 ..])
+	}
+
+	/// Section comments.
+	pub fn comments(&self) -> Vec<&'a str> {
+		self.options().comments()
+	}
+
+	/// Hardware used to create the section.
+	pub fn hardware(&self) -> Option<&'a str> {
+		self.string(option::SHB_HARDWARE)
+	}
+
+	/// Operating system used to create the section.
+	pub fn os(&self) -> Option<&'a str> {
+		self.string(option::SHB_OS)
+	}
+
+	/// Application used to create the section.
+	pub fn application(&self) -> Option<&'a str> {
+		self.string(option::SHB_USERAPPL)
+	}
+
+	fn string(&self, code: u16) -> Option<&'a str> {
+		self.options().filter_map(|o| o.ok()).find(|o| o.code() == code).and_then(|o| o.as_str().ok())
+	}
+}
@@ -0,0 +1,364 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::io::Write;
+use std::net::IpAddr;
+use std::time::Duration;
+use std::cmp;
+
+use crate::error::*;
+use crate::pcap::{Endianness, LinkType};
+use crate::pcapng::block::{self, aligned};
+use crate::pcapng::interface::{units, encode};
+use crate::pcapng::names;
+use crate::pcapng::option::{self, Option};
+
+/// pcapng file writer.
+#[derive(Debug)]
+pub struct Writer<W: Write> {
+	output:     W,
+	endianness: Endianness,
+	interfaces: Vec<State>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct State {
+	snaplen: u32,
+	units:   u64,
+	offset:  i64,
+}
+
+impl<W: Write> Writer<W> {
+	/// Create a new writer, writing the Section Header Block with the given
+	/// options.
+	pub fn new(output: W, endianness: Endianness, options: &[Option]) -> Result<Writer<W>> {
+		let mut writer = Writer {
+			output,
+			endianness,
+			interfaces: Vec::new(),
+		};
+
+		let mut body = Vec::new();
+		endianness.write_u32(&mut body, block::MAGIC)?;
+		endianness.write_u16(&mut body, 1)?;
+		endianness.write_u16(&mut body, 0)?;
+		endianness.write_u64(&mut body, u64::MAX)?;
+		option::write(&mut body, endianness, options)?;
+
+		writer.block(block::SECTION, &body)?;
+
+		Ok(writer)
+	}
+
+	/// Byte order of the section.
+	pub fn endianness(&self) -> Endianness {
+		self.endianness
+	}
+
+	/// Write an Interface Description Block, returning the identifier of the
+	/// interface.
+	///
+	/// Timestamps for the interface are written with the resolution in the
+	/// `if_tsresol` option, if any.
+	pub fn interface(&mut self, link_type: LinkType, snaplen: u32, options: &[Option]) -> Result<u32> {
+		let link_type: u32 = link_type.into();
+
+		if link_type > u16::MAX as u32 {
+			Err(Error::InvalidValue)?
+		}
+
+		let mut state = State {
+			snaplen,
+			units:   units(6)?,
+			offset:  0,
+		};
+
+		for option in options {
+			match (option.code(), option.value().len()) {
+				(option::IF_TSRESOL, 1) =>
+					state.units = units(option.value()[0])?,
+
+				(option::IF_TSOFFSET, 8) =>
+					state.offset = self.endianness.read_u64(option.value()) as i64,
+
+				_ => ()
+			}
+		}
+
+		let mut body = Vec::new();
+		self.endianness.write_u16(&mut body, link_type as u16)?;
+		self.endianness.write_u16(&mut body, 0)?;
+		self.endianness.write_u32(&mut body, snaplen)?;
+		option::write(&mut body, self.endianness, options)?;
+
+		self.block(block::INTERFACE, &body)?;
+		self.interfaces.push(state);
+
+		Ok(self.interfaces.len() as u32 - 1)
+	}
+
+	/// Write an Enhanced Packet Block, truncating the packet to the snapshot
+	/// length of the interface.
+	pub fn packet(&mut self, interface: u32, timestamp: Duration, data: &[u8], options: &[Option]) -> Result<()> {
+		if data.len() > u32::MAX as usize {
+			Err(Error::InvalidValue)?
+		}
+
+		self.packet_truncated(interface, timestamp, data, data.len() as u32, options)
+	}
+
+	/// Write an Enhanced Packet Block with already truncated data, along with
+	/// the original length of the packet.
+	pub fn packet_truncated(&mut self, interface: u32, timestamp: Duration, data: &[u8], length: u32, options: &[Option]) -> Result<()> {
+		let state     = *self.interfaces.get(interface as usize).ok_or(Error::InvalidValue)?;
+		let captured  = self.captured(state, data);
+		let timestamp = encode(timestamp, state.units, state.offset)?;
+
+		let mut body = Vec::new();
+		self.endianness.write_u32(&mut body, interface)?;
+		self.endianness.write_u32(&mut body, (timestamp >> 32) as u32)?;
+		self.endianness.write_u32(&mut body, timestamp as u32)?;
+		self.endianness.write_u32(&mut body, captured as u32)?;
+		self.endianness.write_u32(&mut body, cmp::max(length, captured as u32))?;
+		body.extend_from_slice(&data[.. captured]);
+		body.resize(20 + aligned(captured), 0);
+		option::write(&mut body, self.endianness, options)?;
+
+		self.block(block::ENHANCED, &body)
+	}
+
+	/// Write a Simple Packet Block, which always refers to the first
+	/// interface.
+	pub fn simple(&mut self, data: &[u8]) -> Result<()> {
+		if data.len() > u32::MAX as usize {
+			Err(Error::InvalidValue)?
+		}
+
+		let state    = *self.interfaces.first().ok_or(Error::InvalidValue)?;
+		let captured = self.captured(state, data);
+
+		let mut body = Vec::new();
+		self.endianness.write_u32(&mut body, data.len() as u32)?;
+		body.extend_from_slice(&data[.. captured]);
+		body.resize(4 + aligned(captured), 0);
+
+		self.block(block::SIMPLE, &body)
+	}
+
+	/// Write a Name Resolution Block with the given address to names records.
+	pub fn names(&mut self, records: &[(IpAddr, &[&str])], options: &[Option]) -> Result<()> {
+		let mut body = Vec::new();
+
+		for &(address, names) in records {
+			let mut value = match address {
+				IpAddr::V4(address) => address.octets().to_vec(),
+				IpAddr::V6(address) => address.octets().to_vec(),
+			};
+
+			for name in names {
+				value.extend_from_slice(name.as_bytes());
+				value.push(0);
+			}
+
+			if value.len() > u16::MAX as usize {
+				Err(Error::InvalidValue)?
+			}
+
+			let kind = if address.is_ipv4() { names::IPV4 } else { names::IPV6 };
+			self.endianness.write_u16(&mut body, kind)?;
+			self.endianness.write_u16(&mut body, value.len() as u16)?;
+			body.extend_from_slice(&value);
+			body.resize(body.len() + aligned(value.len()) - value.len(), 0);
+		}
+
+		self.endianness.write_u16(&mut body, names::END)?;
+		self.endianness.write_u16(&mut body, 0)?;
+		option::write(&mut body, self.endianness, options)?;
+
+		self.block(block::NAME_RESOLUTION, &body)
+	}
+
+	/// Write a Custom Block for the given Private Enterprise Number.
+	pub fn custom(&mut self, enterprise: u32, data: &[u8], copiable: bool) -> Result<()> {
+		let mut body = Vec::new();
+		self.endianness.write_u32(&mut body, enterprise)?;
+		body.extend_from_slice(data);
+		body.resize(4 + aligned(data.len()), 0);
+
+		self.block(if copiable { block::CUSTOM } else { block::CUSTOM_NO_COPY }, &body)
+	}
+
+	/// Flush the underlying output.
+	pub fn flush(&mut self) -> Result<()> {
+		self.output.flush()?;
+
+		Ok(())
+	}
+
+	/// Get the underlying output back.
+	pub fn into_inner(self) -> W {
+		self.output
+	}
+
+	fn captured(&self, state: State, data: &[u8]) -> usize {
+		if state.snaplen == 0 {
+			data.len()
+		}
+		else {
+			cmp::min(data.len(), state.snaplen as usize)
+		}
+	}
+
+	fn block(&mut self, kind: u32, body: &[u8]) -> Result<()> {
+		if body.len() > u32::MAX as usize - 12 {
+			Err(Error::InvalidValue)?
+		}
+
+		let length = 12 + body.len() as u32;
+
+		self.endianness.write_u32(&mut self.output, kind)?;
+		self.endianness.write_u32(&mut self.output, length)?;
+		self.output.write_all(body)?;
+		self.endianness.write_u32(&mut self.output, length)?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::IpAddr;
+	use std::time::Duration;
+	use crate::builder::Builder;
+	use crate::ether;
+	use crate::ip;
+	use crate::pcapng::{self, Block, Option, option};
+
+	#[test]
+	fn round_trip() {
+		let frame = ether::Builder::default()
+			.ip().unwrap().v4().unwrap()
+				.source("66.102.1.108".parse().unwrap()).unwrap()
+				.destination("192.168.0.79".parse().unwrap()).unwrap()
+				.protocol(ip::Protocol::Tcp).unwrap()
+				.build().unwrap();
+
+		let timestamp = Duration::new(1_500_000_000, 123_456_789);
+		let address   = "192.168.0.79".parse::<IpAddr>().unwrap();
+
+		for &endianness in &[pcapng::Endianness::Little, pcapng::Endianness::Big] {
+			let mut writer = pcapng::Writer::new(Vec::new(), endianness, &[
+				Option::comment("generated"),
+				Option::new(option::SHB_USERAPPL, b"test"),
+			]).unwrap();
+
+			let eth = writer.interface(pcapng::LinkType::Ethernet, 0, &[
+				Option::new(option::IF_NAME, b"eth0"),
+			]).unwrap();
+
+			let raw = writer.interface(pcapng::LinkType::Raw, 16, &[
+				Option::new(option::IF_TSRESOL, &[9]),
+			]).unwrap();
+
+			writer.names(&[(address, &["local", "host"])], &[]).unwrap();
+			writer.packet(eth, timestamp, &frame, &[Option::comment("first")]).unwrap();
+			writer.packet(raw, timestamp, &frame[14 ..], &[]).unwrap();
+			writer.simple(&frame).unwrap();
+			writer.custom(32473, &[1, 2, 3], false).unwrap();
+
+			let output = writer.into_inner();
+			let blocks = pcapng::Reader::new(&output).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+			assert_eq!(blocks.len(), 8);
+
+			match &blocks[0] {
+				Block::Section(section) => {
+					assert_eq!(section.endianness(), endianness);
+					assert_eq!(section.comments(), vec!["generated"]);
+					assert_eq!(section.application(), Some("test"));
+				}
+
+				_ => unreachable!()
+			}
+
+			match &blocks[1] {
+				Block::Interface(interface) =>
+					assert_eq!(interface.name(), Some("eth0")),
+
+				_ => unreachable!()
+			}
+
+			match &blocks[2] {
+				Block::Interface(interface) =>
+					assert_eq!(interface.resolution(), 9),
+
+				_ => unreachable!()
+			}
+
+			match &blocks[3] {
+				Block::NameResolution(names) => {
+					let records = names.records().collect::<Vec<_>>();
+					assert_eq!(records.len(), 1);
+					assert_eq!(records[0].address(), address);
+					assert_eq!(records[0].names(), vec!["local", "host"]);
+				}
+
+				_ => unreachable!()
+			}
+
+			match &blocks[4] {
+				Block::Packet(record) => {
+					assert_eq!(record.interface(), eth);
+					assert_eq!(record.timestamp(), Some(Duration::new(1_500_000_000, 123_456_000)));
+					assert_eq!(record.comments(), vec!["first"]);
+					assert_eq!(record.ether().unwrap().protocol(), ether::Protocol::Ipv4);
+					assert!(record.ip().is_ok());
+				}
+
+				_ => unreachable!()
+			}
+
+			match &blocks[5] {
+				Block::Packet(record) => {
+					assert_eq!(record.interface(), raw);
+					assert_eq!(record.link_type(), pcapng::LinkType::Raw);
+					assert_eq!(record.timestamp(), Some(timestamp));
+					assert_eq!(record.captured_length(), 16);
+					assert_eq!(record.original_length(), 20);
+					assert!(record.is_truncated());
+				}
+
+				_ => unreachable!()
+			}
+
+			match &blocks[6] {
+				Block::Packet(record) => {
+					assert!(record.is_simple());
+					assert_eq!(record.data(), &frame[..]);
+				}
+
+				_ => unreachable!()
+			}
+
+			match &blocks[7] {
+				Block::Custom(custom) => {
+					assert!(!custom.is_copiable());
+					assert_eq!(custom.enterprise(), 32473);
+					assert_eq!(&custom.data()[.. 3], &[1, 2, 3]);
+				}
+
+				_ => unreachable!()
+			}
+		}
+	}
+}
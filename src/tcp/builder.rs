@@ -22,6 +22,7 @@ use crate::packet::{AsPacket, AsPacketMut};
 use crate::ip;
 use crate::tcp::Packet;
 use crate::tcp::Flags;
+use crate::tcp::option::{Number, Value};
 use crate::tcp::checksum;
 
 /// TCP packet builder.
@@ -31,9 +32,8 @@ pub struct Builder<B: Buffer = buffer::Dynamic> {
 	finalizer: Finalization,
 
 	ip:      (usize, usize),
-	options: bool,
+	options: usize,
 	payload: bool,
-	payload_length: usize,
 }
 
 impl<B: Buffer> Build<B> for Builder<B> {
@@ -54,9 +54,8 @@ impl<B: Buffer> Build<B> for Builder<B> {
 			finalizer: Default::default(),
 
 			ip:      ip,
-			options: false,
+			options: 0,
 			payload: false,
-			payload_length: 0,
 		})
 	}
 
@@ -65,6 +64,7 @@ impl<B: Buffer> Build<B> for Builder<B> {
 	}
 
 	fn build(mut self) -> Result<B::Inner> {
+		self.pad()?;
 		self.prepare();
 
 		let mut buffer = self.buffer.into_inner();
@@ -134,27 +134,98 @@ impl<B: Buffer> Builder<B> {
 		Ok(self)
 	}
 
+	/// Maximum segment size option.
+	pub fn mss(self, value: u16) -> Result<Self> {
+		self.option(Value::MaximumSegmentSize(value))
+	}
+
+	/// Window scale option, the shift count cannot be over 14.
+	pub fn window_scale(self, value: u8) -> Result<Self> {
+		self.option(Value::WindowScale(value))
+	}
+
+	/// Selective acknowledgment permitted option.
+	pub fn sack_permitted(self) -> Result<Self> {
+		self.option(Value::SelectiveAcknowledgmentPermitted)
+	}
+
+	/// Selective acknowledgment option, with up to 4 blocks.
+	pub fn sack(self, blocks: &[(u32, u32)]) -> Result<Self> {
+		self.option(Value::SelectiveAcknowledgment(blocks.to_vec()))
+	}
+
+	/// Timestamp option.
+	pub fn timestamp(self, value: u32, echo: u32) -> Result<Self> {
+		self.option(Value::Timestamp { value, echo })
+	}
+
+	/// Append an option.
+	///
+	/// # Note
+	///
+	/// Options are padded with End of Option List to a 32-bit boundary, use
+	/// `Value::NoOperation` to align options within the list.
+	pub fn option(mut self, value: Value) -> Result<Self> {
+		self.append(&value.encode()?)?;
+		Ok(self)
+	}
+
+	/// Append a raw option with the given number and payload.
+	pub fn raw(mut self, number: Number, payload: &[u8]) -> Result<Self> {
+		let number: u8 = number.into();
+
+		self.append(&Value::Unknown(number, payload.to_vec()).encode()?)?;
+		Ok(self)
+	}
+
 	/// Payload for the packet.
 	pub fn payload<'a, T: IntoIterator<Item = &'a u8>>(mut self, value: T) -> Result<Self> {
 		if self.payload {
 			Err(Error::AlreadyDefined)?
 		}
 
+		self.pad()?;
 		self.payload = true;
 
 		for byte in value {
 			self.buffer.more(1)?;
-			self.payload_length += 1;
 			*self.buffer.data_mut().last_mut().unwrap() = *byte;
 		}
 
 		Ok(self)
 	}
 
+	fn append(&mut self, option: &[u8]) -> Result<()> {
+		if self.payload {
+			Err(Error::AlreadyDefined)?
+		}
+
+		if self.options + option.len() > 40 {
+			Err(Error::InvalidValue)?
+		}
+
+		let offset = 20 + self.options;
+		self.buffer.more(option.len())?;
+		self.buffer.data_mut()[offset .. offset + option.len()].copy_from_slice(option);
+		self.options += option.len();
+
+		Ok(())
+	}
+
+	fn pad(&mut self) -> Result<()> {
+		let padding = self.options.next_multiple_of(4) - self.options;
+
+		if padding > 0 {
+			self.append(&[0; 3][.. padding])?;
+		}
+
+		Ok(())
+	}
+
 	fn prepare(&mut self) {
 		let ip     = self.ip;
 		let length = self.buffer.length();
-		let payload_length = self.payload_length;
+		let header = 20 + self.options;
 
 		self.finalizer.add(move |out| {
 			// Split the buffer into IP and TCP parts.
@@ -162,10 +233,12 @@ impl<B: Buffer> Builder<B> {
 			let ip              = &mut before[ip.0 ..];
 			let tcp             = &mut after[.. length];
 
+			ip::payload_length(ip, length);
+
 			// Set the TCP data offset.
 			let flags  = tcp[12] & 0b1111;
 
-			let offset = ((length - payload_length) / 4) as u8;
+			let offset = (header / 4) as u8;
 			tcp[12] = offset << 4 | flags;
 
 			// Calculate the checksum by parsing back the IP packet and set it.
@@ -180,7 +253,7 @@ impl<B: Buffer> Builder<B> {
 
 #[cfg(test)]
 mod test {
-	use std::net::Ipv4Addr;
+	use std::net::{Ipv4Addr, Ipv6Addr};
 	use crate::builder::Builder;
 	use crate::packet::Packet;
 	use crate::ip;
@@ -216,4 +289,110 @@ mod test {
 		assert_eq!(tcp.flags(), tcp::flag::SYN);
 		assert!(tcp.is_valid(&ip::Packet::from(&ip)));
 	}
+
+	#[test]
+	fn v6() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(64).unwrap()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.tcp().unwrap()
+				.source(1337).unwrap()
+				.destination(9001).unwrap()
+				.flags(tcp::flag::SYN).unwrap()
+				.payload(b"lol").unwrap()
+				.build().unwrap();
+
+		let ip = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(ip.length(), 23);
+		assert_eq!(ip.next_header(), ip::Protocol::Tcp);
+		assert_eq!(ip.source(), "2001:db8::1".parse::<Ipv6Addr>().unwrap());
+
+		let tcp = tcp::Packet::new(ip.payload()).unwrap();
+		assert_eq!(tcp.source(), 1337);
+		assert_eq!(tcp.destination(), 9001);
+		assert_eq!(tcp.flags(), tcp::flag::SYN);
+		assert!(tcp.is_valid(&ip::Packet::from(&ip)));
+	}
+
+	#[test]
+	fn options() {
+		let packet = ip::v4::Builder::default()
+			.source("10.0.0.1".parse().unwrap()).unwrap()
+			.destination("10.0.0.2".parse().unwrap()).unwrap()
+			.tcp().unwrap()
+				.source(1337).unwrap()
+				.destination(80).unwrap()
+				.flags(tcp::flag::SYN).unwrap()
+				.mss(1460).unwrap()
+				.sack_permitted().unwrap()
+				.timestamp(0x592b2997, 0).unwrap()
+				.option(tcp::option::Value::NoOperation).unwrap()
+				.window_scale(7).unwrap()
+				.payload(b"lol").unwrap()
+				.build().unwrap();
+
+		let ip = ip::v4::Packet::new(packet).unwrap();
+		assert_eq!(ip.length(), 20 + 40 + 3);
+
+		let tcp = tcp::Packet::new(ip.payload()).unwrap();
+		assert_eq!(tcp.offset(), 10);
+		assert_eq!(tcp.payload(), b"lol");
+		assert!(tcp.is_valid(&ip::Packet::from(&ip)));
+
+		let values = tcp.options().map(|o| o.unwrap().value().unwrap()).collect::<Vec<_>>();
+		assert_eq!(values, vec![
+			tcp::option::Value::MaximumSegmentSize(1460),
+			tcp::option::Value::SelectiveAcknowledgmentPermitted,
+			tcp::option::Value::Timestamp { value: 0x592b2997, echo: 0 },
+			tcp::option::Value::NoOperation,
+			tcp::option::Value::WindowScale(7),
+		]);
+	}
+
+	#[test]
+	fn options_padding() {
+		let packet = ip::v4::Builder::default().tcp().unwrap()
+			.mss(1460).unwrap()
+			.window_scale(7).unwrap()
+			.raw(tcp::option::Number::Unknown(30), &[1, 2]).unwrap()
+			.build().unwrap();
+
+		assert_eq!(packet.len(), 20 + 20 + 12);
+		assert_eq!(&packet[40 ..], &[0x02, 0x04, 0x05, 0xb4, 0x03, 0x03, 0x07, 0x1e, 0x04, 0x01, 0x02, 0x00]);
+
+		let tcp = tcp::Packet::new(&packet[20 ..]).unwrap();
+		assert_eq!(tcp.offset(), 8);
+		assert_eq!(tcp.options().count(), 3);
+	}
+
+	#[test]
+	fn options_limit() {
+		let builder = ip::v4::Builder::default().tcp().unwrap()
+			.sack(&[(1, 2), (3, 4), (5, 6), (7, 8)]).unwrap()
+			.timestamp(1, 2);
+		assert!(builder.is_err());
+
+		let builder = ip::v4::Builder::default().tcp().unwrap()
+			.sack(&[(1, 2), (3, 4), (5, 6), (7, 8), (9, 10)]);
+		assert!(builder.is_err());
+
+		let builder = ip::v4::Builder::default().tcp().unwrap()
+			.window_scale(15);
+		assert!(builder.is_err());
+
+		let builder = ip::v4::Builder::default().tcp().unwrap()
+			.payload(b"lol").unwrap()
+			.mss(1460);
+		assert!(builder.is_err());
+
+		let packet = ip::v4::Builder::default().tcp().unwrap()
+			.sack(&[(1, 2), (3, 4), (5, 6), (7, 8)]).unwrap()
+			.option(tcp::option::Value::NoOperation).unwrap()
+			.option(tcp::option::Value::NoOperation).unwrap()
+			.option(tcp::option::Value::End).unwrap()
+			.build().unwrap();
+
+		assert_eq!(packet.len(), 20 + 60);
+	}
 }
@@ -0,0 +1,587 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::packet::Packet as P;
+use crate::ip;
+use crate::tcp::{self, Flags};
+
+/// RFC 793 connection state of an endpoint.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum State {
+	/// No connection.
+	Closed,
+
+	/// Connection request sent.
+	SynSent,
+
+	/// Connection request received and acknowledged.
+	SynReceived,
+
+	/// Connection open.
+	Established,
+
+	/// Connection closed locally, waiting for the acknowledgment.
+	FinWait1,
+
+	/// Connection closed locally and acknowledged.
+	FinWait2,
+
+	/// Connection closed remotely.
+	CloseWait,
+
+	/// Connection closed on both sides at once.
+	Closing,
+
+	/// Connection closed remotely then locally.
+	LastAck,
+
+	/// Waiting for stale segments to drain.
+	TimeWait,
+}
+
+/// Something that happened to a connection.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Event {
+	/// A SYN opened a new connection.
+	Opening,
+
+	/// The three-way handshake completed.
+	Established,
+
+	/// The endpoint sent its FIN first, the other can still send data.
+	HalfClosed(SocketAddr),
+
+	/// Both endpoints closed the connection.
+	Closed,
+
+	/// The endpoint reset the connection.
+	Reset(SocketAddr),
+
+	/// The segment does not fit the state of the connection, it is ignored.
+	Invalid,
+}
+
+/// Endpoint of a connection, as seen from the segments it sent.
+#[derive(Copy, Clone, Debug)]
+pub struct Endpoint {
+	address: SocketAddr,
+	state:   State,
+	isn:     Option<u32>,
+	fin:     Option<u32>,
+}
+
+impl Endpoint {
+	fn new(address: SocketAddr, state: State) -> Endpoint {
+		Endpoint {
+			address,
+			state,
+			isn:     None,
+			fin:     None,
+		}
+	}
+
+	/// Address and port of the endpoint.
+	pub fn address(&self) -> SocketAddr {
+		self.address
+	}
+
+	/// Current state of the endpoint.
+	pub fn state(&self) -> State {
+		self.state
+	}
+
+	/// Whether the acknowledgment number covers the FIN of the endpoint.
+	fn is_fin_acked(&self, ack: u32) -> bool {
+		self.fin.is_some_and(|fin| ack.wrapping_sub(fin.wrapping_add(1)) as i32 >= 0)
+	}
+}
+
+/// Both endpoints of a connection, the client being the one that sent the
+/// first SYN.
+#[derive(Copy, Clone, Debug)]
+pub struct Connection {
+	client: Endpoint,
+	server: Endpoint,
+	last:   Duration,
+}
+
+impl Connection {
+	/// The endpoint that opened the connection.
+	pub fn client(&self) -> &Endpoint {
+		&self.client
+	}
+
+	/// The endpoint that accepted the connection.
+	pub fn server(&self) -> &Endpoint {
+		&self.server
+	}
+
+	/// When the last segment was seen.
+	pub fn last(&self) -> Duration {
+		self.last
+	}
+
+	/// Whether both endpoints are done with the connection.
+	pub fn is_closed(&self) -> bool {
+		matches!(self.client.state, State::Closed | State::TimeWait) &&
+		matches!(self.server.state, State::Closed | State::TimeWait)
+	}
+
+	/// Whether a SYN from the source opens a new connection in place of this
+	/// one, because it is closing or never completed its handshake.
+	fn is_replaced_by(&self, source: SocketAddr, seq: u32) -> bool {
+		let retransmitted = self.client.address == source &&
+			self.client.state == State::SynSent && self.client.isn == Some(seq);
+
+		!retransmitted && (self.client.fin.is_some() || self.server.fin.is_some() ||
+			self.client.state != State::Established || self.server.state != State::Established)
+	}
+}
+
+/// Passive TCP connection tracker.
+///
+/// Connections are keyed by their 4-tuple, and are forgotten once reset,
+/// closed by both endpoints or idle for too long. A new SYN replaces a
+/// connection whose end or handshake was missed. Simultaneous open is not
+/// supported.
+///
+/// Timestamps are only used to expire idle connections, so any monotonic
+/// clock works, like the capture timestamps of the packets.
+#[derive(Debug)]
+pub struct Tracker {
+	connections: HashMap<(SocketAddr, SocketAddr), Connection>,
+	timeout:     Duration,
+	capacity:    usize,
+}
+
+impl Default for Tracker {
+	fn default() -> Self {
+		Tracker::new()
+	}
+}
+
+impl Tracker {
+	/// Create an empty tracker, dropping connections after 5 minutes without
+	/// segments and tracking up to 65536 of them.
+	pub fn new() -> Tracker {
+		Tracker {
+			connections: HashMap::new(),
+			timeout:     Duration::from_secs(5 * 60),
+			capacity:    65536,
+		}
+	}
+
+	/// Time without segments after which a connection is dropped.
+	pub fn timeout(mut self, value: Duration) -> Self {
+		self.timeout = value;
+		self
+	}
+
+	/// Maximum number of connections tracked, the least recently active ones
+	/// are dropped to make room.
+	pub fn capacity(mut self, value: usize) -> Self {
+		self.capacity = value;
+		self
+	}
+
+	/// Add a segment, returning what happened to the connection if anything.
+	pub fn push<BI: AsRef<[u8]>, BT: AsRef<[u8]>>(&mut self, timestamp: Duration, ip: &ip::Packet<BI>, tcp: &tcp::Packet<BT>) -> Option<Event> {
+		self.expire(timestamp);
+
+		let source      = SocketAddr::new(ip.source(), tcp.source());
+		let destination = SocketAddr::new(ip.destination(), tcp.destination());
+		let flags       = tcp.flags();
+		let syn         = flags & (Flags::SYN | Flags::ACK | Flags::RST) == Flags::SYN;
+
+		let mut key = if self.connections.contains_key(&(source, destination)) {
+			Some((source, destination))
+		}
+		else if self.connections.contains_key(&(destination, source)) {
+			Some((destination, source))
+		}
+		else {
+			None
+		};
+
+		if let Some(existing) = key {
+			if syn && self.connections[&existing].is_replaced_by(source, tcp.sequence()) {
+				self.connections.remove(&existing);
+				key = None;
+			}
+		}
+
+		let key = match key {
+			Some(key) =>
+				key,
+
+			None if syn => {
+				while !self.connections.is_empty() && self.connections.len() >= self.capacity {
+					self.evict();
+				}
+
+				let mut client = Endpoint::new(source, State::SynSent);
+				client.isn = Some(tcp.sequence());
+
+				self.connections.insert((source, destination), Connection {
+					client,
+					server: Endpoint::new(destination, State::SynReceived),
+					last:   timestamp,
+				});
+
+				return Some(Event::Opening);
+			}
+
+			None =>
+				return Some(Event::Invalid),
+		};
+
+		if flags.contains(Flags::RST) {
+			self.connections.remove(&key);
+			return Some(Event::Reset(source));
+		}
+
+		let connection = self.connections.get_mut(&key).unwrap();
+		connection.last = timestamp;
+
+		let event = if key.0 == source {
+			update(&mut connection.client, &mut connection.server, tcp)
+		}
+		else {
+			update(&mut connection.server, &mut connection.client, tcp)
+		};
+
+		if event == Some(Event::Closed) {
+			self.connections.remove(&key);
+		}
+
+		event
+	}
+
+	/// The connection between the two endpoints, in either direction.
+	pub fn connection(&self, a: SocketAddr, b: SocketAddr) -> Option<&Connection> {
+		self.connections.get(&(a, b)).or_else(|| self.connections.get(&(b, a)))
+	}
+
+	/// Number of connections being tracked.
+	pub fn len(&self) -> usize {
+		self.connections.len()
+	}
+
+	/// Whether no connection is being tracked.
+	pub fn is_empty(&self) -> bool {
+		self.connections.is_empty()
+	}
+
+	/// Stop tracking the connection between the two endpoints.
+	pub fn remove(&mut self, a: SocketAddr, b: SocketAddr) -> Option<Connection> {
+		self.connections.remove(&(a, b)).or_else(|| self.connections.remove(&(b, a)))
+	}
+
+	/// Drop the connections idle for longer than the timeout, returning how
+	/// many were dropped.
+	pub fn expire(&mut self, now: Duration) -> usize {
+		let timeout = self.timeout;
+		let before  = self.connections.len();
+
+		self.connections.retain(|_, connection| now.saturating_sub(connection.last) < timeout);
+
+		before - self.connections.len()
+	}
+
+	fn evict(&mut self) {
+		let oldest = self.connections.iter()
+			.min_by_key(|&(_, connection)| connection.last)
+			.map(|(&key, _)| key);
+
+		if let Some(key) = oldest {
+			self.connections.remove(&key);
+		}
+	}
+}
+
+/// Apply a segment going from the sender to the receiver, assuming the
+/// receiver gets it.
+fn update<B: AsRef<[u8]>>(sender: &mut Endpoint, receiver: &mut Endpoint, tcp: &tcp::Packet<B>) -> Option<Event> {
+	let flags  = tcp.flags();
+	let seq    = tcp.sequence();
+	let ack    = tcp.acknowledgment();
+	let length = tcp.payload().len() as u32;
+
+	let established = sender.state == State::Established && receiver.state == State::Established;
+
+	if flags.contains(Flags::SYN) {
+		// Retransmitted SYN.
+		if !flags.contains(Flags::ACK) {
+			return if sender.state == State::SynSent && sender.isn == Some(seq) { None } else { Some(Event::Invalid) };
+		}
+
+		let expected = receiver.isn.map(|isn| isn.wrapping_add(1)) == Some(ack);
+
+		match (sender.state, receiver.state) {
+			(State::SynReceived, State::SynSent) if expected => {
+				sender.isn     = Some(seq);
+				receiver.state = State::Established;
+
+				return None;
+			}
+
+			// Retransmitted SYN-ACK.
+			(State::SynReceived, State::Established) if expected && sender.isn == Some(seq) =>
+				return None,
+
+			_ =>
+				return Some(Event::Invalid),
+		}
+	}
+
+	if !flags.contains(Flags::ACK) || matches!(sender.state, State::SynSent | State::Closed) {
+		return Some(Event::Invalid);
+	}
+
+	if receiver.state == State::SynReceived {
+		if receiver.isn.map(|isn| isn.wrapping_add(1)) != Some(ack) {
+			return Some(Event::Invalid);
+		}
+
+		receiver.state = State::Established;
+	}
+
+	if receiver.is_fin_acked(ack) {
+		receiver.state = match receiver.state {
+			State::FinWait1 => State::FinWait2,
+			State::Closing  => State::TimeWait,
+			State::LastAck  => State::Closed,
+			state           => state,
+		};
+	}
+
+	let mut event = None;
+
+	if flags.contains(Flags::FIN) && sender.fin.is_none() {
+		sender.state = match sender.state {
+			State::Established | State::SynReceived => State::FinWait1,
+			State::CloseWait                        => State::LastAck,
+			_                                       => return Some(Event::Invalid),
+		};
+
+		receiver.state = match receiver.state {
+			State::Established => State::CloseWait,
+			State::FinWait1    => State::Closing,
+			State::FinWait2    => State::TimeWait,
+			state              => state,
+		};
+
+		sender.fin = Some(seq.wrapping_add(length));
+
+		if receiver.state == State::CloseWait {
+			event = Some(Event::HalfClosed(sender.address));
+		}
+	}
+
+	if !established && sender.state == State::Established && receiver.state == State::Established {
+		event = Some(Event::Established);
+	}
+
+	if matches!(sender.state, State::Closed | State::TimeWait) && matches!(receiver.state, State::Closed | State::TimeWait) {
+		event = Some(Event::Closed);
+	}
+
+	event
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::SocketAddr;
+	use std::time::Duration;
+	use crate::builder::Builder;
+	use crate::packet::Packet;
+	use crate::ip;
+	use crate::tcp;
+	use crate::tcp::Flags;
+	use crate::tcp::connection::{Event, State};
+
+	const CLIENT: bool = true;
+	const SERVER: bool = false;
+
+	fn push(tracker: &mut tcp::Tracker, client: bool, seq: u32, ack: u32, flags: Flags, payload: &[u8]) -> Option<Event> {
+		push_at(tracker, 0, client, seq, ack, flags, payload)
+	}
+
+	fn push_at(tracker: &mut tcp::Tracker, seconds: u64, client: bool, seq: u32, ack: u32, flags: Flags, payload: &[u8]) -> Option<Event> {
+		let (source, destination, sport, dport) = if client {
+			("10.0.0.1", "10.0.0.2", 1337, 80)
+		}
+		else {
+			("10.0.0.2", "10.0.0.1", 80, 1337)
+		};
+
+		let raw = ip::v4::Builder::default()
+			.source(source.parse().unwrap()).unwrap()
+			.destination(destination.parse().unwrap()).unwrap()
+			.tcp().unwrap()
+				.source(sport).unwrap()
+				.destination(dport).unwrap()
+				.sequence(seq).unwrap()
+				.acknowledgment(ack).unwrap()
+				.flags(flags).unwrap()
+				.payload(payload).unwrap()
+				.build().unwrap();
+
+		let ip  = ip::Packet::new(&raw[..]).unwrap();
+		let tcp = tcp::Packet::new(ip.payload()).unwrap();
+
+		tracker.push(Duration::from_secs(seconds), &ip, &tcp)
+	}
+
+	fn states(tracker: &tcp::Tracker) -> (State, State) {
+		let client: SocketAddr = "10.0.0.1:1337".parse().unwrap();
+		let server: SocketAddr = "10.0.0.2:80".parse().unwrap();
+		let connection = tracker.connection(server, client).unwrap();
+
+		assert_eq!(connection.client().address(), client);
+		(connection.client().state(), connection.server().state())
+	}
+
+	fn handshake(tracker: &mut tcp::Tracker) {
+		assert_eq!(push(tracker, CLIENT, 100, 0, Flags::SYN, b""), Some(Event::Opening));
+		assert_eq!(states(tracker), (State::SynSent, State::SynReceived));
+
+		assert_eq!(push(tracker, SERVER, 500, 101, Flags::SYN | Flags::ACK, b""), None);
+		assert_eq!(states(tracker), (State::Established, State::SynReceived));
+
+		assert_eq!(push(tracker, CLIENT, 101, 501, Flags::ACK, b""), Some(Event::Established));
+		assert_eq!(states(tracker), (State::Established, State::Established));
+	}
+
+	#[test]
+	fn close() {
+		let mut tracker = tcp::Tracker::new();
+		handshake(&mut tracker);
+
+		assert_eq!(push(&mut tracker, CLIENT, 101, 501, Flags::ACK | Flags::PSH, b"GET /"), None);
+		assert_eq!(push(&mut tracker, CLIENT, 106, 501, Flags::ACK | Flags::FIN, b""),
+			Some(Event::HalfClosed("10.0.0.1:1337".parse().unwrap())));
+		assert_eq!(states(&tracker), (State::FinWait1, State::CloseWait));
+
+		assert_eq!(push(&mut tracker, SERVER, 501, 107, Flags::ACK, b"200 OK"), None);
+		assert_eq!(states(&tracker), (State::FinWait2, State::CloseWait));
+
+		assert_eq!(push(&mut tracker, SERVER, 507, 107, Flags::ACK | Flags::FIN, b""), None);
+		assert_eq!(states(&tracker), (State::TimeWait, State::LastAck));
+
+		assert_eq!(push(&mut tracker, CLIENT, 107, 508, Flags::ACK, b""), Some(Event::Closed));
+		assert!(tracker.is_empty());
+	}
+
+	#[test]
+	fn simultaneous_close() {
+		let mut tracker = tcp::Tracker::new();
+		handshake(&mut tracker);
+
+		assert!(push(&mut tracker, CLIENT, 101, 501, Flags::ACK | Flags::FIN, b"").is_some());
+		assert_eq!(push(&mut tracker, SERVER, 501, 101, Flags::ACK | Flags::FIN, b""), None);
+		assert_eq!(states(&tracker), (State::Closing, State::LastAck));
+
+		assert_eq!(push(&mut tracker, SERVER, 502, 102, Flags::ACK, b""), None);
+		assert_eq!(states(&tracker), (State::TimeWait, State::LastAck));
+
+		assert_eq!(push(&mut tracker, CLIENT, 102, 502, Flags::ACK, b""), Some(Event::Closed));
+		assert!(tracker.is_empty());
+	}
+
+	#[test]
+	fn reset() {
+		let mut tracker = tcp::Tracker::new();
+		handshake(&mut tracker);
+
+		assert_eq!(push(&mut tracker, SERVER, 501, 101, Flags::RST, b""),
+			Some(Event::Reset("10.0.0.2:80".parse().unwrap())));
+		assert!(tracker.is_empty());
+	}
+
+	#[test]
+	fn invalid() {
+		let mut tracker = tcp::Tracker::new();
+
+		assert_eq!(push(&mut tracker, CLIENT, 101, 501, Flags::ACK, b""), Some(Event::Invalid));
+		assert!(tracker.is_empty());
+
+		assert_eq!(push(&mut tracker, CLIENT, 100, 0, Flags::SYN, b""), Some(Event::Opening));
+		assert_eq!(push(&mut tracker, CLIENT, 100, 0, Flags::SYN, b""), None);
+		assert_eq!(push(&mut tracker, SERVER, 500, 42, Flags::SYN | Flags::ACK, b""), Some(Event::Invalid));
+		assert_eq!(push(&mut tracker, CLIENT, 101, 501, Flags::ACK, b"early"), Some(Event::Invalid));
+		assert_eq!(states(&tracker), (State::SynSent, State::SynReceived));
+	}
+
+	#[test]
+	fn expire() {
+		let mut tracker = tcp::Tracker::new()
+			.timeout(Duration::from_secs(60));
+		handshake(&mut tracker);
+
+		assert_eq!(push_at(&mut tracker, 50, CLIENT, 101, 501, Flags::ACK, b"hello"), None);
+		assert_eq!(tracker.expire(Duration::from_secs(100)), 0);
+		assert_eq!(tracker.expire(Duration::from_secs(110)), 1);
+		assert!(tracker.is_empty());
+
+		let mut tracker = tcp::Tracker::new()
+			.capacity(1);
+		handshake(&mut tracker);
+
+		let raw = ip::v4::Builder::default()
+			.source("10.0.0.3".parse().unwrap()).unwrap()
+			.destination("10.0.0.2".parse().unwrap()).unwrap()
+			.tcp().unwrap()
+				.source(1337).unwrap()
+				.destination(80).unwrap()
+				.flags(Flags::SYN).unwrap()
+				.build().unwrap();
+
+		let ip  = ip::Packet::new(&raw[..]).unwrap();
+		let tcp = tcp::Packet::new(ip.payload()).unwrap();
+
+		assert_eq!(tracker.push(Duration::from_secs(1), &ip, &tcp), Some(Event::Opening));
+		assert_eq!(tracker.len(), 1);
+		assert!(tracker.connection("10.0.0.1:1337".parse().unwrap(), "10.0.0.2:80".parse().unwrap()).is_none());
+	}
+
+	#[test]
+	fn reuse() {
+		let mut tracker = tcp::Tracker::new();
+		handshake(&mut tracker);
+
+		// A SYN does not replace an established connection.
+		assert_eq!(push(&mut tracker, CLIENT, 1000, 0, Flags::SYN, b""), Some(Event::Invalid));
+		assert_eq!(states(&tracker), (State::Established, State::Established));
+
+		// The last FIN and ACK are missed.
+		assert!(push(&mut tracker, CLIENT, 101, 501, Flags::ACK | Flags::FIN, b"").is_some());
+		assert_eq!(push(&mut tracker, SERVER, 501, 102, Flags::ACK, b""), None);
+		assert_eq!(states(&tracker), (State::FinWait2, State::CloseWait));
+
+		assert_eq!(push(&mut tracker, CLIENT, 1000, 0, Flags::SYN, b""), Some(Event::Opening));
+		assert_eq!(states(&tracker), (State::SynSent, State::SynReceived));
+		assert_eq!(tracker.len(), 1);
+
+		// So does a handshake that never completed.
+		assert_eq!(push(&mut tracker, CLIENT, 1000, 0, Flags::SYN, b""), None);
+		assert_eq!(push(&mut tracker, CLIENT, 2000, 0, Flags::SYN, b""), Some(Event::Opening));
+		assert_eq!(push(&mut tracker, SERVER, 500, 2001, Flags::SYN | Flags::ACK, b""), None);
+		assert_eq!(push(&mut tracker, CLIENT, 2001, 501, Flags::ACK, b""), Some(Event::Established));
+	}
+}
@@ -26,6 +26,14 @@ pub use self::packet::Packet;
 mod builder;
 pub use self::builder::Builder;
 
+/// TCP connection tracking.
+pub mod connection;
+pub use self::connection::Tracker;
+
+/// TCP stream reassembly.
+pub mod stream;
+pub use self::stream::Stream;
+
 use crate::ip;
 use crate::ip::Protocol;
 
@@ -34,38 +42,18 @@ use crate::ip::Protocol;
 ///
 /// # Note
 ///
-/// Since the checksum for UDP packets includes a pseudo-header based on the
+/// Since the checksum for TCP packets includes a pseudo-header based on the
 /// enclosing IP packet, one has to be given.
 pub fn checksum<B: AsRef<[u8]>>(ip: &ip::Packet<B>, buffer: &[u8]) -> u16 {
     use std::io::Cursor;
-    use byteorder::{WriteBytesExt, ReadBytesExt, BigEndian};
+    use byteorder::{ReadBytesExt, BigEndian};
 
     let buffer_length = buffer.len();
-    let mut prefix = [0u8; 40];
-    match *ip {
-        ip::Packet::V4(ref packet) => {
-            prefix[0..4].copy_from_slice(&packet.source().octets());
-            prefix[4..8].copy_from_slice(&packet.destination().octets());
-
-            prefix[9] = Protocol::Tcp.into();
-            Cursor::new(&mut prefix[10..])
-                .write_u16::<BigEndian>(buffer.len() as u16).unwrap();
-        }
-
-        ip::Packet::V6(ref _packet) => {
-            unimplemented!();
-        }
-    };
+    let (prefix, size) = ip::pseudo_header(ip, Protocol::Tcp, buffer_length);
 
     let mut result = 0u32;
     let mut buffer = Cursor::new(buffer);
-    let mut prefix = match *ip {
-        ip::Packet::V4(_) =>
-            Cursor::new(&prefix[0..12]),
-
-        ip::Packet::V6(_) =>
-            Cursor::new(&prefix[0..40]),
-    };
+    let mut prefix = Cursor::new(&prefix[.. size]);
 
     while let Ok(value) = prefix.read_u16::<BigEndian>() {
         result += u32::from(value);
@@ -86,8 +74,9 @@ pub fn checksum<B: AsRef<[u8]>>(ip: &ip::Packet<B>, buffer: &[u8]) -> u16 {
     // NOTE(kuriko): read_u16 may remain 1 byte in the buffer.
     //   read_u16 is based on `read_exact` which will throw an eof error when buffer is not filled.
     if bytes_read != buffer_length {
-        // Deal with remaining 1 byte
-        let rem = buffer.read_u8().unwrap() as u32;
+        // Deal with remaining 1 byte, the failed `read_u16` may already have
+        // moved the cursor past it.
+        let rem = buffer.get_ref()[buffer_length - 1] as u32;
         result += rem << 8;
     }
 
@@ -144,4 +133,21 @@ mod test {
         let checksum = tcp_parse.checksum();
         assert_eq!(checksum_orig, checksum);
     }
+
+    #[test]
+    fn test_tcp_checksum_v6() {
+        let raw_tcp = [
+            0x60, 0x00, 0x00, 0x00, 0x00, 0x18, 0x06, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x9c, 0x40,
+            0x00, 0x50, 0x12, 0x34, 0x56, 0x78, 0x00, 0x00, 0x00, 0x00, 0x60, 0x02, 0xff, 0xff,
+            0x37, 0x89, 0x00, 0x00, 0x02, 0x04, 0x05, 0xa0,
+        ];
+
+        let ip = ip::v6::Packet::new(&raw_tcp[..]).unwrap();
+        let tcp = tcp::Packet::new(ip.payload()).unwrap();
+
+        assert_eq!(checksum(&ip::Packet::from(&ip), ip.payload()), 0x3789);
+        assert!(tcp.is_valid(&ip::Packet::from(&ip)));
+    }
 }
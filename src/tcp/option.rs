@@ -13,6 +13,7 @@
 //  0. You just DO WHAT THE FUCK YOU WANT TO.
 
 use std::fmt;
+use byteorder::{ByteOrder, BigEndian};
 
 use crate::error::*;
 use crate::size;
@@ -70,6 +71,40 @@ pub enum Number {
 	Unknown(u8),
 }
 
+/// Decoded TCP option value.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum Value {
+	/// End of the option list.
+	End,
+
+	/// Padding between options.
+	NoOperation,
+
+	/// Maximum segment size the sender can receive.
+	MaximumSegmentSize(u16),
+
+	/// Shift count for the window.
+	WindowScale(u8),
+
+	/// Selective acknowledgments can be used.
+	SelectiveAcknowledgmentPermitted,
+
+	/// Left and right edges of the received blocks.
+	SelectiveAcknowledgment(Vec<(u32, u32)>),
+
+	/// Timestamp value and echo reply.
+	Timestamp {
+		/// Timestamp value.
+		value: u32,
+
+		/// Timestamp echo reply.
+		echo: u32,
+	},
+
+	/// Any other option, with its number and payload.
+	Unknown(u8, Vec<u8>),
+}
+
 impl<B: AsRef<[u8]>> fmt::Debug for Option<B> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.debug_struct("tcp::Option")
@@ -80,6 +115,18 @@ impl<B: AsRef<[u8]>> fmt::Debug for Option<B> {
 	}
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Option<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.value() {
+			Ok(value) =>
+				fmt::Display::fmt(&value, f),
+
+			Err(_) =>
+				write!(f, "opt-{}:bad", Into::<u8>::into(self.number())),
+		}
+	}
+}
+
 impl<B: AsRef<[u8]>> Option<B> {
 	/// Parse a TCP option, checking the buffer contents are correct.
 	pub fn new(buffer: B) -> Result<Option<B>> {
@@ -91,6 +138,16 @@ impl<B: AsRef<[u8]>> Option<B> {
 			Err(Error::SmallBuffer)?
 		}
 
+		if option.number() != Number::End && option.number() != Number::NoOperation {
+			if option.buffer.as_ref().len() < 2 {
+				Err(Error::SmallBuffer)?
+			}
+
+			if option.length() < 2 {
+				Err(Error::InvalidPacket)?
+			}
+		}
+
 		if option.buffer.as_ref().len() < option.length() as usize {
 			Err(Error::SmallBuffer)?
 		}
@@ -169,6 +226,162 @@ impl<B: AsRef<[u8]>> Option<B> {
 				self.buffer.as_ref()[1]
 		}
 	}
+
+	/// Decode the option value, checking the length is the one expected for
+	/// the option.
+	pub fn value(&self) -> Result<Value> {
+		let payload = self.payload();
+
+		Ok(match (self.number(), payload.len()) {
+			(Number::End, _) =>
+				Value::End,
+
+			(Number::NoOperation, _) =>
+				Value::NoOperation,
+
+			(Number::MaximumSegmentSize, 2) =>
+				Value::MaximumSegmentSize(BigEndian::read_u16(payload)),
+
+			(Number::WindowScale, 1) =>
+				Value::WindowScale(payload[0]),
+
+			(Number::SelectiveAcknowledgmentPermitted, 0) =>
+				Value::SelectiveAcknowledgmentPermitted,
+
+			(Number::SelectiveAcknowledgment, length) if length > 0 && length <= 32 && length.is_multiple_of(8) =>
+				Value::SelectiveAcknowledgment(payload.chunks(8)
+					.map(|b| (BigEndian::read_u32(&b[0 ..]), BigEndian::read_u32(&b[4 ..])))
+					.collect()),
+
+			(Number::Timestamp, 8) =>
+				Value::Timestamp {
+					value: BigEndian::read_u32(&payload[0 ..]),
+					echo:  BigEndian::read_u32(&payload[4 ..]),
+				},
+
+			(Number::Unknown(number), _) =>
+				Value::Unknown(number, payload.to_vec()),
+
+			_ =>
+				Err(Error::InvalidPacket)?
+		})
+	}
+}
+
+impl Value {
+	/// Encode the option, checking the value fits.
+	pub(in crate::tcp) fn encode(&self) -> Result<Vec<u8>> {
+		let number: u8 = self.number().into();
+
+		let payload = match *self {
+			Value::End | Value::NoOperation =>
+				return Ok(vec![number]),
+
+			Value::MaximumSegmentSize(value) =>
+				value.to_be_bytes().to_vec(),
+
+			Value::WindowScale(value) => {
+				if value > 14 {
+					Err(Error::InvalidValue)?
+				}
+
+				vec![value]
+			}
+
+			Value::SelectiveAcknowledgmentPermitted =>
+				Vec::new(),
+
+			Value::SelectiveAcknowledgment(ref blocks) => {
+				if blocks.is_empty() || blocks.len() > 4 {
+					Err(Error::InvalidValue)?
+				}
+
+				let mut payload = Vec::with_capacity(blocks.len() * 8);
+
+				for &(left, right) in blocks {
+					payload.extend_from_slice(&left.to_be_bytes());
+					payload.extend_from_slice(&right.to_be_bytes());
+				}
+
+				payload
+			}
+
+			Value::Timestamp { value, echo } => {
+				let mut payload = value.to_be_bytes().to_vec();
+				payload.extend_from_slice(&echo.to_be_bytes());
+
+				payload
+			}
+
+			Value::Unknown(number, ref payload) => {
+				if number < 2 {
+					Err(Error::InvalidValue)?
+				}
+
+				payload.clone()
+			}
+		};
+
+		if payload.len() > 38 {
+			Err(Error::InvalidValue)?
+		}
+
+		let mut option = vec![number, payload.len() as u8 + 2];
+		option.extend_from_slice(&payload);
+
+		Ok(option)
+	}
+
+	/// Option number for the value.
+	pub fn number(&self) -> Number {
+		match *self {
+			Value::End                              => Number::End,
+			Value::NoOperation                      => Number::NoOperation,
+			Value::MaximumSegmentSize(..)           => Number::MaximumSegmentSize,
+			Value::WindowScale(..)                  => Number::WindowScale,
+			Value::SelectiveAcknowledgmentPermitted => Number::SelectiveAcknowledgmentPermitted,
+			Value::SelectiveAcknowledgment(..)      => Number::SelectiveAcknowledgment,
+			Value::Timestamp { .. }                 => Number::Timestamp,
+			Value::Unknown(number, ..)              => Number::Unknown(number),
+		}
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match *self {
+			Value::End =>
+				f.write_str("eol"),
+
+			Value::NoOperation =>
+				f.write_str("nop"),
+
+			Value::MaximumSegmentSize(value) =>
+				write!(f, "mss {}", value),
+
+			Value::WindowScale(value) =>
+				write!(f, "wscale {}", value),
+
+			Value::SelectiveAcknowledgmentPermitted =>
+				f.write_str("sackOK"),
+
+			Value::SelectiveAcknowledgment(ref blocks) => {
+				write!(f, "sack {}", blocks.len())?;
+
+				for &(left, right) in blocks {
+					write!(f, " {{{}:{}}}", left, right)?;
+				}
+
+				Ok(())
+			}
+
+			Value::Timestamp { value, echo } =>
+				write!(f, "TS val {} ecr {}", value, echo),
+
+			Value::Unknown(number, _) =>
+				write!(f, "opt-{}", number),
+		}
+	}
 }
 
 impl From<u8> for Number {
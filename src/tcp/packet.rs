@@ -58,6 +58,13 @@ impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
 	}
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} > {}: ", self.source(), self.destination())?;
+		self.summary(f)
+	}
+}
+
 impl<B: AsRef<[u8]>> Packet<B> {
 	/// Create a TCP packet without checking the buffer.
 	pub fn unchecked(buffer: B) -> Packet<B> {
@@ -195,6 +202,61 @@ impl<B: AsRef<[u8]>> Packet<B> {
 			buffer: &self.buffer.as_ref()[20 .. (self.offset() as usize * 4)],
 		}
 	}
+
+	/// Write the summary following the ports.
+	pub(crate) fn summary(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let flags = self.flags();
+
+		f.write_str("Flags [")?;
+
+		for &(flag, name) in &[(Flags::FIN, "F"), (Flags::SYN, "S"), (Flags::RST, "R"),
+			(Flags::PSH, "P"), (Flags::ACK, "."), (Flags::URG, "U"), (Flags::ECE, "E"),
+			(Flags::CWR, "W")]
+		{
+			if flags.contains(flag) {
+				f.write_str(name)?;
+			}
+		}
+
+		if flags.is_empty() {
+			f.write_str("none")?;
+		}
+
+		write!(f, "], seq {}", self.sequence())?;
+
+		if flags.contains(Flags::ACK) {
+			write!(f, ", ack {}", self.acknowledgment())?;
+		}
+
+		write!(f, ", win {}", self.window())?;
+
+		if flags.contains(Flags::URG) {
+			write!(f, ", urg {}", self.pointer())?;
+		}
+
+		let mut options = self.options().peekable();
+
+		if options.peek().is_some() {
+			f.write_str(", options [")?;
+
+			for (i, option) in options.enumerate() {
+				let option = match option {
+					Ok(option) => option,
+					Err(_)     => { f.write_str("bad opts")?; break; }
+				};
+
+				if i != 0 {
+					f.write_str(",")?;
+				}
+
+				write!(f, "{}", option)?;
+			}
+
+			f.write_str("]")?;
+		}
+
+		write!(f, ", length {}", self.payload().len())
+	}
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
@@ -373,8 +435,10 @@ impl<'a> Iterator for OptionIter<'a> {
 				Some(Ok(option))
 			}
 
-			Err(error) =>
+			Err(error) => {
+				self.buffer = &[];
 				Some(Err(error))
+			}
 		}
 	}
 }
@@ -399,6 +463,35 @@ mod test {
 		assert_eq!(tcp.destination(), 80);
 	}
 
+	#[test]
+	fn options() {
+		let raw = [0x45u8, 0x00, 0x00, 0x3c, 0xc8, 0xa5, 0x40, 0x00, 0x40, 0x06, 0x9f, 0xd5, 0xc0, 0xa8, 0x01, 0x89, 0x08, 0x08, 0x08, 0x08, 0x9b, 0x8a, 0x00, 0x50, 0xde, 0x67, 0xc7, 0x4a, 0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x3f, 0x5f, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0x59, 0x2b, 0x29, 0x97, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07];
+
+		let ip  = ip::v4::Packet::new(&raw[..]).unwrap();
+		let tcp = tcp::Packet::new(ip.payload()).unwrap();
+
+		let values = tcp.options().map(|o| o.unwrap().value().unwrap()).collect::<Vec<_>>();
+		assert_eq!(values, vec![
+			tcp::option::Value::MaximumSegmentSize(1460),
+			tcp::option::Value::SelectiveAcknowledgmentPermitted,
+			tcp::option::Value::Timestamp { value: 0x592b2997, echo: 0 },
+			tcp::option::Value::NoOperation,
+			tcp::option::Value::WindowScale(7),
+		]);
+
+		assert!(tcp::Option::new(&[0x02, 0x03, 0x05, 0xb4][..]).unwrap().value().is_err());
+		assert!(tcp::Option::new(&[0x05, 0x0a, 0, 0, 0, 1, 0, 0, 0, 2][..]).unwrap().value().is_ok());
+		assert!(tcp::Option::new(&[0x05, 0x06, 0, 0, 0, 1][..]).unwrap().value().is_err());
+		assert!(tcp::Option::new(&[0x08, 0x01][..]).is_err());
+		assert!(tcp::Option::new(&[0x08][..]).is_err());
+
+		let raw = [0x00u8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x60, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00];
+		let tcp = tcp::Packet::new(&raw[..]).unwrap();
+		let mut options = tcp.options();
+		assert!(options.next().unwrap().is_err());
+		assert!(options.next().is_none());
+	}
+
 	#[test]
 	fn mutable() {
 		let mut raw = [0x45u8, 0x00, 0x00, 0x3c, 0xc8, 0xa5, 0x40, 0x00, 0x40, 0x06, 0x9f, 0xd5, 0xc0, 0xa8, 0x01, 0x89, 0x08, 0x08, 0x08, 0x08, 0x9b, 0x8a, 0x00, 0x50, 0xde, 0x67, 0xc7, 0x4a, 0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x3f, 0x5f, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0x59, 0x2b, 0x29, 0x97, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07];
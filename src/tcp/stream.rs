@@ -0,0 +1,524 @@
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//                    Version 2, December 2004
+//
+// Copyleft (ↄ) meh. <meh@schizofreni.co> | http://meh.schizofreni.co
+//
+// Everyone is permitted to copy and distribute verbatim or modified
+// copies of this license document, and changing it is allowed as long
+// as the name is changed.
+//
+//            DO WHAT THE FUCK YOU WANT TO PUBLIC LICENSE
+//   TERMS AND CONDITIONS FOR COPYING, DISTRIBUTION AND MODIFICATION
+//
+//  0. You just DO WHAT THE FUCK YOU WANT TO.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::packet::Packet as P;
+use crate::ip;
+use crate::tcp::{self, Flags};
+
+/// One direction of a TCP connection, turning segments back into the byte
+/// stream.
+///
+/// Sequence numbers are compared relative to the next expected one, so they
+/// can wrap around. Data already received wins over retransmitted or
+/// overlapping data, and segments after a hole are kept until it is filled or
+/// skipped.
+#[derive(Debug)]
+pub struct Stream {
+	base:     Option<u32>,
+	position: u64,
+	fin:      Option<u64>,
+	reset:    bool,
+
+	data:    Vec<u8>,
+	pending: BTreeMap<u64, Vec<u8>>,
+	size:    usize,
+	limit:   usize,
+	skipped: u64,
+}
+
+impl Default for Stream {
+	fn default() -> Self {
+		Stream::new()
+	}
+}
+
+impl Stream {
+	/// Create a stream holding up to 1 MiB of out of order data.
+	pub fn new() -> Stream {
+		Stream {
+			base:     None,
+			position: 0,
+			fin:      None,
+			reset:    false,
+
+			data:    Vec::new(),
+			pending: BTreeMap::new(),
+			size:    0,
+			limit:   1024 * 1024,
+			skipped: 0,
+		}
+	}
+
+	/// Maximum number of out of order octets held, holes are skipped to stay
+	/// within it.
+	pub fn limit(mut self, value: usize) -> Self {
+		self.limit = value;
+		self
+	}
+
+	/// Add a segment, returning how many octets were appended to the data.
+	///
+	/// The first segment seen fixes the start of the stream, the initial
+	/// sequence number if it is a SYN.
+	pub fn push<B: AsRef<[u8]>>(&mut self, packet: &tcp::Packet<B>) -> usize {
+		let flags   = packet.flags();
+		let payload = packet.payload();
+		let mut seq = packet.sequence();
+
+		if flags.contains(Flags::RST) {
+			self.reset = true;
+			return 0;
+		}
+
+		if flags.contains(Flags::SYN) {
+			seq = seq.wrapping_add(1);
+		}
+
+		let base   = *self.base.get_or_insert(seq);
+		let next   = base.wrapping_add(self.position as u32);
+		let offset = self.position as i64 + i64::from(seq.wrapping_sub(next) as i32);
+
+		if offset + (payload.len() as i64) < 0 {
+			return 0;
+		}
+
+		if flags.contains(Flags::FIN) && self.fin.is_none() {
+			self.fin = Some((offset + payload.len() as i64) as u64);
+		}
+
+		let before = self.data.len();
+		self.insert(offset, payload);
+		self.deliver();
+
+		while self.size > self.limit {
+			self.skip();
+		}
+
+		self.data.len() - before
+	}
+
+	/// Contiguous data not consumed yet.
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Drop the first octets of the data.
+	pub fn consume(&mut self, length: usize) {
+		self.data.drain(.. length.min(self.data.len()));
+	}
+
+	/// Take all the data.
+	pub fn take(&mut self) -> Vec<u8> {
+		std::mem::take(&mut self.data)
+	}
+
+	/// Offset in the stream of the next octet expected.
+	pub fn position(&self) -> u64 {
+		self.position
+	}
+
+	/// Number of out of order octets waiting for a hole to be filled.
+	pub fn pending(&self) -> usize {
+		self.size
+	}
+
+	/// Number of octets lost in skipped holes.
+	pub fn skipped(&self) -> u64 {
+		self.skipped
+	}
+
+	/// Whether all the data up to the FIN has been received.
+	pub fn is_finished(&self) -> bool {
+		self.fin.is_some_and(|fin| self.position >= fin)
+	}
+
+	/// Whether the stream was reset.
+	pub fn is_reset(&self) -> bool {
+		self.reset
+	}
+
+	/// Give up on the first hole, appending the data after it, returning the
+	/// number of octets lost.
+	pub fn skip(&mut self) -> u64 {
+		let start = match self.pending.keys().next() {
+			Some(&start) => start,
+			None         => return 0,
+		};
+
+		let gap = start - self.position;
+		self.position  = start;
+		self.skipped  += gap;
+		self.deliver();
+
+		gap
+	}
+
+	/// Keep the parts of the segment that were not received yet.
+	fn insert(&mut self, offset: i64, payload: &[u8]) {
+		let mut payload = payload;
+		let mut start   = offset;
+
+		if start + payload.len() as i64 <= self.position as i64 {
+			return;
+		}
+
+		if start < self.position as i64 {
+			payload = &payload[(self.position as i64 - start) as usize ..];
+			start   = self.position as i64;
+		}
+
+		let start = start as u64;
+		let end   = start + payload.len() as u64;
+
+		let covered = self.pending.range(.. end)
+			.map(|(&from, data)| (from, from + data.len() as u64))
+			.filter(|&(_, to)| to > start)
+			.collect::<Vec<_>>();
+
+		let mut cursor = start;
+		for (from, to) in covered {
+			if from > cursor {
+				self.hold(cursor, &payload[(cursor - start) as usize .. (from - start) as usize]);
+			}
+
+			cursor = cursor.max(to);
+		}
+
+		if cursor < end {
+			self.hold(cursor, &payload[(cursor - start) as usize ..]);
+		}
+	}
+
+	fn hold(&mut self, offset: u64, data: &[u8]) {
+		self.size += data.len();
+		self.pending.insert(offset, data.to_vec());
+	}
+
+	/// Move the data following the position to the contiguous data.
+	fn deliver(&mut self) {
+		while let Some(entry) = self.pending.first_entry() {
+			if *entry.key() > self.position {
+				break;
+			}
+
+			let start = *entry.key();
+			let data  = entry.remove();
+			let skip  = ((self.position - start) as usize).min(data.len());
+
+			self.size     -= data.len();
+			self.position += (data.len() - skip) as u64;
+			self.data.extend_from_slice(&data[skip ..]);
+		}
+	}
+}
+
+/// TCP stream reassembler, tracking both directions of every connection and
+/// handing the data to a callback as soon as it is contiguous.
+///
+/// Timestamps are only used to expire idle connections, so any monotonic
+/// clock works, like the capture timestamps of the packets.
+pub struct Reassembler<F> {
+	streams:  HashMap<(SocketAddr, SocketAddr), (Duration, Stream)>,
+	timeout:  Duration,
+	capacity: usize,
+	limit:    usize,
+	callback: F,
+}
+
+impl<F: FnMut(SocketAddr, SocketAddr, &[u8])> Reassembler<F> {
+	/// Create a reassembler calling the callback with the source, the
+	/// destination and the new data of a direction.
+	///
+	/// Connections are dropped after 5 minutes without segments, and up to
+	/// 65536 directions holding up to 1 MiB of out of order data each are
+	/// tracked.
+	pub fn new(callback: F) -> Reassembler<F> {
+		Reassembler {
+			streams:  HashMap::new(),
+			timeout:  Duration::from_secs(5 * 60),
+			capacity: 65536,
+			limit:    1024 * 1024,
+			callback,
+		}
+	}
+
+	/// Time without segments after which a connection is dropped.
+	pub fn timeout(mut self, value: Duration) -> Self {
+		self.timeout = value;
+		self
+	}
+
+	/// Maximum number of directions tracked, the least recently active
+	/// connections are dropped to make room.
+	pub fn capacity(mut self, value: usize) -> Self {
+		self.capacity = value;
+		self
+	}
+
+	/// Maximum number of out of order octets held by each direction.
+	pub fn limit(mut self, value: usize) -> Self {
+		self.limit = value;
+		self
+	}
+
+	/// Add a segment, returning how many octets were handed to the callback.
+	///
+	/// Both directions are forgotten once the connection is reset or both of
+	/// them are finished.
+	pub fn push<BI: AsRef<[u8]>, BT: AsRef<[u8]>>(&mut self, timestamp: Duration, ip: &ip::Packet<BI>, tcp: &tcp::Packet<BT>) -> usize {
+		self.expire(timestamp);
+
+		let source      = SocketAddr::new(ip.source(), tcp.source());
+		let destination = SocketAddr::new(ip.destination(), tcp.destination());
+
+		if !self.streams.contains_key(&(source, destination)) {
+			while !self.streams.is_empty() && self.streams.len() >= self.capacity {
+				self.evict();
+			}
+		}
+
+		// Either direction keeps the connection alive.
+		if let Some(reverse) = self.streams.get_mut(&(destination, source)) {
+			reverse.0 = timestamp;
+		}
+
+		let limit  = self.limit;
+		let entry  = self.streams.entry((source, destination))
+			.or_insert_with(|| (timestamp, Stream::new().limit(limit)));
+		let stream = &mut entry.1;
+
+		entry.0 = timestamp;
+		stream.push(tcp);
+
+		let data = stream.take();
+		if !data.is_empty() {
+			(self.callback)(source, destination, &data);
+		}
+
+		let done = stream.is_reset() || (stream.is_finished() && self.streams
+			.get(&(destination, source))
+			.is_some_and(|(_, stream)| stream.is_finished()));
+
+		if done {
+			self.remove(source, destination);
+		}
+
+		data.len()
+	}
+
+	/// The stream going from the source to the destination.
+	pub fn stream(&self, source: SocketAddr, destination: SocketAddr) -> Option<&Stream> {
+		self.streams.get(&(source, destination)).map(|(_, stream)| stream)
+	}
+
+	/// Number of directions being tracked.
+	pub fn len(&self) -> usize {
+		self.streams.len()
+	}
+
+	/// Whether no direction is being tracked.
+	pub fn is_empty(&self) -> bool {
+		self.streams.is_empty()
+	}
+
+	/// Stop tracking both directions of a connection.
+	pub fn remove(&mut self, source: SocketAddr, destination: SocketAddr) {
+		self.streams.remove(&(source, destination));
+		self.streams.remove(&(destination, source));
+	}
+
+	/// Drop the connections idle for longer than the timeout, returning how
+	/// many directions were dropped.
+	pub fn expire(&mut self, now: Duration) -> usize {
+		let timeout = self.timeout;
+		let before  = self.streams.len();
+
+		self.streams.retain(|_, &mut (seen, _)| now.saturating_sub(seen) < timeout);
+
+		before - self.streams.len()
+	}
+
+	fn evict(&mut self) {
+		let oldest = self.streams.iter()
+			.min_by_key(|&(_, &(seen, _))| seen)
+			.map(|(&key, _)| key);
+
+		if let Some((source, destination)) = oldest {
+			self.remove(source, destination);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use crate::builder::Builder;
+	use crate::packet::Packet;
+	use crate::ip;
+	use crate::tcp;
+	use crate::tcp::Flags;
+
+	fn segment(source: &str, destination: &str, seq: u32, flags: Flags, payload: &[u8]) -> Vec<u8> {
+		ip::v4::Builder::default()
+			.source(source.parse().unwrap()).unwrap()
+			.destination(destination.parse().unwrap()).unwrap()
+			.tcp().unwrap()
+				.source(if source.ends_with(".1") { 1337 } else { 80 }).unwrap()
+				.destination(if destination.ends_with(".1") { 1337 } else { 80 }).unwrap()
+				.sequence(seq).unwrap()
+				.flags(flags).unwrap()
+				.payload(payload).unwrap()
+				.build().unwrap()
+	}
+
+	fn push(stream: &mut tcp::Stream, seq: u32, flags: Flags, payload: &[u8]) -> usize {
+		let raw = segment("10.0.0.1", "10.0.0.2", seq, flags, payload);
+		let ip  = ip::v4::Packet::new(&raw[..]).unwrap();
+
+		stream.push(&tcp::Packet::new(ip.payload()).unwrap())
+	}
+
+	#[test]
+	fn ordered() {
+		let mut stream = tcp::Stream::new();
+		let isn = 0xffff_fffa;
+
+		assert_eq!(push(&mut stream, isn, Flags::SYN, b""), 0);
+		assert_eq!(push(&mut stream, isn.wrapping_add(1), Flags::ACK, b"hello "), 6);
+		assert_eq!(push(&mut stream, isn.wrapping_add(7), Flags::ACK | Flags::FIN, b"world"), 5);
+
+		assert_eq!(stream.data(), b"hello world");
+		assert!(stream.is_finished());
+
+		stream.consume(6);
+		assert_eq!(stream.take(), b"world");
+		assert!(stream.data().is_empty());
+	}
+
+	#[test]
+	fn unordered() {
+		let mut stream = tcp::Stream::new();
+
+		assert_eq!(push(&mut stream, 99, Flags::SYN, b""), 0);
+		assert_eq!(push(&mut stream, 106, Flags::ACK, b"world"), 0);
+		assert_eq!(stream.pending(), 5);
+
+		// Overlapping data already received is kept.
+		assert_eq!(push(&mut stream, 103, Flags::ACK, b"XXXXX"), 0);
+		assert_eq!(push(&mut stream, 100, Flags::ACK, b"hel"), 11);
+		assert_eq!(stream.data(), b"helXXXworld");
+		assert_eq!(stream.pending(), 0);
+
+		assert_eq!(push(&mut stream, 100, Flags::ACK, b"hello "), 0);
+		assert_eq!(push(&mut stream, 98, Flags::ACK, b""), 0);
+		assert_eq!(stream.position(), 11);
+	}
+
+	#[test]
+	fn holes() {
+		let mut stream = tcp::Stream::new();
+
+		assert_eq!(push(&mut stream, 1000, Flags::ACK, b"abc"), 3);
+		assert_eq!(push(&mut stream, 1010, Flags::ACK, b"xyz"), 0);
+		assert_eq!(stream.skip(), 7);
+		assert_eq!(stream.data(), b"abcxyz");
+		assert_eq!(stream.skipped(), 7);
+
+		let mut stream = tcp::Stream::new().limit(4);
+		assert_eq!(push(&mut stream, 1000, Flags::ACK, b"abc"), 3);
+		assert_eq!(push(&mut stream, 1010, Flags::ACK, b"xyz"), 0);
+		assert_eq!(push(&mut stream, 1020, Flags::ACK, b"123"), 3);
+		assert_eq!(stream.data(), b"abcxyz");
+		assert_eq!(stream.pending(), 3);
+		assert_eq!(stream.skipped(), 7);
+	}
+
+	#[test]
+	fn reassembler() {
+		let mut received = Vec::new();
+
+		{
+			let mut reassembler = tcp::stream::Reassembler::new(|source, _, data: &[u8]| {
+				received.push((source.port(), data.to_vec()));
+			});
+
+			let segments = [
+				segment("10.0.0.1", "10.0.0.2", 10, Flags::SYN, b""),
+				segment("10.0.0.2", "10.0.0.1", 50, Flags::SYN | Flags::ACK, b""),
+				segment("10.0.0.1", "10.0.0.2", 15, Flags::ACK, b"d"),
+				segment("10.0.0.1", "10.0.0.2", 11, Flags::ACK, b"worl"),
+				segment("10.0.0.2", "10.0.0.1", 51, Flags::ACK | Flags::FIN, b"ok"),
+				segment("10.0.0.1", "10.0.0.2", 16, Flags::ACK | Flags::FIN, b""),
+			];
+
+			for raw in &segments {
+				let ip  = ip::Packet::new(&raw[..]).unwrap();
+				let tcp = tcp::Packet::new(ip.payload()).unwrap();
+
+				reassembler.push(Duration::from_secs(0), &ip, &tcp);
+			}
+
+			assert!(reassembler.is_empty());
+		}
+
+		assert_eq!(received, vec![(1337, b"world".to_vec()), (80, b"ok".to_vec())]);
+	}
+
+	#[test]
+	fn expire() {
+		let mut received = 0;
+		let mut reassembler = tcp::stream::Reassembler::new(|_, _, data: &[u8]| received += data.len())
+			.timeout(Duration::from_secs(60))
+			.capacity(4)
+			.limit(4);
+
+		let segments = [
+			segment("10.0.0.1", "10.0.0.2", 10, Flags::SYN, b""),
+			segment("10.0.0.2", "10.0.0.1", 50, Flags::SYN | Flags::ACK, b""),
+			segment("10.0.0.1", "10.0.0.2", 20, Flags::ACK, b"0123456789"),
+			segment("10.0.0.3", "10.0.0.2", 10, Flags::SYN, b""),
+			segment("10.0.0.5", "10.0.0.2", 10, Flags::SYN, b""),
+			segment("10.0.0.7", "10.0.0.2", 10, Flags::SYN, b""),
+		];
+
+		let mut push = |seconds, raw: &[u8]| {
+			let ip  = ip::Packet::new(raw).unwrap();
+			let tcp = tcp::Packet::new(ip.payload()).unwrap();
+
+			reassembler.push(Duration::from_secs(seconds), &ip, &tcp);
+			reassembler.len()
+		};
+
+		assert_eq!(push(0, &segments[0]), 1);
+		assert_eq!(push(10, &segments[1]), 2);
+
+		// The hole is skipped to stay within the limit.
+		assert_eq!(push(20, &segments[2]), 2);
+
+		// The first connection is the least recently active.
+		assert_eq!(push(30, &segments[3]), 3);
+		assert_eq!(push(40, &segments[4]), 4);
+		assert_eq!(push(50, &segments[5]), 3);
+
+		// Idle connections are dropped.
+		assert_eq!(push(95, &segments[5]), 2);
+		assert_eq!(push(200, &segments[5]), 1);
+
+		assert_eq!(received, 10);
+	}
+}
@@ -118,6 +118,8 @@ impl<B: Buffer> Builder<B> {
 			let ip              = &mut before[ip.0 ..];
 			let udp             = &mut after[.. length];
 
+			ip::payload_length(ip, length);
+
 			Cursor::new(&mut udp[4 ..])
 				.write_u16::<BigEndian>(length as u16)?;
 
@@ -141,7 +143,7 @@ impl<B: Buffer> Builder<B> {
 
 #[cfg(test)]
 mod test {
-	use std::net::Ipv4Addr;
+	use std::net::{Ipv4Addr, Ipv6Addr};
 	use crate::builder::Builder;
 	use crate::packet::Packet;
 	use crate::ip;
@@ -174,4 +176,53 @@ mod test {
 		assert_eq!(udp.destination(), 9001);
 		assert!(udp.is_valid(&ip::Packet::from(&ip)));
 	}
+
+	#[test]
+	fn v6() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(64).unwrap()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::2".parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.payload(b"hello").unwrap()
+				.build().unwrap();
+
+		let ip = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(ip.length(), 13);
+		assert_eq!(ip.next_header(), ip::Protocol::Udp);
+		assert_eq!(ip.destination(), "2001:db8::2".parse::<Ipv6Addr>().unwrap());
+
+		let udp = udp::Packet::new(ip.payload()).unwrap();
+		assert_eq!(udp.source(), 1337);
+		assert_eq!(udp.destination(), 53);
+		assert_eq!(udp.checksum(), 0x5b1f);
+		assert!(udp.is_valid(&ip::Packet::from(&ip)));
+	}
+
+	#[test]
+	fn routing() {
+		let packet = ip::v6::Builder::default()
+			.hop_limit(64).unwrap()
+			.source("2001:db8::1".parse().unwrap()).unwrap()
+			.destination("2001:db8::3".parse().unwrap()).unwrap()
+			.routing(ip::v6::routing::Kind::SegmentRouting).unwrap()
+				.segments_left(1).unwrap()
+				.address("2001:db8::2".parse().unwrap()).unwrap()
+				.address("2001:db8::3".parse().unwrap()).unwrap()
+			.udp().unwrap()
+				.source(1337).unwrap()
+				.destination(53).unwrap()
+				.payload(b"hello").unwrap()
+				.build().unwrap();
+
+		let ip = ip::v6::Packet::new(packet).unwrap();
+		assert_eq!(ip.length(), 40 + 13);
+
+		// Same checksum as the packet sent straight to the final destination.
+		let udp = udp::Packet::new(&ip.payload()[40 ..]).unwrap();
+		assert_eq!(udp.checksum(), 0x5b1f);
+		assert!(udp.is_valid(&ip::Packet::from(&ip)));
+	}
 }
@@ -29,33 +29,13 @@ use crate::ip::Protocol;
 /// enclosing IP packet, one has to be given.
 pub fn checksum<B: AsRef<[u8]>>(ip: &ip::Packet<B>, buffer: &[u8]) -> u16 {
 	use std::io::Cursor;
-	use byteorder::{WriteBytesExt, ReadBytesExt, BigEndian};
+	use byteorder::{ReadBytesExt, BigEndian};
 
-	let mut prefix = [0u8; 40];
-	match *ip {
-		ip::Packet::V4(ref packet) => {
-			prefix[0 .. 4].copy_from_slice(&packet.source().octets());
-			prefix[4 .. 8].copy_from_slice(&packet.destination().octets());
-
-			prefix[9] = Protocol::Udp.into();
-			Cursor::new(&mut prefix[10 ..])
-				.write_u16::<BigEndian>(buffer.len() as u16).unwrap();
-		}
-
-		ip::Packet::V6(ref _packet) => {
-			unimplemented!();
-		}
-	};
+	let (prefix, size) = ip::pseudo_header(ip, Protocol::Udp, buffer.len());
 
 	let mut result = 0x0000u32;
 	let mut buffer = Cursor::new(buffer);
-	let mut prefix = match *ip {
-		ip::Packet::V4(_) =>
-			Cursor::new(&prefix[0 .. 12]),
-
-		ip::Packet::V6(_) =>
-			Cursor::new(&prefix[0 .. 40]),
-	};
+	let mut prefix = Cursor::new(&prefix[.. size]);
 
 	while let Ok(value) = prefix.read_u16::<BigEndian>() {
 		result += u32::from(value);
@@ -78,9 +58,10 @@ pub fn checksum<B: AsRef<[u8]>>(ip: &ip::Packet<B>, buffer: &[u8]) -> u16 {
 		}
 	}
 
-	if let Ok(value) = buffer.read_u8() {
-		// if we have a trailing byte, make a padded 16-bit value.
-		let value = (value as u16) << 8;
+	if buffer.get_ref().len() % 2 == 1 {
+		// if we have a trailing byte, make a padded 16-bit value, the failed
+		// `read_u16` may already have moved the cursor past it.
+		let value = (*buffer.get_ref().last().unwrap() as u16) << 8;
 
 		result += u32::from(value);
 
@@ -89,7 +70,12 @@ pub fn checksum<B: AsRef<[u8]>>(ip: &ip::Packet<B>, buffer: &[u8]) -> u16 {
 		}
 	}
 
-	!result as u16
+	// A computed checksum of zero is transmitted as all ones, since zero means
+	// no checksum for IPv4 and is invalid for IPv6.
+	match !result as u16 {
+		0 => 0xffff,
+		v => v,
+	}
 }
 
 #[cfg(test)]
@@ -116,4 +102,45 @@ mod tests {
 
 		assert_eq!(checksum(&ip::Packet::V4(ip), ip.payload()), udp.checksum());
 	}
+
+	#[test]
+	fn test_checksum_v6() {
+		let raw = [
+			// IPv6
+			0x60, 0x00, 0x00, 0x00, 0x00, 0x0d, 0x11, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+			// UDP
+			0x05, 0x39, 0x00, 0x35, 0x00, 0x0d, 0x5b, 0x1f,
+			// data
+			0x68, 0x65, 0x6c, 0x6c, 0x6f,
+		];
+
+		let ip  = ip::v6::Packet::new(&raw[..]).unwrap();
+		let udp = udp::Packet::new(ip.payload()).unwrap();
+
+		assert_eq!(checksum(&ip::Packet::from(&ip), ip.payload()), udp.checksum());
+		assert!(udp.is_valid(&ip::Packet::from(&ip)));
+	}
+
+	#[test]
+	fn test_checksum_v6_extension() {
+		let raw = [
+			// IPv6
+			0x60, 0x00, 0x00, 0x00, 0x00, 0x15, 0x3c, 0x40, 0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x20, 0x01, 0x0d, 0xb8,
+			0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+			// Destination Options
+			0x11, 0x00, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00,
+			// UDP
+			0x05, 0x39, 0x00, 0x35, 0x00, 0x0d, 0x5b, 0x1f,
+			// data
+			0x68, 0x65, 0x6c, 0x6c, 0x6f,
+		];
+
+		let ip  = ip::v6::Packet::new(&raw[..]).unwrap();
+		let udp = udp::Packet::new(&ip.payload()[8 ..]).unwrap();
+
+		assert!(udp.is_valid(&ip::Packet::from(&ip)));
+	}
 }
@@ -51,6 +51,13 @@ impl<B: AsRef<[u8]>> fmt::Debug for Packet<B> {
 	}
 }
 
+impl<B: AsRef<[u8]>> fmt::Display for Packet<B> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} > {}: ", self.source(), self.destination())?;
+		self.summary(f)
+	}
+}
+
 impl<B: AsRef<[u8]>> Packet<B> {
 	/// Create a UDP packet without checking.
 	pub fn unchecked(buffer: B) -> Packet<B> {
@@ -188,6 +195,11 @@ impl<B: AsRef<[u8]>> Packet<B> {
 	pub fn is_valid<I: AsRef<[u8]>>(&self, ip: &ip::Packet<I>) -> bool {
 		checksum(ip, self.buffer.as_ref()) == self.checksum()
 	}
+
+	/// Write the summary following the ports.
+	pub(crate) fn summary(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "UDP, length {}", self.length().saturating_sub(8))
+	}
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> Packet<B> {
@@ -286,6 +298,7 @@ mod test {
 		assert!(udp.is_valid(&ip::Packet::from(&ip)));
 
 		assert_eq!(udp.destination(), 53);
+		assert_eq!(udp.to_string(), "47663 > 53: UDP, length 38");
 	}
 
 	#[test]